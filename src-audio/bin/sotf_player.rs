@@ -372,8 +372,15 @@ fn parse_filter_type(type_str: &str) -> Result<BiquadFilterType, String> {
         "HP" | "HIGHPASS" => Ok(BiquadFilterType::Highpass),
         "NO" | "NOTCH" => Ok(BiquadFilterType::Notch),
         "BP" | "BANDPASS" => Ok(BiquadFilterType::Bandpass),
+        "LP1" => Ok(BiquadFilterType::LowpassFirstOrder),
+        "HP1" => Ok(BiquadFilterType::HighpassFirstOrder),
+        "LS1" => Ok(BiquadFilterType::LowshelfFirstOrder),
+        "HS1" => Ok(BiquadFilterType::HighshelfFirstOrder),
+        "AP1" => Ok(BiquadFilterType::AllpassFirstOrder),
+        "AP" | "ALLPASS" => Ok(BiquadFilterType::Allpass),
+        "LT" => Ok(BiquadFilterType::LinkwitzTransform),
         _ => Err(format!(
-            "Unknown filter type '{}'. Valid types: PK/PEAK, LS/LOWSHELF, HS/HIGHSHELF, LP/LOWPASS, HP/HIGHPASS, NO/NOTCH, BP/BANDPASS",
+            "Unknown filter type '{}'. Valid types: PK/PEAK, LS/LOWSHELF, HS/HIGHSHELF, LP/LOWPASS, HP/HIGHPASS, NO/NOTCH, BP/BANDPASS, LP1, HP1, LS1, HS1, AP1, AP/ALLPASS, LT",
             type_str
         )),
     }
@@ -10,8 +10,8 @@ use super::{
 };
 use crate::plugins::{
     AnalyzerPlugin, CompressorPluginParams, EqPluginParams, GainPluginParams, GatePluginParams,
-    LimiterPluginParams, LoudnessCompensationPluginParams, Plugin, PluginHost, ProcessContext,
-    UpmixerPluginParams,
+    LimiterPluginParams, LoudnessCompensationPluginParams, ParameterId, ParameterValue, Plugin,
+    PluginHost, ProcessContext, UpmixerPluginParams,
 };
 
 use std::collections::HashMap;
@@ -325,13 +325,26 @@ fn run_processing_thread(
                     param_id,
                     value,
                 } => {
-                    // Update parameter on current host
-                    // TODO: Implement parameter setting
+                    // Update parameter on current host (and on the host being loaded)
                     eprintln!(
                         "[Processing Thread] Set parameter: plugin {} param {} = {}",
                         plugin_index, param_id, value
                     );
-                    response_tx.send(ProcessingResponse::Ok).ok();
+                    let id = ParameterId::from(param_id.as_str());
+                    let result = state.host.set_parameter(
+                        plugin_index,
+                        id.clone(),
+                        ParameterValue::Float(value),
+                    );
+                    if let Some(next_host) = &mut state.next_host {
+                        next_host
+                            .set_parameter(plugin_index, id, ParameterValue::Float(value))
+                            .ok();
+                    }
+                    match result {
+                        Ok(()) => response_tx.send(ProcessingResponse::Ok).ok(),
+                        Err(e) => response_tx.send(ProcessingResponse::Error(e)).ok(),
+                    };
                 }
                 ProcessingCommand::Bypass(bypass) => {
                     state.bypassed = bypass;
@@ -2,6 +2,7 @@
 // Plugin Host - Chain multiple plugins together
 // ============================================================================
 
+use super::parameters::{ParameterId, ParameterValue};
 use super::plugin::{Plugin, ProcessContext};
 use std::sync::{Arc, Mutex};
 
@@ -116,6 +117,22 @@ impl PluginHost {
         self.plugins.get(index).map(|p| p.as_ref())
     }
 
+    /// Set a parameter of the plugin at the given index
+    ///
+    /// Plugins that support it ramp the change over their smoothing time.
+    pub fn set_parameter(
+        &mut self,
+        index: usize,
+        id: ParameterId,
+        value: ParameterValue,
+    ) -> Result<(), String> {
+        let plugin = self
+            .plugins
+            .get_mut(index)
+            .ok_or_else(|| format!("Plugin index {} out of bounds", index))?;
+        plugin.set_parameter(id, value)
+    }
+
     // Note: Direct mutable access to plugins is not provided to avoid lifetime issues.
     // Use parameter system to modify plugin behavior dynamically.
     // If you need to modify a plugin, use the with_plugin method or access via host methods.
@@ -34,6 +34,7 @@ mod plugin_loudness_compensation;
 mod plugin_matrix;
 mod plugin_resampler;
 mod plugin_upmixer;
+mod smoothing;
 
 pub use analyzer::{AnalyzerData, AnalyzerPlugin, LoudnessData, SpectrumData};
 pub use host::{PluginHost, SharedPluginHost};
@@ -51,6 +52,7 @@ pub use plugin_loudness_compensation::{
 pub use plugin_matrix::MatrixPlugin;
 pub use plugin_resampler::ResamplerPlugin;
 pub use plugin_upmixer::{UpmixerPlugin, UpmixerPluginParams};
+pub use smoothing::DEFAULT_SMOOTHING_MS;
 
 #[allow(unused_imports)]
 pub(crate) use analyzer_loudness_monitor::LoudnessMonitor;
@@ -3,11 +3,15 @@
 // ============================================================================
 //
 // This plugin applies a chain of IIR biquad filters for parametric equalization.
-// Supports multiple channels with the same EQ curve applied to each channel.
+// Supports multiple channels, either linked with the same EQ curve applied to
+// each channel, or with a separate filter chain per channel (e.g. L/R room EQ).
+// Blocks are processed by a `BiquadCascade`, several channels at a time, and
+// filter changes ramp the coefficients over `smoothing_ms` to avoid clicks.
 
 use super::parameters::{Parameter, ParameterId, ParameterValue};
 use super::plugin::{Plugin, PluginInfo, PluginResult, ProcessContext};
-use autoeq_iir::Biquad;
+use super::smoothing::{DEFAULT_SMOOTHING_MS, default_smoothing_ms, smoothing_frames};
+use autoeq_iir::{Biquad, BiquadCascade};
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -22,12 +26,18 @@ pub struct BiquadFilterConfig {
     pub q: f64,
     #[serde(default)]
     pub db_gain: f64,
+    /// Channels the filter applies to (0-based); all channels when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<Vec<usize>>,
 }
 
 /// Configuration parameters for EqPlugin
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EqPluginParams {
     pub filters: Vec<BiquadFilterConfig>,
+    /// Ramp time for filter changes during playback (ms, 0 = instant)
+    #[serde(default = "default_smoothing_ms")]
+    pub smoothing_ms: f32,
 }
 
 // ============================================================================
@@ -39,12 +49,18 @@ pub struct EqPlugin {
     /// Number of input/output channels
     num_channels: usize,
 
-    /// IIR filters (one chain per channel)
-    /// filters[channel_idx][filter_idx]
+    /// IIR filter chain of each channel
     filters: Vec<Vec<Biquad>>,
 
+    /// Block processor holding the per channel filter state
+    cascade: BiquadCascade,
+
     /// Sample rate
     sample_rate: u32,
+
+    /// Ramp time for filter changes (ms)
+    param_smoothing_ms: ParameterId,
+    smoothing_ms: f32,
 }
 
 impl EqPlugin {
@@ -54,16 +70,24 @@ impl EqPlugin {
     /// * `num_channels` - Number of audio channels to process
     /// * `filters` - List of biquad filters to apply (will be cloned for each channel)
     pub fn new(num_channels: usize, filters: Vec<Biquad>) -> Self {
-        // Clone the filter chain for each channel
-        let mut channel_filters = Vec::with_capacity(num_channels);
-        for _ in 0..num_channels {
-            channel_filters.push(filters.clone());
-        }
+        Self::new_per_channel(vec![filters; num_channels])
+    }
+
+    /// Create a new EQ plugin with one filter chain per channel
+    ///
+    /// # Arguments
+    /// * `filters` - List of biquad filters of each channel; the number of
+    ///   channels is the number of chains
+    pub fn new_per_channel(filters: Vec<Vec<Biquad>>) -> Self {
+        let cascade = BiquadCascade::new_per_channel(&filters);
 
         Self {
-            num_channels,
-            filters: channel_filters,
+            num_channels: filters.len(),
+            filters,
+            cascade,
             sample_rate: 48000, // Will be updated in initialize()
+            param_smoothing_ms: ParameterId::from("smoothing_ms"),
+            smoothing_ms: DEFAULT_SMOOTHING_MS,
         }
     }
 
@@ -75,73 +99,108 @@ impl EqPlugin {
     ) -> Result<Self, String> {
         use autoeq_iir::BiquadFilterType;
 
-        let filters: Result<Vec<Biquad>, String> = params
-            .filters
-            .iter()
-            .map(|f| {
-                let filter_type = match f.filter_type.as_str() {
-                    "peak" => BiquadFilterType::Peak,
-                    "lowshelf" => BiquadFilterType::Lowshelf,
-                    "highshelf" => BiquadFilterType::Highshelf,
-                    "lowpass" => BiquadFilterType::Lowpass,
-                    "highpass" => BiquadFilterType::Highpass,
-                    "notch" => BiquadFilterType::Notch,
-                    "bandpass" => BiquadFilterType::Bandpass,
-                    other => return Err(format!("Unknown filter type: {}", other)),
-                };
-
-                Ok(Biquad::new(
-                    filter_type,
-                    f.freq,
-                    sample_rate as f64,
-                    f.q,
-                    f.db_gain,
-                ))
-            })
-            .collect();
-
-        let filters = filters?;
-        Ok(Self::new(num_channels, filters))
+        let mut chains: Vec<Vec<Biquad>> = vec![Vec::new(); num_channels];
+        for f in &params.filters {
+            let filter_type = match f.filter_type.as_str() {
+                "peak" => BiquadFilterType::Peak,
+                "lowshelf" => BiquadFilterType::Lowshelf,
+                "highshelf" => BiquadFilterType::Highshelf,
+                "lowpass" => BiquadFilterType::Lowpass,
+                "highpass" => BiquadFilterType::Highpass,
+                "notch" => BiquadFilterType::Notch,
+                "bandpass" => BiquadFilterType::Bandpass,
+                "lowpassfirstorder" => BiquadFilterType::LowpassFirstOrder,
+                "highpassfirstorder" => BiquadFilterType::HighpassFirstOrder,
+                "lowshelffirstorder" => BiquadFilterType::LowshelfFirstOrder,
+                "highshelffirstorder" => BiquadFilterType::HighshelfFirstOrder,
+                "allpassfirstorder" => BiquadFilterType::AllpassFirstOrder,
+                "allpass" => BiquadFilterType::Allpass,
+                "linkwitztransform" => BiquadFilterType::LinkwitzTransform,
+                other => return Err(format!("Unknown filter type: {}", other)),
+            };
+            let biquad = Biquad::new(filter_type, f.freq, sample_rate as f64, f.q, f.db_gain);
+
+            match &f.channels {
+                None => chains.iter_mut().for_each(|c| c.push(biquad.clone())),
+                Some(channels) => {
+                    for &ch in channels {
+                        let chain = chains.get_mut(ch).ok_or_else(|| {
+                            format!(
+                                "Filter channel {} out of range ({} channels)",
+                                ch, num_channels
+                            )
+                        })?;
+                        chain.push(biquad.clone());
+                    }
+                }
+            }
+        }
+
+        let mut plugin = Self::new_per_channel(chains);
+        plugin.set_smoothing_ms(params.smoothing_ms);
+        Ok(plugin)
     }
 
     /// Update the sample rate for all filters
     pub fn set_sample_rate(&mut self, sample_rate: u32) {
         self.sample_rate = sample_rate;
 
-        // Update sample rate for all filters
-        for channel_filters in &mut self.filters {
-            for filter in channel_filters {
-                filter.srate = sample_rate as f64;
-                // Recompute coefficients with new sample rate
-                // Note: This requires making compute_coeffs public or adding a method
-                // For now we'll recreate the filter
-                *filter = Biquad::new(
-                    filter.filter_type,
-                    filter.freq,
-                    sample_rate as f64,
-                    filter.q,
-                    filter.db_gain,
-                );
-            }
+        // Recompute coefficients with the new sample rate
+        for filter in self.filters.iter_mut().flatten() {
+            *filter = filter.redesign(sample_rate as f64, filter.design);
         }
+        self.cascade = BiquadCascade::new_per_channel(&self.filters);
     }
 
-    /// Replace the filter chain
+    /// Replace the filter chain of every channel
+    ///
+    /// When the number of filters does not change, the coefficients ramp to
+    /// the new ones over the smoothing time and the filter state is kept.
+    /// Otherwise the new chain is applied at once.
     pub fn set_filters(&mut self, filters: Vec<Biquad>) {
-        // Clone the new filter chain for each channel
-        self.filters.clear();
-        for _ in 0..self.num_channels {
-            self.filters.push(filters.clone());
+        let ramp_frames = smoothing_frames(self.smoothing_ms, self.sample_rate);
+        self.cascade.set_filters_smoothed(&filters, ramp_frames);
+        self.filters = vec![filters; self.num_channels];
+    }
+
+    /// Replace the filter chain of each channel
+    ///
+    /// Same as `set_filters` with one chain per channel.
+    pub fn set_channel_filters(&mut self, filters: Vec<Vec<Biquad>>) -> Result<(), String> {
+        if filters.len() != self.num_channels {
+            return Err(format!(
+                "Expected {} filter chains, got {}",
+                self.num_channels,
+                filters.len()
+            ));
         }
+        let ramp_frames = smoothing_frames(self.smoothing_ms, self.sample_rate);
+        self.cascade
+            .set_channel_filters_smoothed(&filters, ramp_frames);
+        self.filters = filters;
+        Ok(())
+    }
+
+    /// Set the ramp time used by `set_filters` (ms, 0 = instant)
+    pub fn set_smoothing_ms(&mut self, smoothing_ms: f32) {
+        self.smoothing_ms = smoothing_ms.max(0.0);
+    }
+
+    /// Get the ramp time used by `set_filters` (ms)
+    pub fn smoothing_ms(&self) -> f32 {
+        self.smoothing_ms
     }
 
-    /// Get a reference to the filter chain
+    /// Get a reference to the filter chain of the first channel
+    ///
+    /// This is the chain of every channel when they are linked.
     pub fn filters(&self) -> &[Biquad] {
-        if !self.filters.is_empty() {
-            &self.filters[0]
-        } else {
-            &[]
-        }
+        self.channel_filters(0)
+    }
+
+    /// Get a reference to the filter chain of a channel (empty if out of range)
+    pub fn channel_filters(&self, channel: usize) -> &[Biquad] {
+        self.filters.get(channel).map_or(&[], Vec::as_slice)
     }
 }
 
@@ -153,7 +212,7 @@ impl Plugin for EqPlugin {
             author: "AutoEQ".to_string(),
             description: format!(
                 "Parametric equalizer with {} IIR biquad filters",
-                self.filters.first().map(|f| f.len()).unwrap_or(0)
+                self.cascade.len()
             ),
         }
     }
@@ -167,17 +226,41 @@ impl Plugin for EqPlugin {
     }
 
     fn parameters(&self) -> Vec<Parameter> {
-        // EQ parameters are managed externally via set_filters()
-        // Could add per-filter gain controls here if needed
-        vec![]
+        // Filters are managed externally via set_filters()
+        vec![
+            Parameter::new_float(
+                "smoothing_ms",
+                "Smoothing (ms)",
+                DEFAULT_SMOOTHING_MS,
+                0.0,
+                500.0,
+            )
+            .with_description("Ramp time of filter changes during playback"),
+        ]
     }
 
-    fn set_parameter(&mut self, _id: ParameterId, _value: ParameterValue) -> PluginResult<()> {
-        Err("EQ plugin has no adjustable parameters (use set_filters() instead)".to_string())
+    fn set_parameter(&mut self, id: ParameterId, value: ParameterValue) -> PluginResult<()> {
+        if id == self.param_smoothing_ms {
+            if let Some(smoothing_ms) = value.as_float() {
+                self.set_smoothing_ms(smoothing_ms);
+                Ok(())
+            } else {
+                Err("Smoothing parameter must be a float".to_string())
+            }
+        } else {
+            Err(format!(
+                "Unknown parameter: {} (use set_filters() to change the filters)",
+                id
+            ))
+        }
     }
 
-    fn get_parameter(&self, _id: &ParameterId) -> Option<ParameterValue> {
-        None
+    fn get_parameter(&self, id: &ParameterId) -> Option<ParameterValue> {
+        if id == &self.param_smoothing_ms {
+            Some(ParameterValue::Float(self.smoothing_ms))
+        } else {
+            None
+        }
     }
 
     fn initialize(&mut self, sample_rate: u32) -> PluginResult<()> {
@@ -187,18 +270,7 @@ impl Plugin for EqPlugin {
 
     fn reset(&mut self) {
         // Reset filter state for all channels
-        for channel_filters in &mut self.filters {
-            for filter in channel_filters {
-                // Reset filter state by recreating
-                *filter = Biquad::new(
-                    filter.filter_type,
-                    filter.freq,
-                    filter.srate,
-                    filter.q,
-                    filter.db_gain,
-                );
-            }
-        }
+        self.cascade.reset();
     }
 
     fn process(
@@ -226,20 +298,8 @@ impl Plugin for EqPlugin {
             ));
         }
 
-        // Process each frame
-        for frame_idx in 0..context.num_frames {
-            for ch in 0..self.num_channels {
-                let sample_idx = frame_idx * self.num_channels + ch;
-                let mut sample = input[sample_idx] as f64;
-
-                // Apply all filters in the chain for this channel
-                for filter in &mut self.filters[ch] {
-                    sample = filter.process(sample);
-                }
-
-                output[sample_idx] = sample as f32;
-            }
-        }
+        // Apply the filter chain to the whole block
+        self.cascade.process_interleaved(input, output);
 
         Ok(())
     }
@@ -372,4 +432,68 @@ mod tests {
         let sum: f32 = output.iter().map(|x| x.abs()).sum();
         assert!(sum > 0.0, "Output should not be all zeros");
     }
+
+    #[test]
+    fn test_eq_matches_scalar_biquads() {
+        // 6 channels span two groups of the block processor
+        let filters = vec![
+            Biquad::new(BiquadFilterType::Highpass, 30.0, 48000.0, 0.707, 0.0),
+            Biquad::new(BiquadFilterType::Peak, 250.0, 48000.0, 2.0, -6.0),
+            Biquad::new(BiquadFilterType::Highshelf, 6000.0, 48000.0, 0.707, 4.0),
+        ];
+        let num_channels = 6;
+        let num_frames = 2048;
+        let mut plugin = EqPlugin::new(num_channels, filters.clone());
+        plugin.initialize(48000).unwrap();
+
+        let mut input = vec![0.0_f32; num_frames * num_channels];
+        for (i, x) in input.iter_mut().enumerate() {
+            *x = ((i as f32 * 0.37).sin() + (i as f32 * 0.011).cos()) * 0.25;
+        }
+        let mut output = vec![0.0_f32; input.len()];
+        let context = ProcessContext {
+            sample_rate: 48000,
+            num_frames,
+        };
+        plugin.process(&input, &mut output, &context).unwrap();
+
+        for ch in 0..num_channels {
+            let mut chain = filters.clone();
+            for frame in 0..num_frames {
+                let idx = frame * num_channels + ch;
+                let mut sample = input[idx] as f64;
+                for filter in &mut chain {
+                    sample = filter.process(sample);
+                }
+                assert!(
+                    (sample as f32 - output[idx]).abs() < 1e-5,
+                    "channel {} frame {}",
+                    ch,
+                    frame
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_eq_from_params_channel_mask() {
+        let json = serde_json::json!({
+            "filters": [
+                {"filter_type": "highpass", "freq": 30.0, "q": 0.707},
+                {"filter_type": "peak", "freq": 120.0, "q": 4.0, "db_gain": -6.0, "channels": [0]},
+                {"filter_type": "peak", "freq": 90.0, "q": 3.0, "db_gain": -4.0, "channels": [1]},
+                {"filter_type": "highshelf", "freq": 8000.0, "q": 0.707, "db_gain": 2.0, "channels": [0, 1]}
+            ]
+        });
+        let params: EqPluginParams = serde_json::from_value(json).unwrap();
+        let plugin = EqPlugin::from_params(2, 48000, params.clone()).unwrap();
+        assert_eq!(plugin.channel_filters(0).len(), 3);
+        assert_eq!(plugin.channel_filters(1).len(), 3);
+        assert_eq!(plugin.channel_filters(0)[1].freq, 120.0);
+        assert_eq!(plugin.channel_filters(1)[1].freq, 90.0);
+        assert_eq!(plugin.filters()[1].freq, 120.0);
+
+        // a channel outside of the plugin is an error
+        assert!(EqPlugin::from_params(1, 48000, params).is_err());
+    }
 }
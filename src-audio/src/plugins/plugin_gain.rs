@@ -4,6 +4,7 @@
 
 use super::parameters::{Parameter, ParameterId, ParameterValue};
 use super::plugin::{InPlacePlugin, PluginInfo, PluginResult, ProcessContext};
+use super::smoothing::{DEFAULT_SMOOTHING_MS, LinearRamp, default_smoothing_ms, smoothing_frames};
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -19,6 +20,9 @@ fn default_gain_db() -> f32 {
 pub struct GainPluginParams {
     #[serde(default = "default_gain_db")]
     pub gain_db: f32,
+    /// Ramp time for gain changes during playback (ms, 0 = instant)
+    #[serde(default = "default_smoothing_ms")]
+    pub smoothing_ms: f32,
 }
 
 // ============================================================================
@@ -27,7 +31,8 @@ pub struct GainPluginParams {
 
 /// Simple gain plugin that multiplies all samples by a gain factor
 ///
-/// The gain can be specified in dB or as a linear multiplier.
+/// The gain can be specified in dB or as a linear multiplier. Changes after
+/// creation ramp linearly over the smoothing time (20 ms by default).
 ///
 /// # Example
 /// ```
@@ -45,6 +50,14 @@ pub struct GainPlugin {
     gain_linear: f32,
     /// Parameter ID for gain
     param_gain_db: ParameterId,
+    /// Applied linear gain, ramping towards gain_linear
+    ramp: LinearRamp,
+    /// Ramp time for gain changes (ms)
+    smoothing_ms: f32,
+    /// Parameter ID for the ramp time
+    param_smoothing_ms: ParameterId,
+    /// Sample rate
+    sample_rate: u32,
 }
 
 impl GainPlugin {
@@ -60,27 +73,50 @@ impl GainPlugin {
             gain_db,
             gain_linear,
             param_gain_db: ParameterId::from("gain_db"),
+            ramp: LinearRamp::new(gain_linear),
+            smoothing_ms: DEFAULT_SMOOTHING_MS,
+            param_smoothing_ms: ParameterId::from("smoothing_ms"),
+            sample_rate: 48000, // Will be updated in initialize()
         }
     }
 
     /// Create a new gain plugin from configuration parameters
     pub fn from_params(channels: usize, params: GainPluginParams) -> Self {
-        Self::new(channels, params.gain_db)
+        let mut plugin = Self::new(channels, params.gain_db);
+        plugin.set_smoothing_ms(params.smoothing_ms);
+        plugin
     }
 
     /// Set gain in dB
     pub fn set_gain_db(&mut self, gain_db: f32) {
         self.gain_db = gain_db;
         self.gain_linear = Self::db_to_linear(gain_db);
+        self.start_ramp();
     }
 
     /// Set gain as linear multiplier
     pub fn set_gain_linear(&mut self, gain: f32) {
         self.gain_linear = gain;
         self.gain_db = Self::linear_to_db(gain);
+        self.start_ramp();
+    }
+
+    /// Set the ramp time of gain changes (ms, 0 = instant)
+    pub fn set_smoothing_ms(&mut self, smoothing_ms: f32) {
+        self.smoothing_ms = smoothing_ms.max(0.0);
+    }
+
+    /// Get the ramp time of gain changes (ms)
+    pub fn smoothing_ms(&self) -> f32 {
+        self.smoothing_ms
     }
 
-    /// Get current gain in dB
+    fn start_ramp(&mut self) {
+        let frames = smoothing_frames(self.smoothing_ms, self.sample_rate);
+        self.ramp.set_target(self.gain_linear, frames);
+    }
+
+    /// Get current gain in dB (target of a running ramp)
     pub fn gain_db(&self) -> f32 {
         self.gain_db
     }
@@ -120,6 +156,14 @@ impl InPlacePlugin for GainPlugin {
             Parameter::new_float("gain_db", "Gain (dB)", 0.0, -60.0, 20.0).with_description(
                 "Gain in decibels. 0dB = unity gain, negative = attenuation, positive = boost",
             ),
+            Parameter::new_float(
+                "smoothing_ms",
+                "Smoothing (ms)",
+                DEFAULT_SMOOTHING_MS,
+                0.0,
+                500.0,
+            )
+            .with_description("Ramp time of gain changes during playback"),
         ]
     }
 
@@ -131,6 +175,13 @@ impl InPlacePlugin for GainPlugin {
             } else {
                 Err("Gain parameter must be a float".to_string())
             }
+        } else if id == self.param_smoothing_ms {
+            if let Some(smoothing_ms) = value.as_float() {
+                self.set_smoothing_ms(smoothing_ms);
+                Ok(())
+            } else {
+                Err("Smoothing parameter must be a float".to_string())
+            }
         } else {
             Err(format!("Unknown parameter: {}", id))
         }
@@ -139,11 +190,22 @@ impl InPlacePlugin for GainPlugin {
     fn get_parameter(&self, id: &ParameterId) -> Option<ParameterValue> {
         if id == &self.param_gain_db {
             Some(ParameterValue::Float(self.gain_db))
+        } else if id == &self.param_smoothing_ms {
+            Some(ParameterValue::Float(self.smoothing_ms))
         } else {
             None
         }
     }
 
+    fn initialize(&mut self, sample_rate: u32) -> PluginResult<()> {
+        self.sample_rate = sample_rate;
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.ramp.finish();
+    }
+
     fn process_in_place(
         &mut self,
         buffer: &mut [f32],
@@ -158,9 +220,20 @@ impl InPlacePlugin for GainPlugin {
             ));
         }
 
-        // Apply gain to all samples
-        for sample in buffer.iter_mut() {
-            *sample *= self.gain_linear;
+        if self.ramp.is_active() {
+            // Ramp the gain frame by frame
+            for frame in buffer.chunks_exact_mut(self.channels.max(1)) {
+                let gain = self.ramp.next_value();
+                for sample in frame.iter_mut() {
+                    *sample *= gain;
+                }
+            }
+        } else {
+            // Apply gain to all samples
+            let gain = self.ramp.value();
+            for sample in buffer.iter_mut() {
+                *sample *= gain;
+            }
         }
 
         Ok(())
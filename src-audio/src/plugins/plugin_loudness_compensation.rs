@@ -7,12 +7,16 @@
 // - High-shelf filter with 12dB/octave slope (2 cascaded biquads)
 // - Automatic gain compensation to prevent clipping
 //
+// Parameter changes during playback ramp the filter coefficients and the
+// compensation gain over `smoothing_ms`.
+//
 // Typical use: Boost bass and treble at low listening volumes to compensate
 // for the Fletcher-Munson equal-loudness contours.
 
 use super::parameters::{Parameter, ParameterId, ParameterValue};
 use super::plugin::{Plugin, PluginInfo, PluginResult, ProcessContext};
-use autoeq_iir::{Biquad, BiquadFilterType};
+use super::smoothing::{DEFAULT_SMOOTHING_MS, LinearRamp, default_smoothing_ms, smoothing_frames};
+use autoeq_iir::{Biquad, BiquadCascade, BiquadFilterType};
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -95,6 +99,9 @@ pub struct LoudnessCompensationPluginParams {
     pub high_freq: f32,
     #[serde(default = "default_high_gain")]
     pub high_gain: f32,
+    /// Ramp time for parameter changes during playback (ms, 0 = instant)
+    #[serde(default = "default_smoothing_ms")]
+    pub smoothing_ms: f32,
 }
 
 // ============================================================================
@@ -125,14 +132,23 @@ pub struct LoudnessCompensationPlugin {
     /// Sample rate
     sample_rate: u32,
 
-    /// Filters for each channel
-    /// filters[channel][filter_idx] where filter_idx:
+    /// Filter chain shared by all channels:
     /// 0-1: Low-shelf stages (2 for 12dB/oct)
     /// 2-3: High-shelf stages (2 for 12dB/oct)
-    filters: Vec<Vec<Biquad>>,
+    filters: Vec<Biquad>,
+
+    /// Block processor holding the per channel filter state
+    cascade: BiquadCascade,
 
     /// Compensation gain to prevent clipping
     compensation_gain: f32,
+
+    /// Applied linear compensation gain, ramping towards compensation_gain
+    compensation_ramp: LinearRamp,
+
+    /// Ramp time for parameter changes (ms)
+    param_smoothing_ms: ParameterId,
+    smoothing_ms: f32,
 }
 
 impl LoudnessCompensationPlugin {
@@ -163,7 +179,11 @@ impl LoudnessCompensationPlugin {
             high_gain,
             sample_rate: 48000,
             filters: Vec::new(),
+            cascade: BiquadCascade::new(&[], num_channels),
             compensation_gain: 0.0,
+            compensation_ramp: LinearRamp::new(1.0),
+            param_smoothing_ms: ParameterId::from("smoothing_ms"),
+            smoothing_ms: DEFAULT_SMOOTHING_MS,
         };
 
         plugin.rebuild_filters();
@@ -172,17 +192,39 @@ impl LoudnessCompensationPlugin {
 
     /// Create a new loudness compensation plugin from configuration parameters
     pub fn from_params(num_channels: usize, params: LoudnessCompensationPluginParams) -> Self {
-        Self::new(
+        let mut plugin = Self::new(
             num_channels,
             params.low_freq,
             params.low_gain,
             params.high_freq,
             params.high_gain,
-        )
+        );
+        plugin.smoothing_ms = params.smoothing_ms.max(0.0);
+        plugin
     }
 
-    /// Rebuild all filters based on current parameters
+    /// Rebuild all filters based on current parameters, resetting their state
     fn rebuild_filters(&mut self) {
+        self.compute_filters();
+        self.cascade = BiquadCascade::new(&self.filters, self.num_channels);
+        self.compensation_ramp = LinearRamp::new(self.compensation_gain_linear());
+    }
+
+    /// Ramp the filters and the compensation gain to the current parameters
+    fn update_filters(&mut self) {
+        self.compute_filters();
+        let frames = smoothing_frames(self.smoothing_ms, self.sample_rate);
+        self.cascade.set_filters_smoothed(&self.filters, frames);
+        self.compensation_ramp
+            .set_target(self.compensation_gain_linear(), frames);
+    }
+
+    fn compensation_gain_linear(&self) -> f32 {
+        10.0_f32.powf(self.compensation_gain / 20.0)
+    }
+
+    /// Compute the filter chain and compensation gain from current parameters
+    fn compute_filters(&mut self) {
         // Calculate compensation gain: -max(low_gain, high_gain)
         // This prevents clipping when both shelves boost
         self.compensation_gain = -self.low_gain.max(self.high_gain);
@@ -195,49 +237,49 @@ impl LoudnessCompensationPlugin {
         // Q factor for shelving filters (0.707 = Butterworth response)
         let q = 0.707;
 
-        self.filters.clear();
-        for _ in 0..self.num_channels {
-            let channel_filters = vec![
-                // Low-shelf stage 1
-                Biquad::new(
-                    BiquadFilterType::Lowshelf,
-                    self.low_freq as f64,
-                    self.sample_rate as f64,
-                    q,
-                    low_gain_per_stage as f64,
-                ),
-                // Low-shelf stage 2
-                Biquad::new(
-                    BiquadFilterType::Lowshelf,
-                    self.low_freq as f64,
-                    self.sample_rate as f64,
-                    q,
-                    low_gain_per_stage as f64,
-                ),
-                // High-shelf stage 1
-                Biquad::new(
-                    BiquadFilterType::Highshelf,
-                    self.high_freq as f64,
-                    self.sample_rate as f64,
-                    q,
-                    high_gain_per_stage as f64,
-                ),
-                // High-shelf stage 2
-                Biquad::new(
-                    BiquadFilterType::Highshelf,
-                    self.high_freq as f64,
-                    self.sample_rate as f64,
-                    q,
-                    high_gain_per_stage as f64,
-                ),
-            ];
-
-            self.filters.push(channel_filters);
-        }
+        self.filters = vec![
+            // Low-shelf stage 1
+            Biquad::new(
+                BiquadFilterType::Lowshelf,
+                self.low_freq as f64,
+                self.sample_rate as f64,
+                q,
+                low_gain_per_stage as f64,
+            ),
+            // Low-shelf stage 2
+            Biquad::new(
+                BiquadFilterType::Lowshelf,
+                self.low_freq as f64,
+                self.sample_rate as f64,
+                q,
+                low_gain_per_stage as f64,
+            ),
+            // High-shelf stage 1
+            Biquad::new(
+                BiquadFilterType::Highshelf,
+                self.high_freq as f64,
+                self.sample_rate as f64,
+                q,
+                high_gain_per_stage as f64,
+            ),
+            // High-shelf stage 2
+            Biquad::new(
+                BiquadFilterType::Highshelf,
+                self.high_freq as f64,
+                self.sample_rate as f64,
+                q,
+                high_gain_per_stage as f64,
+            ),
+        ];
     }
 
-    /// Update a parameter and rebuild filters if needed
+    /// Update a parameter and ramp the filters to it if needed
     fn update_parameter(&mut self, id: &ParameterId, value: f32) -> bool {
+        if id == &self.param_smoothing_ms {
+            self.smoothing_ms = value.max(0.0);
+            return true;
+        }
+
         let mut changed = false;
 
         if id == &self.param_low_freq {
@@ -255,7 +297,7 @@ impl LoudnessCompensationPlugin {
         }
 
         if changed {
-            self.rebuild_filters();
+            self.update_filters();
         }
 
         changed
@@ -304,6 +346,14 @@ impl Plugin for LoudnessCompensationPlugin {
             .with_description("Frequency for treble boost (Hz)"),
             Parameter::new_float("high_gain", "High-shelf Gain", self.high_gain, -20.0, 20.0)
                 .with_description("Treble boost amount (dB)"),
+            Parameter::new_float(
+                "smoothing_ms",
+                "Smoothing (ms)",
+                self.smoothing_ms,
+                0.0,
+                500.0,
+            )
+            .with_description("Ramp time of parameter changes during playback"),
         ]
     }
 
@@ -325,6 +375,8 @@ impl Plugin for LoudnessCompensationPlugin {
             Some(ParameterValue::Float(self.high_freq))
         } else if id == &self.param_high_gain {
             Some(ParameterValue::Float(self.high_gain))
+        } else if id == &self.param_smoothing_ms {
+            Some(ParameterValue::Float(self.smoothing_ms))
         } else {
             None
         }
@@ -366,22 +418,14 @@ impl Plugin for LoudnessCompensationPlugin {
             ));
         }
 
-        // Calculate linear compensation gain
-        let comp_gain_linear = 10.0_f32.powf(self.compensation_gain / 20.0);
-
-        // Process each frame
-        for frame_idx in 0..context.num_frames {
-            for ch in 0..self.num_channels {
-                let sample_idx = frame_idx * self.num_channels + ch;
-                let mut sample = input[sample_idx] as f64;
-
-                // Apply all 4 filters in series (2 low-shelf + 2 high-shelf)
-                for filter in &mut self.filters[ch] {
-                    sample = filter.process(sample);
-                }
+        // Apply all 4 filters in series (2 low-shelf + 2 high-shelf)
+        self.cascade.process_interleaved(input, output);
 
-                // Apply compensation gain
-                output[sample_idx] = (sample as f32) * comp_gain_linear;
+        // Apply compensation gain
+        for frame in output.chunks_exact_mut(self.num_channels.max(1)) {
+            let gain = self.compensation_ramp.next_value();
+            for sample in frame.iter_mut() {
+                *sample *= gain;
             }
         }
 
@@ -403,8 +447,8 @@ mod tests {
         let plugin = LoudnessCompensationPlugin::new(2, 100.0, 6.0, 10000.0, 6.0);
         assert_eq!(plugin.input_channels(), 2);
         assert_eq!(plugin.output_channels(), 2);
-        assert_eq!(plugin.filters.len(), 2); // 2 channels
-        assert_eq!(plugin.filters[0].len(), 4); // 4 filters per channel
+        assert_eq!(plugin.cascade.num_channels(), 2); // 2 channels
+        assert_eq!(plugin.filters.len(), 4); // 4 filters per channel
     }
 
     #[test]
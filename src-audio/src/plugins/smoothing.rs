@@ -0,0 +1,107 @@
+// ============================================================================
+// Parameter Smoothing
+// ============================================================================
+//
+// Live parameter changes are ramped over a short time instead of being applied
+// at a block boundary, which would click. Gains use a linear ramp per frame;
+// biquad chains ramp their coefficients in `autoeq_iir::BiquadCascade`.
+
+/// Default smoothing time for live parameter changes (ms)
+pub const DEFAULT_SMOOTHING_MS: f32 = 20.0;
+
+/// Serde default for the `smoothing_ms` field of plugin parameters
+pub(crate) fn default_smoothing_ms() -> f32 {
+    DEFAULT_SMOOTHING_MS
+}
+
+/// Number of frames of a ramp lasting `smoothing_ms` at `sample_rate`
+pub(crate) fn smoothing_frames(smoothing_ms: f32, sample_rate: u32) -> usize {
+    (smoothing_ms.max(0.0) * sample_rate as f32 / 1000.0).round() as usize
+}
+
+/// A value ramping linearly towards its target, one step per frame
+#[derive(Debug, Clone)]
+pub(crate) struct LinearRamp {
+    current: f32,
+    target: f32,
+    step: f32,
+    remaining: usize,
+}
+
+impl LinearRamp {
+    /// Create a ramp resting at `value`
+    pub(crate) fn new(value: f32) -> Self {
+        Self {
+            current: value,
+            target: value,
+            step: 0.0,
+            remaining: 0,
+        }
+    }
+
+    /// Start a ramp from the current value to `target` over `frames` frames
+    pub(crate) fn set_target(&mut self, target: f32, frames: usize) {
+        self.target = target;
+        if frames == 0 {
+            self.current = target;
+            self.remaining = 0;
+        } else {
+            self.step = (target - self.current) / frames as f32;
+            self.remaining = frames;
+        }
+    }
+
+    /// Jump to the target
+    pub(crate) fn finish(&mut self) {
+        self.current = self.target;
+        self.remaining = 0;
+    }
+
+    /// True while the value is moving
+    pub(crate) fn is_active(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Current value
+    pub(crate) fn value(&self) -> f32 {
+        self.current
+    }
+
+    /// Advance one frame and return the new value
+    pub(crate) fn next_value(&mut self) -> f32 {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.current = if self.remaining == 0 {
+                self.target
+            } else {
+                self.current + self.step
+            };
+        }
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothing_frames() {
+        assert_eq!(smoothing_frames(20.0, 48000), 960);
+        assert_eq!(smoothing_frames(0.0, 48000), 0);
+        assert_eq!(smoothing_frames(-5.0, 48000), 0);
+    }
+
+    #[test]
+    fn test_linear_ramp() {
+        let mut ramp = LinearRamp::new(1.0);
+        assert!(!ramp.is_active());
+        ramp.set_target(2.0, 4);
+        let values: Vec<f32> = (0..6).map(|_| ramp.next_value()).collect();
+        assert_eq!(values, vec![1.25, 1.5, 1.75, 2.0, 2.0, 2.0]);
+        assert!(!ramp.is_active());
+
+        ramp.set_target(0.0, 0);
+        assert_eq!(ramp.value(), 0.0);
+    }
+}
@@ -0,0 +1,30 @@
+// Helpers shared by the integration tests
+
+use autoeq_iir::{Biquad, BiquadCascade, BiquadFilterType};
+
+/// Energy above 4 kHz of the first channel from frame `start` on. The test
+/// tone is far below, so this measures the click of a parameter change.
+pub fn transient_energy(output: &[f32], channels: usize, start: usize) -> f64 {
+    let first: Vec<f32> = output.iter().step_by(channels).copied().collect();
+    let mut highpass = BiquadCascade::new(
+        &[
+            Biquad::new(BiquadFilterType::Highpass, 4000.0, 48000.0, 0.54, 0.0),
+            Biquad::new(BiquadFilterType::Highpass, 4000.0, 48000.0, 1.31, 0.0),
+        ],
+        1,
+    );
+    let mut high = vec![0.0_f32; first.len()];
+    highpass.process_interleaved(&first, &mut high);
+    high[start..].iter().map(|x| (*x as f64).powi(2)).sum()
+}
+
+/// Stereo 100 Hz tone
+pub fn low_tone(num_frames: usize) -> Vec<f32> {
+    let mut input = vec![0.0_f32; num_frames * 2];
+    for i in 0..num_frames {
+        let phase = 2.0 * std::f32::consts::PI * 100.0 * i as f32 / 48000.0;
+        input[i * 2] = phase.sin() * 0.3;
+        input[i * 2 + 1] = phase.sin() * 0.3;
+    }
+    input
+}
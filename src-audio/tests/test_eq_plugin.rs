@@ -4,7 +4,10 @@
 //
 // This file demonstrates how to use the EQ plugin with IIR biquad filters.
 
+mod common;
+
 use autoeq_iir::{Biquad, BiquadFilterType};
+use common::{low_tone, transient_energy};
 use sotf_audio::plugins::{EqPlugin, Plugin, ProcessContext};
 
 #[test]
@@ -202,3 +205,111 @@ fn test_eq_plugin_multi_channel() {
 
     println!("5-channel EQ processed successfully");
 }
+
+#[test]
+fn test_eq_plugin_per_channel() {
+    // Room EQ with a different correction on each side
+    let left = vec![Biquad::new(
+        BiquadFilterType::Peak,
+        100.0,
+        48000.0,
+        1.0,
+        -9.0,
+    )];
+    let right = vec![
+        Biquad::new(BiquadFilterType::Peak, 1000.0, 48000.0, 1.0, -9.0),
+        Biquad::new(BiquadFilterType::Highshelf, 8000.0, 48000.0, 0.707, 2.0),
+    ];
+    let mut plugin = EqPlugin::new_per_channel(vec![left, right]);
+    plugin.initialize(48000).unwrap();
+    assert_eq!(plugin.input_channels(), 2);
+
+    // 100 Hz on both channels: only the left one is cut
+    let num_frames = 9600;
+    let input = low_tone(num_frames);
+    let mut output = vec![0.0_f32; input.len()];
+    let context = ProcessContext {
+        sample_rate: 48000,
+        num_frames,
+    };
+    plugin.process(&input, &mut output, &context).unwrap();
+
+    let rms = |ch: usize| {
+        let tail = &output[num_frames..];
+        (tail.iter().skip(ch).step_by(2).map(|x| x * x).sum::<f32>() / (num_frames / 2) as f32)
+            .sqrt()
+    };
+    let input_rms = 0.3 / 2.0_f32.sqrt();
+    let left_db = 20.0 * (rms(0) / input_rms).log10();
+    let right_db = 20.0 * (rms(1) / input_rms).log10();
+    assert!((left_db + 9.0).abs() < 0.5, "left {} dB", left_db);
+    assert!(right_db.abs() < 0.5, "right {} dB", right_db);
+
+    // one chain per channel is required
+    assert!(plugin.set_channel_filters(vec![vec![]]).is_err());
+    assert!(plugin.set_channel_filters(vec![vec![], vec![]]).is_ok());
+    assert!(plugin.channel_filters(1).is_empty());
+}
+
+/// Run blocks of 480 frames and swap the filters after `change` frames
+fn eq_with_filter_change(smoothing_ms: f32, change: usize) -> Vec<f32> {
+    let peak = |gain| {
+        vec![Biquad::new(
+            BiquadFilterType::Peak,
+            120.0,
+            48000.0,
+            1.0,
+            gain,
+        )]
+    };
+    let mut plugin = EqPlugin::new(2, peak(10.0));
+    plugin.initialize(48000).unwrap();
+    plugin.set_smoothing_ms(smoothing_ms);
+
+    let block = 480;
+    let input = low_tone(block * 20);
+    let mut output = vec![0.0_f32; input.len()];
+    let context = ProcessContext {
+        sample_rate: 48000,
+        num_frames: block,
+    };
+    for (i, (inp, out)) in input
+        .chunks(block * 2)
+        .zip(output.chunks_mut(block * 2))
+        .enumerate()
+    {
+        if i * block == change {
+            plugin.set_filters(peak(-10.0));
+        }
+        plugin.process(inp, out, &context).unwrap();
+    }
+    output
+}
+
+#[test]
+fn test_eq_plugin_smoothed_filter_change() {
+    let change = 4800;
+    let instant = transient_energy(&eq_with_filter_change(0.0, change), 2, change - 100);
+    let smoothed = transient_energy(&eq_with_filter_change(20.0, change), 2, change - 100);
+
+    println!(
+        "Transient energy: instant {:.3e}, smoothed {:.3e}",
+        instant, smoothed
+    );
+    assert!(instant > 0.0);
+    assert!(
+        smoothed < instant / 100.0,
+        "Smoothing should remove the click of a filter change"
+    );
+
+    // Both end on the same filters
+    let a = eq_with_filter_change(0.0, change);
+    let b = eq_with_filter_change(20.0, change);
+    let tail = a.len() - 200;
+    let max_diff = a[tail..]
+        .iter()
+        .zip(b[tail..].iter())
+        .map(|(x, y)| (x - y).abs())
+        .fold(0.0_f32, f32::max);
+    assert!(max_diff < 1e-3, "{}", max_diff);
+}
@@ -2,6 +2,9 @@
 // Loudness Compensation Plugin Integration Tests
 // ============================================================================
 
+mod common;
+
+use common::{low_tone, transient_energy};
 use sotf_audio::plugins::{
     LoudnessCompensationPlugin, ParameterId, ParameterValue, Plugin, ProcessContext,
 };
@@ -238,3 +241,54 @@ fn test_loudness_comp_stereo_to_5ch() {
 
     println!("5-channel loudness compensation processed successfully");
 }
+
+/// Run blocks of 480 frames and raise the bass boost after `change` frames
+fn loudness_with_change(smoothing_ms: f32, change: usize) -> Vec<f32> {
+    let mut plugin = LoudnessCompensationPlugin::new(2, 100.0, 0.0, 10000.0, 0.0);
+    plugin.initialize(48000).unwrap();
+    plugin
+        .set_parameter(
+            ParameterId::from("smoothing_ms"),
+            ParameterValue::Float(smoothing_ms),
+        )
+        .unwrap();
+
+    let block = 480;
+    let input = low_tone(block * 20);
+    let mut output = vec![0.0_f32; input.len()];
+    let context = ProcessContext {
+        sample_rate: 48000,
+        num_frames: block,
+    };
+    for (i, (inp, out)) in input
+        .chunks(block * 2)
+        .zip(output.chunks_mut(block * 2))
+        .enumerate()
+    {
+        if i * block == change {
+            plugin
+                .set_parameter(ParameterId::from("low_gain"), ParameterValue::Float(12.0))
+                .unwrap();
+        }
+        plugin.process(inp, out, &context).unwrap();
+    }
+    output
+}
+
+#[test]
+fn test_loudness_comp_smoothed_change() {
+    let change = 4800;
+    let instant = transient_energy(&loudness_with_change(0.0, change), 2, change - 100);
+    let smoothed = transient_energy(&loudness_with_change(20.0, change), 2, change - 100);
+
+    println!(
+        "Transient energy: instant {:.3e}, smoothed {:.3e}",
+        instant, smoothed
+    );
+    assert!(instant > 0.0);
+    // the shelves move right at the frequency of the tone
+    assert!(
+        smoothed < instant / 20.0,
+        "Smoothing should remove the click of a parameter change"
+    );
+}
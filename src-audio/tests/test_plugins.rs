@@ -1,5 +1,9 @@
 // Integration tests for the plugin system
 
+mod common;
+
+use common::{low_tone, transient_energy};
+use sotf_audio::plugins::{ParameterId, ParameterValue};
 use sotf_audio::{GainPlugin, InPlacePluginAdapter, PluginHost};
 
 #[test]
@@ -60,3 +64,66 @@ fn test_empty_plugin_host() {
 
 // Note: Most comprehensive tests are in the unit tests within each plugin module
 // Integration tests here focus on the PluginHost API which is the main public interface
+
+/// Run blocks of 520 frames and set the gain to -20 dB after `change` frames
+fn gain_with_change(smoothing_ms: f32, change: usize) -> Vec<f32> {
+    let mut host = PluginHost::new(2, 48000);
+    let mut gain = GainPlugin::new(2, 0.0);
+    gain.set_smoothing_ms(smoothing_ms);
+    host.add_plugin(Box::new(InPlacePluginAdapter::new(gain)))
+        .unwrap();
+
+    let block = 520;
+    let input = low_tone(block * 20);
+    let mut output = vec![0.0_f32; input.len()];
+    for (i, (inp, out)) in input
+        .chunks(block * 2)
+        .zip(output.chunks_mut(block * 2))
+        .enumerate()
+    {
+        if i * block == change {
+            host.set_parameter(
+                0,
+                ParameterId::from("gain_db"),
+                ParameterValue::Float(-20.0),
+            )
+            .unwrap();
+        }
+        host.process(inp, out).unwrap();
+    }
+    output
+}
+
+#[test]
+fn test_gain_plugin_smoothed_change() {
+    // change close to a peak of the 100 Hz tone: worst case for a gain step
+    let change = 5200;
+    let instant = transient_energy(&gain_with_change(0.0, change), 2, change - 100);
+    let smoothed = transient_energy(&gain_with_change(20.0, change), 2, change - 100);
+
+    println!(
+        "Transient energy: instant {:.3e}, smoothed {:.3e}",
+        instant, smoothed
+    );
+    assert!(instant > 1e-4);
+    assert!(
+        smoothed < instant / 100.0,
+        "Smoothing should remove the click of a gain change"
+    );
+
+    // The ramp ends on the new gain
+    let output = gain_with_change(20.0, change);
+    let last = output.len() - 2;
+    let expected =
+        0.1 * (2.0 * std::f32::consts::PI * 100.0 * (last / 2) as f32 / 48000.0).sin() * 0.3;
+    assert!((output[last] - expected).abs() < 1e-4);
+}
+
+#[test]
+fn test_plugin_host_set_parameter_out_of_bounds() {
+    let mut host = PluginHost::new(2, 48000);
+    assert!(
+        host.set_parameter(0, ParameterId::from("gain_db"), ParameterValue::Float(0.0))
+            .is_err()
+    );
+}
@@ -83,18 +83,30 @@ async fn main() -> Result<(), Box<dyn Error>> {
         return run_multi_driver_optimization(&args).await;
     }
 
+    // Check if this is sub + mains integration mode
+    if args.loss == autoeq::LossType::SubMainsFlat {
+        return run_sub_mains_optimization(&args).await;
+    }
+
+    // Check if this is stereo mode (one measurement per channel)
+    if args.curve_right.is_some() {
+        return run_stereo_optimization(&args).await;
+    }
+
     // Load and prepare all input data
     let (standard_freq, input_curve, target_curve, deviation_curve, spin_data) =
         load::load_and_prepare(&args).await?;
 
     // Objective data
-    let (objective_data, use_cea) = autoeq::workflow::setup_objective_data(
+    let (mut objective_data, use_cea) = autoeq::workflow::setup_objective_data(
         &args,
         &input_curve,
         &target_curve,
         &deviation_curve,
         &spin_data,
     );
+    objective_data.multi_position_data =
+        autoeq::workflow::setup_multi_position_data(&args, &target_curve)?;
 
     // Compute pre-optimization metrics
     let pre_metrics = prescore::compute_pre_optimization_metrics(
@@ -106,9 +118,26 @@ async fn main() -> Result<(), Box<dyn Error>> {
     )
     .await?;
 
+    if args.pareto {
+        return run_pareto_optimization(
+            &args,
+            &objective_data,
+            &input_curve,
+            &target_curve,
+            &deviation_curve,
+            &spin_data,
+        )
+        .await;
+    }
+
     // Optimize
     qa_println!(args, "🚀 Starting optimization...");
-    let opt_result = runopt::perform_optimization(&args, &objective_data)?;
+    let (args, opt_result) = if args.auto_filters {
+        runopt::perform_auto_filter_optimization(&args, &objective_data)?
+    } else {
+        let opt_result = runopt::perform_optimization(&args, &objective_data)?;
+        (args, opt_result)
+    };
 
     // Compute post-optimization metrics
     let post_metrics = postscore::compute_post_optimization_metrics(
@@ -121,25 +150,31 @@ async fn main() -> Result<(), Box<dyn Error>> {
         &input_curve,
         &spin_data,
         pre_metrics.cea2034_metrics,
-        pre_metrics.headphone_loss,
+        pre_metrics.headphone_scores,
     )
     .await?;
 
     // Print pre and post optimization scores
     postscore::print_optimization_scores(&args, &post_metrics);
+    print_position_losses(&args, &objective_data, &opt_result.params);
 
     // Extract scores for QA summary
     let (pre_score, post_score) = match objective_data.loss_type {
         autoeq::LossType::HeadphoneFlat | autoeq::LossType::HeadphoneScore => {
             (post_metrics.pre_headphone_loss, post_metrics.headphone_loss)
         }
-        autoeq::LossType::SpeakerFlat | autoeq::LossType::SpeakerScore => (
+        autoeq::LossType::SpeakerFlat
+        | autoeq::LossType::SpeakerScore
+        | autoeq::LossType::PerceptualFlat => (
             post_metrics.pre_cea2034.as_ref().map(|m| m.pref_score),
             post_metrics.cea2034_metrics.as_ref().map(|m| m.pref_score),
         ),
-        autoeq::LossType::DriversFlat => {
-            // Unreachable: DriversFlat mode uses a separate code path
-            unreachable!("DriversFlat mode should not reach this point");
+        autoeq::LossType::SpeakerScoreWithSub => {
+            (post_metrics.pre_with_sub_score, post_metrics.with_sub_score)
+        }
+        autoeq::LossType::DriversFlat | autoeq::LossType::SubMainsFlat => {
+            // Unreachable: crossover modes use a separate code path
+            unreachable!("Crossover modes should not reach this point");
         }
     };
 
@@ -173,6 +208,20 @@ async fn main() -> Result<(), Box<dyn Error>> {
             "Converge: {} | Spacing: {} | Pre: {} | Post: {}",
             converge_str, spacing_str, pre_str, post_str
         );
+        // Headphones: report both preference models, the selected one drives Pre/Post
+        if let (Some(pre), Some(post)) = (
+            post_metrics.pre_headphone_scores,
+            post_metrics.headphone_scores,
+        ) {
+            println!(
+                "Headphone model: {} | Over-ear: {:.3} -> {:.3} | In-ear: {:.3} -> {:.3}",
+                args.effective_headphone_type().name(),
+                pre.over_ear,
+                post.over_ear,
+                pre.in_ear,
+                post.in_ear
+            );
+        }
 
         // Perform additional QA analysis if threshold was provided
         let qa_result = qa::perform_qa_analysis(
@@ -188,18 +237,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
 
     // Normal mode: plot and report
-    let output_path = args.output.clone().unwrap_or_else(|| {
-        let mut path = PathBuf::from(DATA_GENERATED);
-        path.push("autoeq");
-        if let Some(speaker) = &args.speaker {
-            // Use speaker name for default filename
-            let safe_name = speaker.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
-            path.push(format!("autoeq_{}", safe_name));
-        } else {
-            path.push("autoeq_results");
-        }
-        path
-    });
+    let output_path = default_output_path(&args);
 
     qa_println!(args, "📊 Generating plots: {}", output_path.display());
     if let Err(e) = plot::plot_results(
@@ -209,6 +247,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         &target_curve,
         &deviation_curve,
         &spin_data,
+        None,
         &output_path,
     )
     .await
@@ -230,6 +269,234 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Print the flat loss at each listening position before and after optimization
+fn print_position_losses(
+    args: &autoeq::cli::Args,
+    objective_data: &autoeq::optim::ObjectiveData,
+    x: &[f64],
+) {
+    let Some(mp) = &objective_data.multi_position_data else {
+        return;
+    };
+    let freqs = &objective_data.freqs;
+    let peq_spl = autoeq::x2peq::x2spl_with_design(
+        freqs,
+        x,
+        objective_data.srate,
+        objective_data.peq_model,
+        objective_data.biquad_design,
+    );
+    let zero = ndarray::Array1::zeros(freqs.len());
+    let (min_freq, max_freq) = (objective_data.min_freq, objective_data.max_freq);
+    let pre = autoeq::loss::position_losses(mp, freqs, &zero, min_freq, max_freq);
+    let post = autoeq::loss::position_losses(mp, freqs, &peq_spl, min_freq, max_freq);
+
+    qa_println!(
+        args,
+        "📍 Flat loss per listening position ({:?}):",
+        mp.position_loss
+    );
+    for (i, (path, (before, after))) in args.positions.iter().zip(pre.iter().zip(&post)).enumerate()
+    {
+        qa_println!(
+            args,
+            "   Position {} ({}): {:.3} -> {:.3}",
+            i + 1,
+            path.display(),
+            before,
+            after
+        );
+    }
+    let worst = |losses: &[f64]| losses.iter().copied().fold(0.0, f64::max);
+    qa_println!(
+        args,
+        "   Worst position: {:.3} -> {:.3}",
+        worst(&pre),
+        worst(&post)
+    );
+}
+
+/// Compute the Pareto set of loss, number of filters and total positive gain,
+/// then plot it and save one preset per filter count worth using
+async fn run_pareto_optimization(
+    args: &autoeq::cli::Args,
+    objective_data: &autoeq::optim::ObjectiveData,
+    input_curve: &autoeq::Curve,
+    target_curve: &autoeq::Curve,
+    deviation_curve: &autoeq::Curve,
+    spin_data: &Option<std::collections::HashMap<String, autoeq::Curve>>,
+) -> Result<(), Box<dyn Error>> {
+    let (lower_bounds, upper_bounds) = autoeq::workflow::setup_bounds(args);
+    let x0 = autoeq::workflow::initial_guess(args, &lower_bounds, &upper_bounds);
+
+    qa_println!(
+        args,
+        "🚀 Starting multi-objective optimization (up to {} filters)...",
+        args.num_filters
+    );
+    let front = autoeq::pareto::optimize_pareto(
+        &x0,
+        &lower_bounds,
+        &upper_bounds,
+        objective_data.clone(),
+        args,
+    );
+    let best = autoeq::pareto::best_per_filter_count(&front);
+    let Some(best_loss) = best.last() else {
+        return Err("Multi-objective optimization returned an empty Pareto set".into());
+    };
+
+    qa_println!(args, "");
+    qa_println!(args, "📈 Pareto set ({} solutions):", front.len());
+    qa_println!(args, "   Filters |       Loss | Total boost");
+    for solution in &front {
+        qa_println!(
+            args,
+            "   {:7} | {:10.4} | {:+8.2} dB{}",
+            solution.num_filters,
+            solution.loss,
+            solution.total_positive_gain,
+            if best.iter().any(|b| std::ptr::eq(*b, solution)) {
+                "  *"
+            } else {
+                ""
+            }
+        );
+    }
+    qa_println!(args, "   (* best loss for its number of filters)");
+    qa_println!(args, "");
+
+    // Plot the lowest loss solution together with the Pareto set
+    let output_path = default_output_path(args);
+    let mut best_args = args.clone();
+    best_args.num_filters = best_loss.num_filters;
+    qa_println!(args, "📊 Generating plots: {}", output_path.display());
+    if let Err(e) = plot::plot_results(
+        &best_args,
+        &best_loss.x,
+        input_curve,
+        target_curve,
+        deviation_curve,
+        spin_data,
+        Some(&front),
+        &output_path,
+    )
+    .await
+    {
+        qa_eprintln!(args, "⚠️ Warning: Failed to generate plots: {}", e);
+    } else {
+        qa_println!(args, "✅ Plots generated successfully");
+    }
+
+    save::save_pareto_peqs_to_file(args, &best, &output_path, &objective_data.loss_type).await?;
+
+    Ok(())
+}
+
+/// Output path given with `--output`, or a default one in the generated data directory
+fn default_output_path(args: &autoeq::cli::Args) -> PathBuf {
+    args.output.clone().unwrap_or_else(|| {
+        let mut path = PathBuf::from(DATA_GENERATED);
+        path.push("autoeq");
+        if let Some(speaker) = &args.speaker {
+            // Use speaker name for default filename
+            let safe_name = speaker.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
+            path.push(format!("autoeq_{}", safe_name));
+        } else {
+            path.push("autoeq_results");
+        }
+        path
+    })
+}
+
+/// Run one optimization per channel from left (`--curve`) and right
+/// (`--curve-right`) measurements and save dual-channel presets
+async fn run_stereo_optimization(args: &autoeq::cli::Args) -> Result<(), Box<dyn Error>> {
+    let output_path = default_output_path(args);
+    let file_name = output_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "autoeq_results".to_string());
+
+    let mut channel_params = Vec::new();
+    let mut loss_type = args.loss;
+    for (side, curve) in [("left", &args.curve), ("right", &args.curve_right)] {
+        qa_println!(args, "🎧 Optimizing {} channel", side);
+        let mut channel_args = args.clone();
+        channel_args.curve = curve.clone();
+        channel_args.curve_right = None;
+
+        let (standard_freq, input_curve, target_curve, deviation_curve, spin_data) =
+            load::load_and_prepare(&channel_args).await?;
+        let (objective_data, use_cea) = autoeq::workflow::setup_objective_data(
+            &channel_args,
+            &input_curve,
+            &target_curve,
+            &deviation_curve,
+            &spin_data,
+        );
+        let pre_metrics = prescore::compute_pre_optimization_metrics(
+            &channel_args,
+            &objective_data,
+            use_cea,
+            &deviation_curve,
+            &spin_data,
+        )
+        .await?;
+
+        qa_println!(args, "🚀 Starting optimization...");
+        let opt_result = runopt::perform_optimization(&channel_args, &objective_data)?;
+
+        let post_metrics = postscore::compute_post_optimization_metrics(
+            &channel_args,
+            &objective_data,
+            use_cea,
+            &opt_result.params,
+            &standard_freq,
+            &target_curve,
+            &input_curve,
+            &spin_data,
+            pre_metrics.cea2034_metrics,
+            pre_metrics.headphone_scores,
+        )
+        .await?;
+        postscore::print_optimization_scores(&channel_args, &post_metrics);
+
+        let channel_path = output_path.with_file_name(format!("{}_{}", file_name, side));
+        qa_println!(args, "📊 Generating plots: {}", channel_path.display());
+        if let Err(e) = plot::plot_results(
+            &channel_args,
+            &opt_result.params,
+            &input_curve,
+            &target_curve,
+            &deviation_curve,
+            &spin_data,
+            None,
+            &channel_path,
+        )
+        .await
+        {
+            qa_eprintln!(args, "⚠️ Warning: Failed to generate plots: {}", e);
+        } else {
+            qa_println!(args, "✅ Plots generated successfully");
+        }
+
+        loss_type = objective_data.loss_type;
+        channel_params.push(opt_result.params);
+    }
+
+    save::save_stereo_peq_to_file(
+        args,
+        &channel_params[0],
+        &channel_params[1],
+        &output_path,
+        &loss_type,
+    )
+    .await?;
+
+    Ok(())
+}
+
 /// Run multi-driver crossover optimization
 async fn run_multi_driver_optimization(args: &autoeq::cli::Args) -> Result<(), Box<dyn Error>> {
     qa_println!(args, "🎵 Multi-driver crossover optimization mode");
@@ -243,8 +510,8 @@ async fn run_multi_driver_optimization(args: &autoeq::cli::Args) -> Result<(), B
         let (min_f, max_f) = driver.freq_range();
         qa_println!(
             args,
-            "   Driver {}: {:.0} Hz - {:.0} Hz (mean: {:.0} Hz)",
-            i + 1,
+            "   {}: {:.0} Hz - {:.0} Hz (mean: {:.0} Hz)",
+            drivers_data.driver_name(i),
             min_f,
             max_f,
             driver.mean_freq()
@@ -262,7 +529,7 @@ async fn run_multi_driver_optimization(args: &autoeq::cli::Args) -> Result<(), B
     qa_println!(args, "🎯 Optimization parameters:");
     qa_println!(
         args,
-        "   {} driver gains + {} crossover frequencies = {} parameters",
+        "   {} driver gains + {} crossover frequencies + delays + polarities = {} parameters",
         objective_data.drivers_data.as_ref().unwrap().drivers.len(),
         objective_data.drivers_data.as_ref().unwrap().drivers.len() - 1,
         bounds.0.len()
@@ -280,13 +547,9 @@ async fn run_multi_driver_optimization(args: &autoeq::cli::Args) -> Result<(), B
     let opt_result = runopt::perform_optimization_with_bounds(args, &objective_data, Some(bounds))?;
 
     // Extract results
-    let n_drivers = objective_data.drivers_data.as_ref().unwrap().drivers.len();
-    let gains = &opt_result.params[0..n_drivers];
-    let xover_freqs_log10 = &opt_result.params[n_drivers..];
-    let xover_freqs: Vec<f64> = xover_freqs_log10
-        .iter()
-        .map(|f| 10.0_f64.powf(*f))
-        .collect();
+    let drivers_data = objective_data.drivers_data.as_ref().unwrap();
+    let n_drivers = drivers_data.drivers.len();
+    let params = autoeq::DriversParams::from_x(n_drivers, &opt_result.params);
 
     // Display results
     qa_println!(args, "");
@@ -294,28 +557,34 @@ async fn run_multi_driver_optimization(args: &autoeq::cli::Args) -> Result<(), B
     qa_println!(args, "");
     qa_println!(args, "📊 Results:");
     qa_println!(args, "");
-    qa_println!(args, "Driver Gains:");
-    for (i, gain) in gains.iter().enumerate() {
-        qa_println!(args, "   Driver {}: {:+.2} dB", i + 1, gain);
+    qa_println!(args, "Driver Gains, Delays and Polarities:");
+    for i in 0..n_drivers {
+        qa_println!(
+            args,
+            "   {}: {:+.2} dB, {:.3} ms, {}",
+            drivers_data.driver_name(i),
+            params.gains[i],
+            params.delays_ms[i],
+            if params.inverted[i] {
+                "inverted"
+            } else {
+                "normal"
+            }
+        );
     }
     qa_println!(args, "");
-    qa_println!(args, "Crossover Frequencies:");
-    for (i, freq) in xover_freqs.iter().enumerate() {
+    qa_println!(args, "Crossovers:");
+    for (i, freq) in params.crossover_freqs.iter().enumerate() {
         qa_println!(
             args,
-            "   Between Driver {} and {}: {:.0} Hz",
-            i + 1,
-            i + 2,
-            freq
+            "   Between {} and {}: {:.0} Hz ({})",
+            drivers_data.driver_name(i),
+            drivers_data.driver_name(i + 1),
+            freq,
+            drivers_data.crossovers[i]
         );
     }
     qa_println!(args, "");
-    qa_println!(
-        args,
-        "Crossover Type: {:?}",
-        objective_data.drivers_data.as_ref().unwrap().crossover_type
-    );
-    qa_println!(args, "");
 
     // Compute pre and post objective values
     if let (Some(pre_obj), Some(post_obj)) = (opt_result.pre_objective, opt_result.post_objective) {
@@ -339,13 +608,9 @@ async fn run_multi_driver_optimization(args: &autoeq::cli::Args) -> Result<(), B
     });
 
     qa_println!(args, "📊 Generating plots: {}", output_path.display());
-    if let Err(e) = autoeq::plot::plot_drivers_results(
-        objective_data.drivers_data.as_ref().unwrap(),
-        gains,
-        &xover_freqs,
-        args.sample_rate,
-        &output_path,
-    ) {
+    if let Err(e) =
+        autoeq::plot::plot_drivers_results(drivers_data, &params, args.sample_rate, &output_path)
+    {
         qa_eprintln!(args, "⚠️ Warning: Failed to generate plots: {}", e);
     } else {
         qa_println!(args, "✅ Plots generated successfully");
@@ -370,6 +635,93 @@ async fn run_multi_driver_optimization(args: &autoeq::cli::Args) -> Result<(), B
     Ok(())
 }
 
+/// Integrate a subwoofer with the mains: optimize the crossover, the sub
+/// gain, delay and polarity and a PEQ on each side
+async fn run_sub_mains_optimization(args: &autoeq::cli::Args) -> Result<(), Box<dyn Error>> {
+    let sub_mains_data = load::load_sub_mains_data(args)?;
+    let objective_data = autoeq::workflow::setup_sub_mains_objective_data(args, sub_mains_data);
+    let data = objective_data.sub_mains_data.as_ref().unwrap();
+    let bounds = autoeq::workflow::setup_sub_mains_bounds(args, data);
+
+    qa_println!(args, "🎯 Optimization parameters:");
+    qa_println!(
+        args,
+        "   crossover + sub gain + delay + polarity, {} mains filters, {} sub filters = {} parameters",
+        data.num_mains_filters,
+        data.num_sub_filters,
+        bounds.0.len()
+    );
+    qa_println!(
+        args,
+        "   Delay bounds: [{:.1}, {:.1}] ms",
+        -args.sub_max_delay_ms,
+        args.sub_max_delay_ms
+    );
+    qa_println!(args, "");
+
+    qa_println!(args, "🚀 Starting optimization...");
+    let opt_result = runopt::perform_optimization_with_bounds(args, &objective_data, Some(bounds))?;
+    let params = autoeq::SubMainsParams::from_x(data, &opt_result.params, args.sample_rate);
+
+    qa_println!(args, "");
+    qa_println!(args, "✅ Optimization complete!");
+    qa_println!(args, "");
+    qa_println!(args, "📊 Results:");
+    qa_println!(
+        args,
+        "   Crossover: {:.0} Hz ({})",
+        params.crossover_freq,
+        data.crossover
+    );
+    qa_println!(args, "   Sub gain: {:+.2} dB", params.sub_gain_db);
+    if params.sub_delay_ms >= 0.0 {
+        qa_println!(args, "   Delay: sub by {:.2} ms", params.sub_delay_ms);
+    } else {
+        qa_println!(args, "   Delay: mains by {:.2} ms", -params.sub_delay_ms);
+    }
+    qa_println!(
+        args,
+        "   Sub polarity: {}",
+        if params.sub_inverted {
+            "inverted"
+        } else {
+            "normal"
+        }
+    );
+    qa_println!(args, "");
+    qa_println!(args, "Mains PEQ:");
+    autoeq::iir::peq_print(&params.mains_peq);
+    qa_println!(args, "Sub PEQ:");
+    autoeq::iir::peq_print(&params.sub_peq);
+
+    if let (Some(pre_obj), Some(post_obj)) = (opt_result.pre_objective, opt_result.post_objective) {
+        qa_println!(args, "Loss (deviation from flat):");
+        qa_println!(args, "   Before optimization: {:.6}", pre_obj);
+        qa_println!(args, "   After optimization:  {:.6}", post_obj);
+    }
+
+    qa_println!(args, "");
+    let output_path = args.output.clone().unwrap_or_else(|| {
+        let mut path = PathBuf::from(DATA_GENERATED);
+        path.push("autoeq");
+        path.push("sub_mains_results");
+        path
+    });
+
+    qa_println!(args, "📊 Generating plots: {}", output_path.display());
+    if let Err(e) =
+        autoeq::plot::plot_sub_mains_results(data, &params, args.sample_rate, &output_path)
+    {
+        qa_eprintln!(args, "⚠️ Warning: Failed to generate plots: {}", e);
+    } else {
+        qa_println!(args, "✅ Plots generated successfully");
+    }
+
+    save::save_sub_mains_to_file(args, data, &params, &output_path).await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use autoeq::cli::Args;
@@ -426,7 +778,7 @@ mod tests {
             spl,
         };
 
-        let target_curve = autoeq::workflow::build_target_curve(&args, &freqs, &curve);
+        let target_curve = autoeq::workflow::build_target_curve(&args, &freqs, &curve, None);
         // Since SPL is zero, target_curve.spl == base_target
         assert!((target_curve.spl[0] - 0.0).abs() < 1e-12);
         assert!((target_curve.spl[1] - 0.0).abs() < 1e-12);
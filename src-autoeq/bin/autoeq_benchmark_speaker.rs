@@ -417,7 +417,8 @@ async fn run_one(
     let standard_freq = autoeq::read::create_log_frequency_grid(200, 20.0, 20000.0);
     let input_curve_normalized =
         autoeq::read::normalize_and_interpolate_response(&standard_freq, &input_curve);
-    let target_curve = build_target_curve(args, &standard_freq, &input_curve);
+    let target_curve =
+        build_target_curve(args, &standard_freq, &input_curve, spin_data_raw.as_ref());
     let deviation_curve = autoeq::Curve {
         freq: target_curve.freq.clone(),
         spl: &target_curve.spl - &input_curve_normalized.spl,
@@ -450,11 +451,12 @@ async fn run_one(
 
     if use_cea {
         let freq = &standard_freq;
-        let peq_after = autoeq::x2peq::compute_peq_response_from_x(
+        let peq_after = autoeq::x2peq::x2spl_with_design(
             freq,
             &x,
             args.sample_rate,
             args.effective_peq_model(),
+            args.biquad_design,
         );
         let metrics =
             score::compute_cea2034_metrics(freq, spin_data.as_ref().unwrap(), Some(&peq_after))
@@ -478,8 +480,9 @@ fn build_target_curve(
     args: &autoeq::cli::Args,
     standard_freq: &Array1<f64>,
     input_curve: &autoeq::Curve,
+    spin_data: Option<&HashMap<String, autoeq::Curve>>,
 ) -> autoeq::Curve {
-    autoeq::workflow::build_target_curve(args, standard_freq, input_curve)
+    autoeq::workflow::build_target_curve(args, standard_freq, input_curve, spin_data)
 }
 
 fn setup_objective_data(
@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use autoeq::read;
+use autoeq_env::DATA_CACHED;
+use clap::Parser;
+
+/// Import speaker measurements from a local copy of the spinorama dataset
+///
+/// Works without network access: the measurements are read from a directory,
+/// a zip archive or a tarball and stored like the `download` command does, so
+/// that `--speaker` and the benchmark find them in the cache.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directory, zip archive or tarball (.tar, .tar.gz, .tgz) with the Plotly JSON
+    /// measurements and metadata.json
+    source: PathBuf,
+
+    /// Measurements to import (comma separated)
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        default_value = "CEA2034,Estimated In-Room Response"
+    )]
+    measurement: Vec<String>,
+
+    /// Import only measurements for a specific speaker (case-insensitive substring match)
+    #[arg(short, long)]
+    speaker: Option<String>,
+
+    /// Overwrite measurements already in the cache
+    #[arg(short, long, default_value_t = false)]
+    force: bool,
+
+    /// Cache directory
+    #[arg(long, default_value = DATA_CACHED)]
+    cache_dir: PathBuf,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    let report = read::ingest_spinorama_dataset(
+        &args.source,
+        &args.cache_dir,
+        &args.measurement,
+        args.speaker.as_deref(),
+        args.force,
+    )?;
+
+    for (path, reason) in &report.invalid {
+        eprintln!("[WARN] Skipping '{}': {}", path.display(), reason);
+    }
+    println!(
+        "Imported {} speaker(s): {} file(s) written, {} already cached (use --force to overwrite), {} invalid",
+        report.speakers,
+        report.written,
+        report.skipped,
+        report.invalid.len()
+    );
+
+    let index = read::load_speaker_index(&args.cache_dir)?;
+    println!(
+        "Indexed {} speaker(s) in {}",
+        index.len(),
+        args.cache_dir.join(read::SPEAKER_INDEX_FILE).display()
+    );
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use autoeq::Curve;
-use autoeq::loss::{CrossoverType, DriverMeasurement, DriversLossData};
+use autoeq::loss::{Crossover, DriverMeasurement, DriversLossData, SubMainsLossData};
 use autoeq::read;
 use std::collections::HashMap;
 
@@ -55,8 +55,12 @@ pub(super) async fn load_and_prepare(
     let input_curve = read::normalize_and_interpolate_response(&standard_freq, &input_curve_raw);
 
     // Build/Get target and interpolate it
-    let target_curve_raw =
-        autoeq::workflow::build_target_curve(args, &standard_freq, &input_curve_raw);
+    let target_curve_raw = autoeq::workflow::build_target_curve(
+        args,
+        &standard_freq,
+        &input_curve_raw,
+        spin_data_raw.as_ref(),
+    );
     let target_curve = read::interpolate_log_space(&standard_freq, &target_curve_raw);
 
     // Compute and interpolate deviation curve
@@ -96,29 +100,28 @@ pub(super) async fn load_and_prepare(
 pub(super) async fn load_drivers_data(
     args: &autoeq::cli::Args,
 ) -> Result<DriversLossData, Box<dyn std::error::Error>> {
-    // Collect driver file paths
-    let driver_paths: Vec<_> = [&args.driver1, &args.driver2, &args.driver3, &args.driver4]
-        .iter()
-        .filter_map(|p| p.as_ref())
-        .collect();
-
-    if driver_paths.len() < 2 {
+    let driver_specs = args.driver_specs();
+    if driver_specs.len() < 2 {
         return Err("At least 2 driver files are required for multi-driver optimization".into());
     }
 
     // Load driver measurements
     let mut drivers = Vec::new();
-    for (i, path) in driver_paths.iter().enumerate() {
-        match read::load_driver_measurement(path) {
+    for (i, spec) in driver_specs.iter().enumerate() {
+        match read::load_driver_measurement(&spec.path) {
             Ok((freq, spl, phase)) => {
-                drivers.push(DriverMeasurement::new(freq, spl, phase));
-                eprintln!("✓ Loaded driver {} from {}", i + 1, path.display());
+                let mut driver = DriverMeasurement::new(freq, spl, phase);
+                if let Some(name) = &spec.name {
+                    driver = driver.with_name(name.clone());
+                }
+                drivers.push(driver);
+                eprintln!("✓ Loaded driver {} from {}", i + 1, spec.path.display());
             }
             Err(e) => {
                 return Err(format!(
                     "Failed to load driver {} from {}: {}",
                     i + 1,
-                    path.display(),
+                    spec.path.display(),
                     e
                 )
                 .into());
@@ -126,28 +129,57 @@ pub(super) async fn load_drivers_data(
         }
     }
 
-    // Parse crossover type
-    let crossover_type = match args.crossover_type.as_str() {
-        "butterworth2" => CrossoverType::Butterworth2,
-        "linkwitzriley2" => CrossoverType::LinkwitzRiley2,
-        "linkwitzriley4" => CrossoverType::LinkwitzRiley4,
-        other => {
-            return Err(format!(
-                "Unknown crossover type '{}'. Valid types: butterworth2, linkwitzriley2, linkwitzriley4",
-                other
-            )
-            .into());
-        }
-    };
+    let crossovers = Crossover::parse_list(&args.crossover_type, drivers.len() - 1)?;
 
     // Create DriversLossData (this will sort drivers and create freq grid)
-    let drivers_data = DriversLossData::new(drivers, crossover_type);
+    let drivers_data = DriversLossData::with_crossovers(drivers, crossovers);
 
+    let crossovers: Vec<_> = drivers_data
+        .crossovers
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
     eprintln!(
-        "✓ Initialized {} drivers with {:?} crossover",
+        "✓ Initialized {} drivers with {} crossovers",
         drivers_data.drivers.len(),
-        drivers_data.crossover_type
+        crossovers.join(", ")
     );
 
     Ok(drivers_data)
 }
+
+/// Load the mains and subwoofer measurements for sub + mains integration
+///
+/// # Arguments
+/// * `args` - CLI arguments containing `--mains`, `--sub` and crossover type
+///
+/// # Returns
+/// * SubMainsLossData with both complex responses on a common grid
+pub(super) fn load_sub_mains_data(
+    args: &autoeq::cli::Args,
+) -> Result<SubMainsLossData, Box<dyn std::error::Error>> {
+    let load = |path: &std::path::PathBuf,
+                name: &str|
+     -> Result<DriverMeasurement, Box<dyn std::error::Error>> {
+        let (freq, spl, phase) = read::load_driver_measurement(path)
+            .map_err(|e| format!("Failed to load {} from {}: {}", name, path.display(), e))?;
+        eprintln!("✓ Loaded {} from {}", name, path.display());
+        Ok(DriverMeasurement::new(freq, spl, phase))
+    };
+    let mains = load(args.mains.as_ref().ok_or("--mains is required")?, "mains")?;
+    let sub = load(args.sub.as_ref().ok_or("--sub is required")?, "sub")?;
+
+    let data = SubMainsLossData::new(
+        &mains,
+        &sub,
+        Crossover::parse_list(&args.crossover_type, 1)?[0],
+        args.num_filters,
+        args.sub_num_filters,
+    )?
+    .with_biquad_design(args.biquad_design);
+    eprintln!(
+        "✓ Initialized sub + mains with {} crossover",
+        data.crossover
+    );
+    Ok(data)
+}
@@ -1,6 +1,9 @@
 use autoeq::Curve;
 use autoeq::cea2034 as score;
 use autoeq::loss;
+use autoeq::score_sensitivity::{
+    MetricInterval, ScoreSensitivity, SensitivityConfig, score_sensitivity,
+};
 use std::collections::HashMap;
 
 /// Post-optimization metrics for CEA2034 or headphone loss
@@ -9,6 +12,11 @@ pub(super) struct PostOptMetrics {
     pub(super) headphone_loss: Option<f64>,
     pub(super) pre_cea2034: Option<score::ScoreMetrics>,
     pub(super) pre_headphone_loss: Option<f64>,
+    pub(super) headphone_scores: Option<loss::HeadphoneScores>,
+    pub(super) pre_headphone_scores: Option<loss::HeadphoneScores>,
+    pub(super) score_sensitivity: Option<ScoreSensitivity>,
+    pub(super) with_sub_score: Option<f64>,
+    pub(super) pre_with_sub_score: Option<f64>,
 }
 
 /// Compute post-optimization metrics and compare with pre-optimization
@@ -22,18 +30,22 @@ pub(super) async fn compute_post_optimization_metrics(
     input_curve: &Curve,
     spin_data: &Option<HashMap<String, Curve>>,
     pre_cea2034_metrics: Option<score::ScoreMetrics>,
-    pre_headphone_loss: Option<f64>,
+    pre_headphone_scores: Option<loss::HeadphoneScores>,
 ) -> Result<PostOptMetrics, Box<dyn std::error::Error>> {
     let mut cea2034_metrics: Option<score::ScoreMetrics> = None;
-    let mut headphone_loss_val: Option<f64> = None;
+    let mut headphone_scores: Option<loss::HeadphoneScores> = None;
+    let mut sensitivity: Option<ScoreSensitivity> = None;
+    let mut with_sub_score: Option<f64> = None;
+    let mut pre_with_sub_score: Option<f64> = None;
 
     match objective_data.loss_type {
         autoeq::LossType::HeadphoneFlat | autoeq::LossType::HeadphoneScore => {
-            let peq_after = autoeq::x2peq::compute_peq_response_from_x(
+            let peq_after = autoeq::x2peq::x2spl_with_design(
                 standard_freq,
                 opt_params,
                 args.sample_rate,
                 args.effective_peq_model(),
+                args.biquad_design,
             );
             // Compute remaining deviation from target after applying PEQ
             // Use same convention as deviation_curve: target - corrected
@@ -42,16 +54,20 @@ pub(super) async fn compute_post_optimization_metrics(
                 freq: standard_freq.clone(),
                 spl: &target_curve.spl - &input_curve.spl - &peq_after,
             };
-            headphone_loss_val = Some(loss::headphone_loss(&deviation_after));
+            headphone_scores = Some(loss::HeadphoneScores::new(&deviation_after));
         }
-        autoeq::LossType::SpeakerFlat | autoeq::LossType::SpeakerScore => {
+        autoeq::LossType::SpeakerFlat
+        | autoeq::LossType::SpeakerScore
+        | autoeq::LossType::PerceptualFlat
+        | autoeq::LossType::SpeakerScoreWithSub => {
             if use_cea {
                 let freq = &objective_data.freqs;
-                let peq_after = autoeq::x2peq::compute_peq_response_from_x(
+                let peq_after = autoeq::x2peq::x2spl_with_design(
                     freq,
                     opt_params,
                     args.sample_rate,
                     args.effective_peq_model(),
+                    args.biquad_design,
                 );
                 let metrics = score::compute_cea2034_metrics(
                     freq,
@@ -60,19 +76,54 @@ pub(super) async fn compute_post_optimization_metrics(
                 )
                 .await?;
                 cea2034_metrics = Some(metrics);
+                if objective_data.loss_type == autoeq::LossType::SpeakerScoreWithSub
+                    && let Some(sd) = &objective_data.speaker_score_data
+                {
+                    let no_peq = ndarray::Array1::zeros(freq.len());
+                    pre_with_sub_score = Some(loss::speaker_score_with_sub(
+                        sd,
+                        freq,
+                        &no_peq,
+                        args.sub_crossover,
+                    ));
+                    with_sub_score = Some(loss::speaker_score_with_sub(
+                        sd,
+                        freq,
+                        &peq_after,
+                        args.sub_crossover,
+                    ));
+                }
+                if args.score_sensitivity {
+                    let config = SensitivityConfig {
+                        seed: args.seed,
+                        ..SensitivityConfig::default()
+                    };
+                    sensitivity = Some(score_sensitivity(
+                        freq,
+                        spin_data.as_ref().unwrap(),
+                        Some(&peq_after),
+                        &config,
+                    )?);
+                }
             }
         }
-        autoeq::LossType::DriversFlat => {
-            // Unreachable: DriversFlat mode uses a separate code path
-            unreachable!("DriversFlat mode should not reach this point");
+        autoeq::LossType::DriversFlat | autoeq::LossType::SubMainsFlat => {
+            // Unreachable: crossover modes use a separate code path
+            unreachable!("Crossover modes should not reach this point");
         }
     }
 
+    let headphone_type = args.effective_headphone_type();
     Ok(PostOptMetrics {
         cea2034_metrics,
-        headphone_loss: headphone_loss_val,
+        headphone_loss: headphone_scores.map(|s| s.get(headphone_type)),
         pre_cea2034: pre_cea2034_metrics,
-        pre_headphone_loss,
+        pre_headphone_loss: pre_headphone_scores.map(|s| s.get(headphone_type)),
+        headphone_scores,
+        pre_headphone_scores,
+        score_sensitivity: sensitivity,
+        with_sub_score,
+        pre_with_sub_score,
     })
 }
 
@@ -80,40 +131,106 @@ pub(super) async fn compute_post_optimization_metrics(
 pub(super) fn print_optimization_scores(args: &autoeq::cli::Args, post: &PostOptMetrics) {
     match args.loss {
         autoeq::LossType::HeadphoneFlat | autoeq::LossType::HeadphoneScore => {
-            if let Some(before) = post.pre_headphone_loss {
-                autoeq::qa_println!(args, "✅  Pre-Optimization Headphone Score: {:.3}", before);
+            if let Some(before) = post.pre_headphone_scores {
+                autoeq::qa_println!(
+                    args,
+                    "✅  Pre-Optimization Headphone Score: over-ear={:.3} in-ear={:.3}",
+                    before.over_ear,
+                    before.in_ear
+                );
             }
-            if let Some(after) = post.headphone_loss {
-                autoeq::qa_println!(args, "✅ Post-Optimization Headphone Score: {:.3}", after);
+            if let Some(after) = post.headphone_scores {
+                autoeq::qa_println!(
+                    args,
+                    "✅ Post-Optimization Headphone Score: over-ear={:.3} in-ear={:.3}",
+                    after.over_ear,
+                    after.in_ear
+                );
             }
         }
-        autoeq::LossType::SpeakerFlat | autoeq::LossType::SpeakerScore => {
+        autoeq::LossType::SpeakerFlat
+        | autoeq::LossType::SpeakerScore
+        | autoeq::LossType::PerceptualFlat
+        | autoeq::LossType::SpeakerScoreWithSub => {
             if let Some(before) = &post.pre_cea2034 {
                 autoeq::qa_println!(
                     args,
-                    "✅  Pre-Optimization CEA2034 Score: pref={:.3} | nbd_on={:.3} nbd_pir={:.3} lfx={:.0}Hz sm_pir={:.3}",
+                    "✅  Pre-Optimization CEA2034 Score: pref={:.3} with sub={:.3} | nbd_on={:.3} nbd_pir={:.3} lfx={:.0}Hz sm_pir={:.3}",
                     before.pref_score,
+                    post.pre_with_sub_score.unwrap_or(before.pref_score_wsub),
                     before.nbd_on,
                     before.nbd_pir,
                     10f64.powf(before.lfx),
                     before.sm_pir
                 );
+                if let Some(lfx_hz) = args.sub_lfx {
+                    autoeq::qa_println!(
+                        args,
+                        "✅  Pre-Optimization CEA2034 Score with a {:.0} Hz sub: {:.3}",
+                        lfx_hz,
+                        before.pref_score_with_lfx(lfx_hz)
+                    );
+                }
             }
             if let Some(after) = &post.cea2034_metrics {
                 autoeq::qa_println!(
                     args,
-                    "✅ Post-Optimization CEA2034 Score: pref={:.3} | nbd_on={:.3} nbd_pir={:.3} lfx={:.0}hz sm_pir={:.3}",
+                    "✅ Post-Optimization CEA2034 Score: pref={:.3} with sub={:.3} | nbd_on={:.3} nbd_pir={:.3} lfx={:.0}hz sm_pir={:.3}",
                     after.pref_score,
+                    post.with_sub_score.unwrap_or(after.pref_score_wsub),
                     after.nbd_on,
                     after.nbd_pir,
                     10f64.powf(after.lfx),
                     after.sm_pir
                 );
+                if let Some(lfx_hz) = args.sub_lfx {
+                    autoeq::qa_println!(
+                        args,
+                        "✅ Post-Optimization CEA2034 Score with a {:.0} Hz sub: {:.3}",
+                        lfx_hz,
+                        after.pref_score_with_lfx(lfx_hz)
+                    );
+                }
+            }
+            if let Some(sensitivity) = &post.score_sensitivity {
+                print_score_sensitivity(args, sensitivity);
             }
         }
-        autoeq::LossType::DriversFlat => {
-            // Unreachable: DriversFlat mode uses a separate code path
-            unreachable!("DriversFlat mode should not reach this point");
+        autoeq::LossType::DriversFlat | autoeq::LossType::SubMainsFlat => {
+            // Unreachable: crossover modes use a separate code path
+            unreachable!("Crossover modes should not reach this point");
         }
     }
 }
+
+/// Print the confidence intervals of the CEA2034 sub-metrics and the costliest bands
+fn print_score_sensitivity(args: &autoeq::cli::Args, sensitivity: &ScoreSensitivity) {
+    let row = |name: &str, m: &MetricInterval, to_display: fn(f64) -> f64| {
+        autoeq::qa_println!(
+            args,
+            "   {:<8} {:>8.3}  mean {:>8.3}  95% CI [{:.3}, {:.3}]",
+            name,
+            to_display(m.nominal),
+            to_display(m.mean),
+            to_display(m.low),
+            to_display(m.high)
+        );
+    };
+    autoeq::qa_println!(args, "📏 Score sensitivity (post-optimization):");
+    row("pref", &sensitivity.pref_score, |v| v);
+    row("nbd_on", &sensitivity.nbd_on, |v| v);
+    row("nbd_pir", &sensitivity.nbd_pir, |v| v);
+    row("lfx(Hz)", &sensitivity.lfx, |v| 10f64.powf(v));
+    row("sm_pir", &sensitivity.sm_pir, |v| v);
+    autoeq::qa_println!(args, "   Bands costing the most score points:");
+    for band in sensitivity.bands.iter().take(5) {
+        autoeq::qa_println!(
+            args,
+            "   {:>7.0} Hz  -{:.3}  (nbd_on={:.2} dB nbd_pir={:.2} dB)",
+            band.center,
+            band.penalty,
+            band.nbd_on,
+            band.nbd_pir
+        );
+    }
+}
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 /// Pre-optimization metrics for CEA2034 or headphone loss
 pub(super) struct PreOptMetrics {
     pub(super) cea2034_metrics: Option<score::ScoreMetrics>,
-    pub(super) headphone_loss: Option<f64>,
+    pub(super) headphone_scores: Option<loss::HeadphoneScores>,
 }
 
 /// Compute pre-optimization metrics
@@ -18,14 +18,17 @@ pub(super) async fn compute_pre_optimization_metrics(
     spin_data: &Option<HashMap<String, Curve>>,
 ) -> Result<PreOptMetrics, Box<dyn std::error::Error>> {
     let mut cea2034_metrics: Option<score::ScoreMetrics> = None;
-    let mut headphone_loss_val: Option<f64> = None;
+    let mut headphone_scores: Option<loss::HeadphoneScores> = None;
 
     match objective_data.loss_type {
         autoeq::LossType::HeadphoneFlat | autoeq::LossType::HeadphoneScore => {
-            // headphone_loss expects deviation from Harman target, not raw curve
-            headphone_loss_val = Some(loss::headphone_loss(deviation_curve));
+            // headphone scores expect deviation from Harman target, not raw curve
+            headphone_scores = Some(loss::HeadphoneScores::new(deviation_curve));
         }
-        autoeq::LossType::SpeakerFlat | autoeq::LossType::SpeakerScore => {
+        autoeq::LossType::SpeakerFlat
+        | autoeq::LossType::SpeakerScore
+        | autoeq::LossType::PerceptualFlat
+        | autoeq::LossType::SpeakerScoreWithSub => {
             if use_cea {
                 let metrics = score::compute_cea2034_metrics(
                     &objective_data.freqs,
@@ -36,14 +39,14 @@ pub(super) async fn compute_pre_optimization_metrics(
                 cea2034_metrics = Some(metrics);
             }
         }
-        autoeq::LossType::DriversFlat => {
-            // Unreachable: DriversFlat mode uses a separate code path
-            unreachable!("DriversFlat mode should not reach this point");
+        autoeq::LossType::DriversFlat | autoeq::LossType::SubMainsFlat => {
+            // Unreachable: crossover modes use a separate code path
+            unreachable!("Crossover modes should not reach this point");
         }
     }
 
     Ok(PreOptMetrics {
         cea2034_metrics,
-        headphone_loss: headphone_loss_val,
+        headphone_scores,
     })
 }
@@ -26,11 +26,18 @@ pub(super) fn perform_optimization_with_bounds(
         bounds.unwrap_or_else(|| autoeq::workflow::setup_bounds(args));
 
     // Generate initial guess based on loss type
-    let mut x = if objective_data.loss_type == autoeq::LossType::DriversFlat {
-        let n_drivers = objective_data.drivers_data.as_ref().unwrap().drivers.len();
-        autoeq::workflow::drivers_initial_guess(&lower_bounds, &upper_bounds, n_drivers)
-    } else {
-        autoeq::workflow::initial_guess(args, &lower_bounds, &upper_bounds)
+    let mut x = match objective_data.loss_type {
+        autoeq::LossType::DriversFlat => {
+            let n_drivers = objective_data.drivers_data.as_ref().unwrap().drivers.len();
+            autoeq::workflow::drivers_initial_guess(&lower_bounds, &upper_bounds, n_drivers)
+        }
+        autoeq::LossType::SubMainsFlat => autoeq::workflow::sub_mains_initial_guess(
+            args,
+            objective_data.sub_mains_data.as_ref().unwrap(),
+            &lower_bounds,
+            &upper_bounds,
+        ),
+        _ => autoeq::workflow::initial_guess(args, &lower_bounds, &upper_bounds),
     };
 
     // Calculate pre-optimization objective value
@@ -64,7 +71,7 @@ pub(super) fn perform_optimization_with_bounds(
             );
             converged = true;
             post_objective = Some(val);
-            if args.qa.is_none() && objective_data.loss_type != autoeq::LossType::DriversFlat {
+            if args.qa.is_none() && objective_data.loss_type.uses_peq_layout() {
                 print_freq_spacing(&x, args, "global");
             }
         }
@@ -96,7 +103,7 @@ pub(super) fn perform_optimization_with_bounds(
                 // Update convergence status based on local refinement
                 converged = true;
                 post_objective = Some(local_val);
-                if args.qa.is_none() && objective_data.loss_type != autoeq::LossType::DriversFlat {
+                if args.qa.is_none() && objective_data.loss_type.uses_peq_layout() {
                     print_freq_spacing(&x, args, "local");
                     autoeq::x2peq::peq_print_from_x(&x, args.effective_peq_model());
                 }
@@ -116,3 +123,64 @@ pub(super) fn perform_optimization_with_bounds(
         post_objective: post_objective,
     })
 }
+
+/// Optimize with an increasing number of filters and keep the selected count
+///
+/// # Returns
+/// * Arguments with `num_filters` set to the selected count and its optimization result
+pub(super) fn perform_auto_filter_optimization(
+    args: &autoeq::cli::Args,
+    objective_data: &ObjectiveData,
+) -> Result<(autoeq::cli::Args, OptimizationResult), Box<dyn Error>> {
+    let search = autoeq::auto_filters::optimize_filter_count(
+        objective_data,
+        args,
+        args.auto_filters_min_gain,
+    )
+    .map_err(std::io::Error::other)?;
+    let best = search.best();
+
+    autoeq::qa_println!(args, "");
+    autoeq::qa_println!(
+        args,
+        "📈 Loss vs number of filters (min gain {:.3}):",
+        args.auto_filters_min_gain
+    );
+    autoeq::qa_println!(args, "   Filters |       Loss |       Gain");
+    for step in &search.steps {
+        autoeq::qa_println!(
+            args,
+            "   {:7} | {:10.4} | {}{}",
+            step.num_filters,
+            step.loss,
+            step.gain
+                .map_or_else(|| format!("{:>10}", "-"), |g| format!("{:10.4}", g)),
+            if step.num_filters == best.num_filters {
+                "  *"
+            } else {
+                ""
+            }
+        );
+    }
+    autoeq::qa_println!(args, "   (* selected number of filters)");
+    autoeq::qa_println!(args, "");
+
+    let mut best_args = args.clone();
+    best_args.num_filters = best.num_filters;
+    let (lower_bounds, upper_bounds) = autoeq::workflow::setup_bounds(&best_args);
+    let x0 = autoeq::workflow::initial_guess(&best_args, &lower_bounds, &upper_bounds);
+    let mut data_copy = objective_data.clone();
+    let pre_objective = autoeq::optim::compute_fitness_penalties(&x0, None, &mut data_copy);
+    let post_objective = autoeq::optim::compute_fitness_penalties(&best.x, None, &mut data_copy);
+    if args.qa.is_none() {
+        print_freq_spacing(&best.x, &best_args, "global");
+    }
+
+    let result = OptimizationResult {
+        params: best.x.clone(),
+        converged: true,
+        pre_objective: Some(pre_objective),
+        post_objective: Some(post_objective),
+    };
+    Ok((best_args, result))
+}
@@ -1,9 +1,109 @@
+use autoeq::cli::ExportFormat;
 use autoeq::iir;
 use chrono;
 use std::{error::Error, path::Path};
 use tokio::fs;
 
-/// Save PEQ settings to APO format file
+/// File stem, comment header and preset name shared by every saved preset
+fn preset_header(
+    args: &autoeq::cli::Args,
+    loss_type: &autoeq::LossType,
+) -> (&'static str, String, String) {
+    // Determine file stem based on loss type
+    let stem = match loss_type {
+        autoeq::LossType::SpeakerFlat | autoeq::LossType::HeadphoneFlat => "iir-autoeq-flat",
+        autoeq::LossType::SpeakerScore | autoeq::LossType::HeadphoneScore => "iir-autoeq-score",
+        autoeq::LossType::SpeakerScoreWithSub => "iir-autoeq-score-sub",
+        autoeq::LossType::PerceptualFlat => "iir-autoeq-perceptual",
+        autoeq::LossType::DriversFlat | autoeq::LossType::SubMainsFlat => {
+            // Unreachable: crossover modes use a separate code path
+            unreachable!("Crossover modes should not reach this point");
+        }
+    };
+
+    // Generate comment string with optimization details
+    let comment = format!(
+        "# AutoEQ Parametric Equalizer Settings\n# Speaker: {}\n# Loss Type: {:?}\n# Filters: {}\n# Generated: {}",
+        args.speaker.as_deref().unwrap_or("Unknown"),
+        loss_type,
+        args.num_filters,
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    let preset_name = format!("AutoEQ {}", args.speaker.as_deref().unwrap_or("Unknown"));
+
+    (stem, comment, preset_name)
+}
+
+/// Warn when a format cannot carry the Vicanek design of the filters
+///
+/// APO, CamillaDSP and PipeWire get the raw coefficients and Wavelet the
+/// response; the other formats only store the filter parameters. EasyEffects
+/// rejects Vicanek filters, see [`iir::peq_format_easyeffects`].
+fn warn_parametric_only(format: &ExportFormat, peq: &iir::Peq) {
+    let parametric_only = matches!(format, ExportFormat::Rme | ExportFormat::Aupreset);
+    if parametric_only
+        && peq
+            .iter()
+            .any(|(_, biquad)| biquad.design == iir::BiquadDesign::Vicanek)
+    {
+        eprintln!(
+            "Warning: {} presets only store the filter parameters, the host designs them with RBJ coefficients and not with the Vicanek design",
+            format
+        );
+    }
+}
+
+/// Format a single channel PEQ in one export format
+///
+/// # Returns
+/// * Tuple of (file content, label printed when saved), or an error if the
+///   format cannot hold the filters
+fn format_peq(
+    format: &ExportFormat,
+    comment: &str,
+    preset_name: &str,
+    peq: &iir::Peq,
+) -> Result<(String, &'static str), Box<dyn Error>> {
+    warn_parametric_only(format, peq);
+    Ok(match format {
+        ExportFormat::Apo => (iir::peq_format_apo(comment, peq), "🕶 PEQ settings"),
+        ExportFormat::Rme => (
+            iir::peq_format_rme_room(peq, peq),
+            "🎚  RME TotalMix RoomEQ preset",
+        ),
+        ExportFormat::Aupreset => (
+            iir::peq_format_aupreset(peq, preset_name),
+            "🍎 Apple AUpreset",
+        ),
+        ExportFormat::CamillaDsp => (
+            iir::peq_format_camilladsp(comment, peq),
+            "🐪 CamillaDSP configuration",
+        ),
+        ExportFormat::EasyEffects => {
+            let preset = iir::peq_format_easyeffects(peq)?;
+            if preset.dropped_unsupported > 0 {
+                eprintln!(
+                    "Warning: EasyEffects has no first-order or Linkwitz transform band, {} filters were dropped",
+                    preset.dropped_unsupported
+                );
+            }
+            if preset.dropped_over_limit > 0 {
+                eprintln!(
+                    "Warning: EasyEffects supports at most 32 bands, {} filters were dropped",
+                    preset.dropped_over_limit
+                );
+            }
+            (preset.json, "🎛  EasyEffects preset")
+        }
+        ExportFormat::PipeWire => (
+            iir::peq_format_pipewire(preset_name, peq),
+            "🔗 PipeWire filter-chain",
+        ),
+        ExportFormat::Wavelet => (iir::peq_format_graphiceq(peq), "📱 Wavelet GraphicEQ"),
+    })
+}
+
+/// Save PEQ settings in every format requested with `--export`
 ///
 /// # Arguments
 /// * `args` - Command line arguments
@@ -21,65 +121,223 @@ pub(super) async fn save_peq_to_file(
 ) -> Result<(), Box<dyn Error>> {
     // Build the PEQ from the optimized parameters
     let peq_model = args.effective_peq_model();
-    let peq = autoeq::x2peq::x2peq(x, args.sample_rate, peq_model);
-
-    // Determine filename based on loss type
-    let filename = match loss_type {
-        autoeq::LossType::SpeakerFlat | autoeq::LossType::HeadphoneFlat => "iir-autoeq-flat.txt",
-        autoeq::LossType::SpeakerScore | autoeq::LossType::HeadphoneScore => "iir-autoeq-score.txt",
-        autoeq::LossType::DriversFlat => {
-            // Unreachable: DriversFlat mode uses a separate code path
-            unreachable!("DriversFlat mode should not reach this point");
-        }
-    };
+    let peq = autoeq::x2peq::x2peq_with_design(x, args.sample_rate, peq_model, args.biquad_design);
+
+    let (stem, comment, preset_name) = preset_header(args, loss_type);
 
-    // Create the full path (same directory as the plots)
+    // Files go in the same directory as the plots
     let parent_dir = output_path.parent().unwrap_or(output_path);
-    let file_path = parent_dir.join(filename);
+    fs::create_dir_all(parent_dir).await?;
 
-    // Generate comment string with optimization details
-    let comment = format!(
-        "# AutoEQ Parametric Equalizer Settings\n# Speaker: {}\n# Loss Type: {:?}\n# Filters: {}\n# Generated: {}",
-        args.speaker.as_deref().unwrap_or("Unknown"),
-        loss_type,
-        args.num_filters,
-        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
-    );
+    for format in &args.export {
+        let file_path = parent_dir.join(format!("{}{}", stem, format.file_suffix()));
+        let (content, label) = format_peq(format, &comment, &preset_name, &peq)?;
+        fs::write(&file_path, content).await?;
+        autoeq::qa_println!(args, "{} saved to: {}", label, file_path.display());
+    }
+
+    Ok(())
+}
+
+/// Save one preset per number of filters from a Pareto set (`--pareto`)
+///
+/// Each file name gets a `-<n>filters` suffix; the empty EQ is skipped.
+///
+/// # Arguments
+/// * `args` - Command line arguments
+/// * `solutions` - Best solution for each number of filters
+/// * `output_path` - Base output path for files
+/// * `loss_type` - Type of optimization performed
+///
+/// # Returns
+/// * Result indicating success or error
+pub(super) async fn save_pareto_peqs_to_file(
+    args: &autoeq::cli::Args,
+    solutions: &[&autoeq::pareto::ParetoSolution],
+    output_path: &Path,
+    loss_type: &autoeq::LossType,
+) -> Result<(), Box<dyn Error>> {
+    let parent_dir = output_path.parent().unwrap_or(output_path);
+    fs::create_dir_all(parent_dir).await?;
 
-    // Format the PEQ as APO string
-    let apo_content = iir::peq_format_apo(&comment, &peq);
+    for solution in solutions.iter().filter(|s| s.num_filters > 0) {
+        let mut solution_args = args.clone();
+        solution_args.num_filters = solution.num_filters;
+        let (stem, comment, preset_name) = preset_header(&solution_args, loss_type);
+        let peq = autoeq::x2peq::x2peq_with_design(
+            &solution.x,
+            args.sample_rate,
+            args.effective_peq_model(),
+            args.biquad_design,
+        );
+
+        for format in &args.export {
+            let file_path = parent_dir.join(format!(
+                "{}-{}filters{}",
+                stem,
+                solution.num_filters,
+                format.file_suffix()
+            ));
+            let (content, label) = format_peq(format, &comment, &preset_name, &peq)?;
+            fs::write(&file_path, content).await?;
+            autoeq::qa_println!(args, "{} saved to: {}", label, file_path.display());
+        }
+    }
+
+    Ok(())
+}
 
-    // Ensure parent directory exists
-    if let Some(parent) = file_path.parent() {
-        fs::create_dir_all(parent).await?;
+/// Save left and right PEQ settings in every format requested with `--export`
+///
+/// EqualizerAPO and RME room EQ presets hold both channels in one file. The
+/// other formats have no notion of channels and get one file per side, with a
+/// `-left` / `-right` suffix.
+///
+/// # Arguments
+/// * `args` - Command line arguments
+/// * `x_left` - Optimized filter parameters of the left channel
+/// * `x_right` - Optimized filter parameters of the right channel
+/// * `output_path` - Base output path for files
+/// * `loss_type` - Type of optimization performed
+///
+/// # Returns
+/// * Result indicating success or error
+pub(super) async fn save_stereo_peq_to_file(
+    args: &autoeq::cli::Args,
+    x_left: &[f64],
+    x_right: &[f64],
+    output_path: &Path,
+    loss_type: &autoeq::LossType,
+) -> Result<(), Box<dyn Error>> {
+    let peq_model = args.effective_peq_model();
+    let left =
+        autoeq::x2peq::x2peq_with_design(x_left, args.sample_rate, peq_model, args.biquad_design);
+    let right =
+        autoeq::x2peq::x2peq_with_design(x_right, args.sample_rate, peq_model, args.biquad_design);
+
+    let (stem, comment, preset_name) = preset_header(args, loss_type);
+
+    let parent_dir = output_path.parent().unwrap_or(output_path);
+    fs::create_dir_all(parent_dir).await?;
+
+    for format in &args.export {
+        if *format == ExportFormat::Rme {
+            warn_parametric_only(format, &left);
+        }
+        let stereo = match format {
+            ExportFormat::Apo => Some((
+                iir::peq_format_apo_stereo(&comment, &left, &right),
+                "🕶 PEQ settings (L/R)",
+            )),
+            ExportFormat::Rme => Some((
+                iir::peq_format_rme_room(&left, &right),
+                "🎚  RME TotalMix RoomEQ preset (L/R)",
+            )),
+            _ => None,
+        };
+        if let Some((content, label)) = stereo {
+            let file_path = parent_dir.join(format!("{}{}", stem, format.file_suffix()));
+            fs::write(&file_path, content).await?;
+            autoeq::qa_println!(args, "{} saved to: {}", label, file_path.display());
+            continue;
+        }
+        for (side, peq) in [("left", &left), ("right", &right)] {
+            let file_path = parent_dir.join(format!("{}-{}{}", stem, side, format.file_suffix()));
+            let (content, label) = format_peq(format, &comment, &preset_name, peq)?;
+            fs::write(&file_path, content).await?;
+            autoeq::qa_println!(args, "{} saved to: {}", label, file_path.display());
+        }
     }
 
-    // Write the APO file
-    fs::write(&file_path, apo_content).await?;
-    autoeq::qa_println!(args, "🕶 PEQ settings saved to: {}", file_path.display());
-
-    // Save RME TotalMix format (.xml)
-    let rme_filename = filename.replace(".txt", ".tmreq");
-    let rme_path = parent_dir.join(&rme_filename);
-    let rme_content = iir::peq_format_rme_room(&peq, &peq);
-    fs::write(&rme_path, rme_content).await?;
-    autoeq::qa_println!(
-        args,
-        "🎚  RME TotalMix RoomEQ preset saved to: {}",
-        rme_path.display()
+    Ok(())
+}
+
+/// Save sub + mains settings in every format requested with `--export`
+///
+/// EqualizerAPO gets one configuration with the mains on `L R` and the sub on
+/// `SUB`, including the crossover filters, the sub gain, the delay and the
+/// polarity. The other formats have no notion of delay or routing and get a
+/// `-mains` and a `-sub` file with the filters only.
+///
+/// # Arguments
+/// * `args` - Command line arguments
+/// * `data` - Sub + mains measurement data (crossover type)
+/// * `params` - Optimized crossover, alignment and PEQ parameters
+/// * `output_path` - Base output path for files
+///
+/// # Returns
+/// * Result indicating success or error
+pub(super) async fn save_sub_mains_to_file(
+    args: &autoeq::cli::Args,
+    data: &autoeq::SubMainsLossData,
+    params: &autoeq::SubMainsParams,
+    output_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut mains = params.mains_peq.clone();
+    mains.extend(
+        data.crossover
+            .highpass
+            .highpass(params.crossover_freq, args.sample_rate),
+    );
+    let mut sub = params.sub_peq.clone();
+    sub.extend(
+        data.crossover
+            .lowpass
+            .lowpass(params.crossover_freq, args.sample_rate),
     );
 
-    // Save Apple AUNBandEQ format (.aupreset)
-    let aupreset_filename = filename.replace(".txt", ".aupreset");
-    let aupreset_path = parent_dir.join(&aupreset_filename);
-    let preset_name = format!("AutoEQ {}", args.speaker.as_deref().unwrap_or("Unknown"));
-    let aupreset_content = iir::peq_format_aupreset(&peq, &preset_name);
-    fs::write(&aupreset_path, aupreset_content).await?;
-    autoeq::qa_println!(
-        args,
-        "🍎 Apple AUpreset saved to: {}",
-        aupreset_path.display()
+    let stem = "iir-autoeq-sub-mains";
+    let comment = format!(
+        "# AutoEQ Sub + Mains Settings\n# Crossover: {:.0} Hz {}\n# Sub: {:+.1} dB, delay {:+.2} ms, polarity {}\n# Generated: {}",
+        params.crossover_freq,
+        data.crossover,
+        params.sub_gain_db,
+        params.sub_delay_ms,
+        if params.sub_inverted {
+            "inverted"
+        } else {
+            "normal"
+        },
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
     );
+    let preset_name = "AutoEQ Sub + Mains".to_string();
+
+    let parent_dir = output_path.parent().unwrap_or(output_path);
+    fs::create_dir_all(parent_dir).await?;
+
+    for format in &args.export {
+        if *format == ExportFormat::Apo {
+            let mut content = format!("{}\n", comment);
+            content.push_str(&iir::peq_format_apo("Channel: L R", &mains));
+            if params.sub_delay_ms < 0.0 {
+                content.push_str(&format!("Delay: {:.2} ms\n", -params.sub_delay_ms));
+            }
+            content.push_str(&iir::peq_format_apo("\nChannel: SUB", &sub));
+            content.push_str(&format!("Preamp: {:+.1} dB\n", params.sub_gain_db));
+            if params.sub_delay_ms > 0.0 {
+                content.push_str(&format!("Delay: {:.2} ms\n", params.sub_delay_ms));
+            }
+            if params.sub_inverted {
+                content.push_str("Copy: SUB=-1*SUB\n");
+            }
+            content.push_str("\nChannel: all\n");
+
+            let file_path = parent_dir.join(format!("{}{}", stem, format.file_suffix()));
+            fs::write(&file_path, content).await?;
+            autoeq::qa_println!(
+                args,
+                "🕶 PEQ settings (mains + sub) saved to: {}",
+                file_path.display()
+            );
+            continue;
+        }
+        for (side, peq) in [("mains", &mains), ("sub", &sub)] {
+            let file_path = parent_dir.join(format!("{}-{}{}", stem, side, format.file_suffix()));
+            let (content, label) = format_peq(format, &comment, &preset_name, peq)?;
+            fs::write(&file_path, content).await?;
+            autoeq::qa_println!(args, "{} saved to: {}", label, file_path.display());
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,303 @@
+// Automatic selection of the number of filters: add one filter at a time
+
+use crate::cli::{Args, PeqModel};
+use crate::iir::{Biquad, BiquadFilterType};
+use crate::optim::{self, ObjectiveData, compute_base_fitness, compute_fitness_penalties};
+use crate::param_utils::params_per_filter;
+use crate::workflow::{initial_guess, setup_bounds};
+use crate::x2peq::{peq2x, x2peq, x2spl_with_design};
+
+/// Result of the optimization with a given number of filters
+#[derive(Debug, Clone)]
+pub struct FilterCountStep {
+    /// Number of filters
+    pub num_filters: usize,
+    /// Optimized parameter vector
+    pub x: Vec<f64>,
+    /// Loss of the optimized EQ (without penalties)
+    pub loss: f64,
+    /// Loss improvement compared to the previous step (None for the first step)
+    pub gain: Option<f64>,
+}
+
+/// Result of an automatic filter count search
+#[derive(Debug, Clone)]
+pub struct FilterCountSearch {
+    /// Every filter count that was optimized, in increasing order
+    pub steps: Vec<FilterCountStep>,
+    /// Index in `steps` of the selected filter count
+    pub selected: usize,
+}
+
+impl FilterCountSearch {
+    /// The selected step: the last one whose gain reached the threshold
+    pub fn best(&self) -> &FilterCountStep {
+        &self.steps[self.selected]
+    }
+}
+
+/// Whether the first filter of a PEQ model has a fixed, non peak type
+fn has_fixed_first(model: PeqModel) -> bool {
+    !matches!(model, PeqModel::Pk | PeqModel::Free)
+}
+
+/// Whether the last filter of a PEQ model has a fixed, non peak type
+fn has_fixed_last(model: PeqModel) -> bool {
+    matches!(
+        model,
+        PeqModel::HpPkLp
+            | PeqModel::LsPkHs
+            | PeqModel::Hp1PkLp1
+            | PeqModel::Ls1PkHs1
+            | PeqModel::FreePkFree
+    )
+}
+
+/// Smallest number of filters of a PEQ model with at least one peak filter
+pub fn min_filter_count(model: PeqModel) -> usize {
+    1 + has_fixed_first(model) as usize + has_fixed_last(model) as usize
+}
+
+/// Build a starting point with one more filter from a previous solution
+///
+/// Each filter of the bounds has its own frequency band. The previous peak
+/// filters keep their frequency order and take the bands that need the least
+/// clamping; the new peak filter goes into the remaining band, where the
+/// remaining deviation is the largest.
+///
+/// # Arguments
+/// * `prev_x` - Optimized parameter vector with one filter less
+/// * `data` - Objective data (deviation curve, frequency range, PEQ model)
+/// * `lower_bounds` - Lower bounds for the larger parameter vector
+/// * `upper_bounds` - Upper bounds for the larger parameter vector
+///
+/// # Returns
+/// * Parameter vector with one more filter, clamped into the bounds
+pub fn warm_start(
+    prev_x: &[f64],
+    data: &ObjectiveData,
+    lower_bounds: &[f64],
+    upper_bounds: &[f64],
+) -> Vec<f64> {
+    let model = data.peq_model;
+    let ppf = params_per_filter(model);
+    let freq_offset = ppf - 3;
+    let mut peq = x2peq(prev_x, data.srate, model);
+
+    // Keep the fixed first and last filters where they are
+    let tail = if has_fixed_last(model) {
+        peq.pop()
+    } else {
+        None
+    };
+    let start = has_fixed_first(model) as usize;
+    let mut peaks = peq.split_off(start);
+    peaks.sort_by(|a, b| a.1.freq.total_cmp(&b.1.freq));
+
+    // Frequency band (log10) of each peak filter slot
+    let bands: Vec<(f64, f64)> = (start..start + peaks.len() + 1)
+        .map(|i| {
+            let k = i * ppf + freq_offset;
+            (lower_bounds[k], upper_bounds[k])
+        })
+        .collect();
+    let clamping = |slot: usize| -> f64 {
+        peaks
+            .iter()
+            .zip(bands.iter().enumerate().filter(|(i, _)| *i != slot))
+            .map(|((_, f), (_, (lo, hi)))| {
+                let log_freq = f.freq.log10();
+                (log_freq - log_freq.clamp(*lo, *hi)).abs()
+            })
+            .sum()
+    };
+    let slot = (0..bands.len())
+        .min_by(|a, b| clamping(*a).total_cmp(&clamping(*b)))
+        .unwrap_or(0);
+
+    // Remaining deviation after the previous EQ: target - (input + peq)
+    let residual = &data.deviation
+        - &x2spl_with_design(&data.freqs, prev_x, data.srate, model, data.biquad_design);
+    let (lo, hi) = bands[slot];
+    let (freq, gain) = data
+        .freqs
+        .iter()
+        .zip(residual.iter())
+        .filter(|(f, _)| **f >= data.min_freq && **f <= data.max_freq)
+        .filter(|(f, _)| (lo..=hi).contains(&f.log10()))
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+        .map(|(f, r)| (*f, *r))
+        .unwrap_or((10f64.powf((lo + hi) / 2.0), 0.0));
+    let new_filter = Biquad::new(
+        BiquadFilterType::Peak,
+        freq,
+        data.srate,
+        std::f64::consts::SQRT_2,
+        gain,
+    );
+
+    peaks.insert(slot, (1.0, new_filter));
+    peq.extend(peaks);
+    peq.extend(tail);
+
+    peq2x(&peq, model)
+        .iter()
+        .zip(lower_bounds.iter().zip(upper_bounds))
+        .map(|(v, (lo, hi))| v.clamp(*lo, *hi))
+        .collect()
+}
+
+/// Increase the number of filters until an extra filter stops paying off
+///
+/// Starts with the smallest number of filters of the PEQ model and adds one
+/// filter at a time up to `--num-filters`. Each run is warm started from the
+/// previous solution (see [`warm_start`]). The search stops when the loss
+/// improves by less than `min_gain` with the extra filter; the previous count
+/// is then selected.
+///
+/// # Arguments
+/// * `objective_data` - Base objective configuration
+/// * `args` - CLI arguments; `num_filters` is the largest count tried
+/// * `min_gain` - Minimum loss improvement per extra filter
+///
+/// # Returns
+/// * The loss for each filter count and the selected count, or the optimizer error
+pub fn optimize_filter_count(
+    objective_data: &ObjectiveData,
+    args: &Args,
+    min_gain: f64,
+) -> Result<FilterCountSearch, String> {
+    let first = min_filter_count(args.effective_peq_model());
+    let mut steps: Vec<FilterCountStep> = Vec::new();
+    let mut selected = 0;
+
+    for num_filters in first..=args.num_filters.max(first) {
+        let mut step_args = args.clone();
+        step_args.num_filters = num_filters;
+        let (lower_bounds, upper_bounds) = setup_bounds(&step_args);
+        let x0 = match steps.last() {
+            Some(prev) => warm_start(&prev.x, objective_data, &lower_bounds, &upper_bounds),
+            None => initial_guess(&step_args, &lower_bounds, &upper_bounds),
+        };
+        let mut x = x0.clone();
+
+        optim::optimize_filters(
+            &mut x,
+            &lower_bounds,
+            &upper_bounds,
+            objective_data.clone(),
+            &step_args,
+        )
+        .map_err(|(e, _)| e)?;
+        if args.refine {
+            optim::optimize_filters_with_algo_override(
+                &mut x,
+                &lower_bounds,
+                &upper_bounds,
+                objective_data.clone(),
+                &step_args,
+                Some(&args.local_algo),
+            )
+            .map_err(|(e, _)| e)?;
+        }
+
+        // Optimizers that cannot be seeded may end up worse than the warm start
+        let mut data_copy = objective_data.clone();
+        if compute_fitness_penalties(&x0, None, &mut data_copy)
+            < compute_fitness_penalties(&x, None, &mut data_copy)
+        {
+            x = x0;
+        }
+
+        let loss = compute_base_fitness(&x, objective_data);
+        let gain = steps.last().map(|prev| prev.loss - loss);
+        steps.push(FilterCountStep {
+            num_filters,
+            x,
+            loss,
+            gain,
+        });
+        match gain {
+            Some(g) if g < min_gain => break,
+            _ => selected = steps.len() - 1,
+        }
+    }
+
+    Ok(FilterCountSearch { steps, selected })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LossType;
+    use ndarray::Array1;
+
+    fn flat_data(deviation: Array1<f64>, freqs: Array1<f64>) -> ObjectiveData {
+        ObjectiveData {
+            freqs: freqs.clone(),
+            target: Array1::zeros(freqs.len()),
+            deviation,
+            srate: 48000.0,
+            min_spacing_oct: 0.0,
+            spacing_weight: 0.0,
+            max_db: 6.0,
+            min_db: 0.5,
+            min_freq: 20.0,
+            max_freq: 20000.0,
+            sub_crossover: 80.0,
+            peq_model: PeqModel::Pk,
+            biquad_design: crate::iir::BiquadDesign::Rbj,
+            loss_type: LossType::SpeakerFlat,
+            speaker_score_data: None,
+            headphone_score_data: None,
+            input_curve: None,
+            drivers_data: None,
+            multi_position_data: None,
+            sub_mains_data: None,
+            perceptual_data: None,
+            penalty_w_ceiling: 0.0,
+            penalty_w_spacing: 0.0,
+            penalty_w_mingain: 0.0,
+            integrality: None,
+        }
+    }
+
+    #[test]
+    fn min_filter_count_depends_on_fixed_filters() {
+        assert_eq!(min_filter_count(PeqModel::Pk), 1);
+        assert_eq!(min_filter_count(PeqModel::HpPk), 2);
+        assert_eq!(min_filter_count(PeqModel::LsPkHs), 3);
+        assert_eq!(min_filter_count(PeqModel::Free), 1);
+    }
+
+    #[test]
+    fn warm_start_keeps_previous_filter_in_its_band() {
+        let freqs = Array1::logspace(10.0, 20f64.log10(), 20000f64.log10(), 200);
+        // a 5 dB bump to correct around 300 Hz
+        let deviation = freqs.mapv(|f: f64| 5.0 * (-(f / 300.0).log2().powi(2) * 8.0).exp());
+        let data = flat_data(deviation, freqs);
+
+        // previous solution: one filter at 8 kHz which only fits the second band
+        let prev_x = vec![3.9, 1.0, -2.0];
+        let lower = vec![1.3, 0.1, -36.0, 2.5, 0.1, -36.0];
+        let upper = vec![3.0, 10.0, 6.0, 4.3, 10.0, 6.0];
+        let x = warm_start(&prev_x, &data, &lower, &upper);
+
+        assert_eq!(x.len(), 6);
+        // new filter in the first band at the bump, previous filter unchanged
+        assert!((10f64.powf(x[0]) - 300.0).abs() < 30.0);
+        assert!(x[2] > 4.0 && x[2] <= 6.0);
+        assert!((x[3] - 3.9).abs() < 1e-9);
+        assert!((x[5] + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn warm_start_clamps_into_bounds() {
+        let freqs = Array1::logspace(10.0, 20f64.log10(), 20000f64.log10(), 200);
+        let deviation = freqs.mapv(|f: f64| if f > 5000.0 { 12.0 } else { 0.0 });
+        let data = flat_data(deviation, freqs);
+
+        let x = warm_start(&[2.0, 1.0, 0.0], &data, &[1.3; 6], &[3.0; 6]);
+        assert!(x.iter().all(|v| (1.3..=3.0).contains(v)));
+    }
+}
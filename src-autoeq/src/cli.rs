@@ -17,8 +17,10 @@
 //! along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::optim::{AlgorithmType, get_all_algorithms};
-use crate::LossType;
 use crate::de::Strategy;
+use crate::iir::BiquadDesign;
+use crate::targets::{BuiltinTarget, TargetModifiers};
+use crate::{FrequencyWeighting, HeadphoneType, LossType, PositionLoss, SpatialAverage};
 use clap::{Parser, ValueEnum};
 use std::fmt;
 use std::path::PathBuf;
@@ -42,6 +44,18 @@ pub enum PeqModel {
     /// First filter is low shelve, last is high shelve, rest are peak filters
     #[value(name = "ls-pk-hs")]
     LsPkHs,
+    /// First filter is a first-order highpass, rest are peak filters
+    #[value(name = "hp1-pk")]
+    Hp1Pk,
+    /// First filter is a first-order highpass, last is a first-order lowpass, rest are peak filters
+    #[value(name = "hp1-pk-lp1")]
+    Hp1PkLp1,
+    /// First filter is a first-order low shelve, last is a first-order high shelve, rest are peak filters
+    #[value(name = "ls1-pk-hs1")]
+    Ls1PkHs1,
+    /// First filter is a Linkwitz transform, rest are peak filters
+    #[value(name = "lt-pk")]
+    LtPk,
     /// First and last filters are free (any type), rest are peak filters
     #[value(name = "free-pk-free")]
     FreePkFree,
@@ -58,6 +72,10 @@ impl fmt::Display for PeqModel {
             PeqModel::LsPk => write!(f, "ls-pk"),
             PeqModel::HpPkLp => write!(f, "hp-pk-lp"),
             PeqModel::LsPkHs => write!(f, "ls-pk-hs"),
+            PeqModel::Hp1Pk => write!(f, "hp1-pk"),
+            PeqModel::Hp1PkLp1 => write!(f, "hp1-pk-lp1"),
+            PeqModel::Ls1PkHs1 => write!(f, "ls1-pk-hs1"),
+            PeqModel::LtPk => write!(f, "lt-pk"),
             PeqModel::FreePkFree => write!(f, "free-pk-free"),
             PeqModel::Free => write!(f, "free"),
         }
@@ -73,6 +91,10 @@ impl PeqModel {
             PeqModel::LsPk,
             PeqModel::HpPkLp,
             PeqModel::LsPkHs,
+            PeqModel::Hp1Pk,
+            PeqModel::Hp1PkLp1,
+            PeqModel::Ls1PkHs1,
+            PeqModel::LtPk,
             PeqModel::FreePkFree,
             PeqModel::Free,
         ]
@@ -88,6 +110,16 @@ impl PeqModel {
             PeqModel::LsPkHs => {
                 "First filter is low shelve, last is high shelve, rest are peak filters"
             }
+            PeqModel::Hp1Pk => "First filter is a first-order highpass, rest are peak filters",
+            PeqModel::Hp1PkLp1 => {
+                "First filter is a first-order highpass, last is a first-order lowpass, rest are peak filters"
+            }
+            PeqModel::Ls1PkHs1 => {
+                "First filter is a first-order low shelve, last is a first-order high shelve, rest are peak filters"
+            }
+            PeqModel::LtPk => {
+                "First filter is a Linkwitz transform (f0, Q0, bass boost), rest are peak filters"
+            }
             PeqModel::FreePkFree => {
                 "First and last filters can be any type, middle filters are peak"
             }
@@ -96,6 +128,66 @@ impl PeqModel {
     }
 }
 
+/// Preset formats that can be written after optimization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// EqualizerAPO parametric configuration (.txt)
+    #[value(name = "apo")]
+    Apo,
+    /// RME TotalMix room EQ preset (.tmreq)
+    #[value(name = "rme")]
+    Rme,
+    /// Apple AUNBandEQ preset (.aupreset)
+    #[value(name = "aupreset")]
+    Aupreset,
+    /// CamillaDSP filters and pipeline (.yml)
+    #[value(name = "camilladsp")]
+    CamillaDsp,
+    /// EasyEffects output preset (.json)
+    #[value(name = "easyeffects")]
+    EasyEffects,
+    /// PipeWire filter-chain module (.conf)
+    #[value(name = "pipewire")]
+    PipeWire,
+    /// Wavelet / EqualizerAPO GraphicEQ string (-graphiceq.txt)
+    #[value(name = "wavelet")]
+    Wavelet,
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Apo => write!(f, "apo"),
+            ExportFormat::Rme => write!(f, "rme"),
+            ExportFormat::Aupreset => write!(f, "aupreset"),
+            ExportFormat::CamillaDsp => write!(f, "camilladsp"),
+            ExportFormat::EasyEffects => write!(f, "easyeffects"),
+            ExportFormat::PipeWire => write!(f, "pipewire"),
+            ExportFormat::Wavelet => write!(f, "wavelet"),
+        }
+    }
+}
+
+impl ExportFormat {
+    /// Formats written when `--export` is not given
+    pub fn defaults() -> Vec<Self> {
+        vec![ExportFormat::Apo, ExportFormat::Rme, ExportFormat::Aupreset]
+    }
+
+    /// Suffix appended to the output file stem (e.g. "iir-autoeq-flat")
+    pub fn file_suffix(&self) -> &'static str {
+        match self {
+            ExportFormat::Apo => ".txt",
+            ExportFormat::Rme => ".tmreq",
+            ExportFormat::Aupreset => ".aupreset",
+            ExportFormat::CamillaDsp => ".yml",
+            ExportFormat::EasyEffects => ".json",
+            ExportFormat::PipeWire => ".conf",
+            ExportFormat::Wavelet => "-graphiceq.txt",
+        }
+    }
+}
+
 /// Shared CLI arguments for AutoEQ binaries.
 #[derive(Parser, Debug, Clone)]
 #[command(author, about, long_about = None)]
@@ -109,11 +201,71 @@ pub struct Args {
     #[arg(short, long)]
     pub curve: Option<PathBuf>,
 
+    /// Path to the right channel curve CSV file (format: frequency,spl).
+    /// When provided, --curve is the left channel and one PEQ is optimized per channel.
+    #[arg(long)]
+    pub curve_right: Option<PathBuf>,
+
+    /// Paths to curve CSV files measured at several listening positions (comma separated).
+    /// Replaces --curve: the positions are combined with --spatial-average.
+    #[arg(long, value_delimiter = ',')]
+    pub positions: Vec<PathBuf>,
+
+    /// Weight of each listening position (comma separated, same order as --positions).
+    /// If not provided, all positions have the same weight.
+    #[arg(long, value_delimiter = ',')]
+    pub position_weights: Vec<f64>,
+
+    /// How the listening positions are averaged (power or db)
+    #[arg(long, value_enum, default_value_t = SpatialAverage::Power)]
+    pub spatial_average: SpatialAverage,
+
+    /// How the errors at the listening positions are combined (average, mean-variance, minimax)
+    #[arg(long, value_enum, default_value_t = PositionLoss::MeanVariance)]
+    pub position_loss: PositionLoss,
+
+    /// Weight of the spread between positions for --position-loss mean-variance
+    #[arg(long, default_value_t = 1.0, value_parser = parse_nonnegative_f64)]
+    pub position_spread_weight: f64,
+
     /// Path to the optional target curve CSV file (format: frequency,spl).
     /// If not provided, a flat 0 dB target is assumed.
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "target_builtin")]
     pub target: Option<PathBuf>,
 
+    /// Built-in target curve, used instead of --target
+    #[arg(long, value_enum)]
+    pub target_builtin: Option<BuiltinTarget>,
+
+    /// Tilt added to the target in dB/octave, pivoting at 1 kHz
+    #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+    pub target_tilt: f64,
+
+    /// Corner frequency of the bass shelf added to the target (Hz)
+    #[arg(long, default_value_t = 105.0)]
+    pub target_bass_freq: f64,
+
+    /// Gain of the bass shelf added to the target (dB)
+    #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+    pub target_bass_gain: f64,
+
+    /// Corner frequency of the treble shelf added to the target (Hz)
+    #[arg(long, default_value_t = 2500.0)]
+    pub target_treble_freq: f64,
+
+    /// Gain of the treble shelf added to the target (dB)
+    #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+    pub target_treble_gain: f64,
+
+    /// Bass rise due to the room (dB) added to the predicted in-room target
+    /// used when optimizing the Estimated In-Room Response of a speaker
+    #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+    pub room_gain: f64,
+
+    /// Corner frequency of the room gain bass rise (Hz)
+    #[arg(long, default_value_t = 120.0)]
+    pub room_gain_freq: f64,
+
     /// The sample rate for the IIR filters.
     #[arg(short, long, default_value_t = 48000.0)]
     pub sample_rate: f64,
@@ -163,6 +315,12 @@ pub struct Args {
     #[arg(long, default_value = "Listening Window")]
     pub curve_name: String,
 
+    /// Directory of per-angle speaker measurements (horizontal and vertical, 0 to 180 degrees
+    /// in 10 degree steps, files named like `Speaker_H-30.txt` or `Speaker V 40.txt`).
+    /// The CEA2034 curves are computed locally and --curve-name selects the curve to optimize.
+    #[arg(long)]
+    pub spin_dir: Option<PathBuf>,
+
     /// Optimization algorithm to use (e.g., isres, cobyla)
     #[arg(long, default_value = "nlopt:cobyla")]
     pub algo: String,
@@ -179,6 +337,28 @@ pub struct Args {
     #[arg(long, default_value_t = false)]
     pub refine: bool,
 
+    /// Multi-objective mode: optimize the loss together with the number of filters and the
+    /// total positive gain, with up to --num-filters peak filters (a filter below --min-db is
+    /// switched off), and report the Pareto set
+    #[arg(long, default_value_t = false)]
+    pub pareto: bool,
+
+    /// Choose the number of filters automatically: optimize with one filter, then add one
+    /// filter at a time (warm started from the previous solution) up to --num-filters, and
+    /// stop when an extra filter improves the loss by less than --auto-filters-min-gain
+    #[arg(long, default_value_t = false)]
+    pub auto_filters: bool,
+
+    /// Minimum loss improvement an extra filter must bring with --auto-filters
+    #[arg(long, default_value_t = 0.05, value_parser = parse_nonnegative_f64)]
+    pub auto_filters_min_gain: f64,
+
+    /// Report how stable the CEA2034 score is: confidence intervals of each sub-metric over
+    /// perturbed curves, resolutions, NBD bands and SM limits, and the frequency bands that
+    /// cost the most score points after EQ
+    #[arg(long, default_value_t = false)]
+    pub score_sensitivity: bool,
+
     /// Local optimizer to use for refinement (e.g., cobyla)
     #[arg(long, default_value = "cobyla")]
     pub local_algo: String,
@@ -203,6 +383,37 @@ pub struct Args {
     #[arg(long, value_enum, default_value_t = LossType::SpeakerFlat)]
     pub loss: LossType,
 
+    /// Crossover frequency (Hz) to the subwoofer with --loss speaker-score-with-sub; the
+    /// response below it is left to the sub and neither scored nor equalized
+    #[arg(long, default_value_t = 80.0)]
+    pub sub_crossover: f64,
+
+    /// Low Frequency Extension (Hz) of your subwoofer, e.g. its -6 dB point; the
+    /// pre and post optimization scores are also printed with this LFX
+    #[arg(long, value_parser = parse_strictly_positive_f64)]
+    pub sub_lfx: Option<f64>,
+
+    /// Frequency weighting of --loss perceptual-flat (none, erb, equal-loudness)
+    #[arg(long, value_enum, default_value_t = FrequencyWeighting::Erb)]
+    pub perceptual_weighting: FrequencyWeighting,
+
+    /// Weight of peaks (response above target) in --loss perceptual-flat
+    #[arg(long, default_value_t = 1.0, value_parser = parse_nonnegative_f64)]
+    pub peak_weight: f64,
+
+    /// Weight of dips (response below target) in --loss perceptual-flat
+    #[arg(long, default_value_t = 0.5, value_parser = parse_nonnegative_f64)]
+    pub dip_weight: f64,
+
+    /// Dips narrower than this width in octaves are ignored by --loss perceptual-flat
+    #[arg(long, value_parser = parse_nonnegative_f64)]
+    pub dip_min_width: Option<f64>,
+
+    /// Headphone type selecting the preference model of the headphone score
+    /// (over-ear or in-ear). If not provided, it is guessed from the target file name.
+    #[arg(long, value_enum)]
+    pub headphone_type: Option<HeadphoneType>,
+
     /// PEQ model that defines the filter structure
     #[arg(long, value_enum, default_value_t = PeqModel::Pk)]
     pub peq_model: PeqModel,
@@ -211,6 +422,11 @@ pub struct Args {
     #[arg(long, default_value_t = false)]
     pub peq_model_list: bool,
 
+    /// Coefficient design of the filters: rbj (Audio EQ Cookbook) or vicanek
+    /// (matched magnitude, follows the analog filter up to Nyquist)
+    #[arg(long, default_value = "rbj", value_parser = parse_biquad_design)]
+    pub biquad_design: BiquadDesign,
+
     /// Display list of available optimization algorithms with descriptions and exit.
     #[arg(long, default_value_t = false)]
     pub algo_list: bool,
@@ -277,22 +493,115 @@ pub struct Args {
     #[arg(long)]
     pub driver4: Option<PathBuf>,
 
-    /// Crossover type for multi-driver optimization (butterworth2, linkwitzriley2, linkwitzriley4)
+    /// Driver measurement CSV file with an optional name, as path[:name] (repeatable, any number
+    /// of drivers, for multi-driver optimization with --loss drivers-flat)
+    #[arg(long = "driver", value_name = "PATH[:NAME]", value_parser = parse_driver_spec)]
+    pub drivers: Vec<DriverSpec>,
+
+    /// Crossover type (butterworth1-4, linkwitzriley2/4/8 or bw1-4, lr2/4/8). Use lowpass/highpass
+    /// for asymmetric slopes (e.g. lr4/bw3) and a comma separated list for one crossover per
+    /// driver pair, from the lowest pair to the highest
     #[arg(long, default_value = "linkwitzriley4")]
     pub crossover_type: String,
+
+    /// Maximum delay added to each driver in ms (--loss drivers-flat)
+    #[arg(long, default_value_t = 1.0, value_parser = parse_nonnegative_f64)]
+    pub driver_max_delay_ms: f64,
+
+    /// Path to the mains measurement at the listening position (freq, spl, phase) for --loss sub-mains-flat
+    #[arg(long)]
+    pub mains: Option<PathBuf>,
+
+    /// Path to the subwoofer measurement at the listening position (freq, spl, phase) for --loss sub-mains-flat
+    #[arg(long)]
+    pub sub: Option<PathBuf>,
+
+    /// Number of PEQ filters on the subwoofer (--loss sub-mains-flat, --num-filters sets the mains)
+    #[arg(long, default_value_t = 3)]
+    pub sub_num_filters: usize,
+
+    /// Maximum delay between the subwoofer and the mains in ms, either way (--loss sub-mains-flat)
+    #[arg(long, default_value_t = 20.0, value_parser = parse_nonnegative_f64)]
+    pub sub_max_delay_ms: f64,
+
+    /// Comma separated list of preset formats to write next to the plots
+    /// (apo, rme, aupreset, camilladsp, easyeffects, pipewire, wavelet)
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = ExportFormat::defaults())]
+    pub export: Vec<ExportFormat>,
+}
+
+/// Driver measurement file given on the command line as `path[:name]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriverSpec {
+    /// Path to the measurement CSV file
+    pub path: PathBuf,
+    /// Optional display name (e.g. "woofer")
+    pub name: Option<String>,
+}
+
+impl Default for Args {
+    /// Arguments with every CLI default, as when `autoeq` runs without flags
+    fn default() -> Self {
+        Self::parse_from(["autoeq"])
+    }
 }
 
 impl Args {
+    /// All driver files: --driver1 to --driver4 first, then each --driver
+    pub fn driver_specs(&self) -> Vec<DriverSpec> {
+        [&self.driver1, &self.driver2, &self.driver3, &self.driver4]
+            .into_iter()
+            .flatten()
+            .map(|path| DriverSpec {
+                path: path.clone(),
+                name: None,
+            })
+            .chain(self.drivers.iter().cloned())
+            .collect()
+    }
+
     /// Get the effective PEQ model
     pub fn effective_peq_model(&self) -> PeqModel {
         self.peq_model
     }
 
+    /// Get the lowest frequency to equalize: --min-freq, raised to --sub-crossover
+    /// when the subwoofer plays the bass (--loss speaker-score-with-sub)
+    pub fn effective_min_freq(&self) -> f64 {
+        if self.loss == LossType::SpeakerScoreWithSub {
+            self.min_freq.max(self.sub_crossover)
+        } else {
+            self.min_freq
+        }
+    }
+
+    /// Get the effective headphone type: --headphone-type if given,
+    /// otherwise guessed from the built-in target or the target file name
+    pub fn effective_headphone_type(&self) -> HeadphoneType {
+        match (self.headphone_type, self.target_builtin, &self.target) {
+            (Some(headphone_type), _, _) => headphone_type,
+            (None, Some(builtin), _) => builtin.headphone_type(),
+            (None, None, Some(target)) => HeadphoneType::from_target_path(target),
+            (None, None, None) => HeadphoneType::OverEar,
+        }
+    }
+
+    /// Tilt and shelves added to the target curve
+    pub fn target_modifiers(&self) -> TargetModifiers {
+        TargetModifiers {
+            tilt_db_per_octave: self.target_tilt,
+            bass_shelf_freq: self.target_bass_freq,
+            bass_shelf_gain: self.target_bass_gain,
+            treble_shelf_freq: self.target_treble_freq,
+            treble_shelf_gain: self.target_treble_gain,
+        }
+    }
+
     /// Check if the first filter should be a highpass (for compatibility)
     pub fn uses_highpass_first(&self) -> bool {
         matches!(
             self.effective_peq_model(),
-            PeqModel::HpPk | PeqModel::HpPkLp
+            PeqModel::HpPk | PeqModel::HpPkLp | PeqModel::Hp1Pk | PeqModel::Hp1PkLp1
         )
     }
 }
@@ -685,6 +994,22 @@ pub fn validate_args(args: &Args) -> Result<(), String> {
         ));
     }
 
+    // Check target and room gain shelves: corners must be below Nyquist
+    for (name, freq) in [
+        ("target_bass_freq", args.target_bass_freq),
+        ("target_treble_freq", args.target_treble_freq),
+        ("room_gain_freq", args.room_gain_freq),
+    ] {
+        if !(freq > 0.0 && freq < args.sample_rate / 2.0) {
+            return Err(format!(
+                "Invalid {}: {} Hz. Must be in (0, {}) Hz",
+                name,
+                freq,
+                args.sample_rate / 2.0
+            ));
+        }
+    }
+
     // Check smoothing parameters
     if args.smooth_n < 1 || args.smooth_n > 24 {
         return Err(format!(
@@ -733,45 +1058,151 @@ pub fn validate_args(args: &Args) -> Result<(), String> {
         return Err("Adaptive weight for CR must be between 0.0 and 1.0".to_string());
     }
 
-    // Validate multi-driver arguments
-    if args.loss == LossType::DriversFlat {
-        // Check that at least driver1 and driver2 are provided
-        if args.driver1.is_none() || args.driver2.is_none() {
-            return Err("Multi-driver optimization requires at least --driver1 and --driver2 when using --loss drivers-flat".to_string());
+    if args.spin_dir.is_some()
+        && (args.curve.is_some()
+            || args.curve_right.is_some()
+            || args.speaker.is_some()
+            || !args.positions.is_empty())
+    {
+        return Err(
+            "--spin-dir cannot be used with --curve, --curve-right, --speaker or --positions"
+                .to_string(),
+        );
+    }
+
+    // Multi-position room EQ replaces the single input curve
+    if !args.positions.is_empty() {
+        if args.positions.len() < 2 {
+            return Err("--positions needs at least 2 curves".to_string());
+        }
+        if args.curve.is_some() || args.curve_right.is_some() || args.speaker.is_some() {
+            return Err(
+                "--positions cannot be used with --curve, --curve-right or --speaker".to_string(),
+            );
+        }
+        if !matches!(args.loss, LossType::SpeakerFlat | LossType::HeadphoneFlat) {
+            return Err(
+                "--positions requires a flat loss (speaker-flat or headphone-flat)".to_string(),
+            );
+        }
+        if !args.position_weights.is_empty() {
+            if args.position_weights.len() != args.positions.len() {
+                return Err(format!(
+                    "Got {} position weights for {} positions",
+                    args.position_weights.len(),
+                    args.positions.len()
+                ));
+            }
+            if args.position_weights.iter().any(|w| *w < 0.0)
+                || args.position_weights.iter().sum::<f64>() <= 0.0
+            {
+                return Err("Position weights must be >= 0 with a positive sum".to_string());
+            }
         }
+    } else if !args.position_weights.is_empty() {
+        return Err("--position-weights requires --positions".to_string());
+    }
 
-        // Check crossover type
-        let valid_crossover_types = ["butterworth2", "linkwitzriley2", "linkwitzriley4"];
-        if !valid_crossover_types.contains(&args.crossover_type.as_str()) {
-            return Err(format!(
-                "Invalid crossover type '{}'. Valid types: {}",
-                args.crossover_type,
-                valid_crossover_types.join(", ")
-            ));
+    // Stereo optimization needs a measurement file per channel
+    if args.curve_right.is_some() {
+        if args.curve.is_none() {
+            return Err("--curve-right requires --curve for the left channel".to_string());
+        }
+        if args.loss == LossType::DriversFlat {
+            return Err("--curve-right cannot be used with --loss drivers-flat".to_string());
         }
+        if args.qa.is_some() {
+            return Err("--curve-right cannot be used with --qa".to_string());
+        }
+    }
 
-        // Count number of drivers
-        let n_drivers = [&args.driver1, &args.driver2, &args.driver3, &args.driver4]
-            .iter()
-            .filter(|d| d.is_some())
-            .count();
-        if n_drivers < 2 || n_drivers > 4 {
-            return Err(format!(
-                "Multi-driver optimization requires 2-4 drivers, got {}",
-                n_drivers
-            ));
+    // Validate sub + mains arguments
+    if args.loss == LossType::SubMainsFlat {
+        if args.mains.is_none() || args.sub.is_none() {
+            return Err("--loss sub-mains-flat requires --mains and --sub".to_string());
         }
-    } else {
-        // If not using drivers-flat loss, driver arguments should not be provided
-        if args.driver1.is_some()
-            || args.driver2.is_some()
-            || args.driver3.is_some()
-            || args.driver4.is_some()
-        {
-            return Err("Driver arguments (--driver1, --driver2, etc.) can only be used with --loss drivers-flat".to_string());
+        if args.curve.is_some() || args.speaker.is_some() || !args.positions.is_empty() {
+            return Err(
+                "--loss sub-mains-flat cannot be used with --curve, --speaker or --positions"
+                    .to_string(),
+            );
+        }
+        if args.qa.is_some() {
+            return Err("--loss sub-mains-flat cannot be used with --qa".to_string());
+        }
+        crate::loss::Crossover::parse_list(&args.crossover_type, 1)?;
+    } else if args.mains.is_some() || args.sub.is_some() {
+        return Err("--mains and --sub can only be used with --loss sub-mains-flat".to_string());
+    }
+
+    if args.pareto {
+        if args.effective_peq_model() != PeqModel::Pk {
+            return Err("--pareto requires --peq-model pk".to_string());
+        }
+        if !args.loss.uses_peq_layout() || args.curve_right.is_some() {
+            return Err(
+                "--pareto cannot be used with crossover losses or --curve-right".to_string(),
+            );
+        }
+        if args.qa.is_some() || args.refine {
+            return Err("--pareto cannot be used with --qa or --refine".to_string());
         }
     }
 
+    if args.auto_filters {
+        if args.pareto {
+            return Err("--auto-filters cannot be used with --pareto".to_string());
+        }
+        if !args.loss.uses_peq_layout() || args.curve_right.is_some() {
+            return Err(
+                "--auto-filters cannot be used with crossover losses or --curve-right".to_string(),
+            );
+        }
+    }
+
+    if args.biquad_design == BiquadDesign::Vicanek
+        && args.export.contains(&ExportFormat::EasyEffects)
+    {
+        return Err(
+            "--export easyeffects cannot be used with --biquad-design vicanek: EasyEffects designs its bands with RBJ coefficients"
+                .to_string(),
+        );
+    }
+
+    if args.loss == LossType::SpeakerScoreWithSub && !(20.0..=500.0).contains(&args.sub_crossover) {
+        return Err(format!(
+            "--sub-crossover must be between 20 and 500 Hz (got {})",
+            args.sub_crossover
+        ));
+    }
+
+    if args.score_sensitivity
+        && !matches!(
+            args.loss,
+            LossType::SpeakerFlat
+                | LossType::SpeakerScore
+                | LossType::PerceptualFlat
+                | LossType::SpeakerScoreWithSub
+        )
+    {
+        return Err(
+            "--score-sensitivity requires a speaker loss (speaker-flat, speaker-score, perceptual-flat or speaker-score-with-sub)"
+                .to_string(),
+        );
+    }
+
+    // Validate multi-driver arguments
+    let n_drivers = args.driver_specs().len();
+    if args.loss == LossType::DriversFlat {
+        if n_drivers < 2 {
+            return Err("Multi-driver optimization requires at least 2 drivers (--driver or --driver1 and --driver2) when using --loss drivers-flat".to_string());
+        }
+        crate::loss::Crossover::parse_list(&args.crossover_type, n_drivers - 1)?;
+    } else if n_drivers > 0 {
+        // If not using drivers-flat loss, driver arguments should not be provided
+        return Err("Driver arguments (--driver, --driver1, --driver2, etc.) can only be used with --loss drivers-flat".to_string());
+    }
+
     Ok(())
 }
 
@@ -818,6 +1249,50 @@ mod tests {
         assert_eq!(args.maxeval, 2000);
         assert_eq!(args.curve_name, "Listening Window");
         assert_eq!(args.peq_model, PeqModel::Pk);
+        assert_eq!(args.export, ExportFormat::defaults());
+    }
+
+    #[test]
+    fn export_accepts_comma_separated_list() {
+        let args = Args::parse_from(["autoeq-test", "--export", "camilladsp,easyeffects,wavelet"]);
+        assert_eq!(
+            args.export,
+            vec![
+                ExportFormat::CamillaDsp,
+                ExportFormat::EasyEffects,
+                ExportFormat::Wavelet
+            ]
+        );
+        let res = Args::try_parse_from(["autoeq-test", "--export", "apo,unknown"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn sub_lfx_parsing() {
+        let args = Args::parse_from(["autoeq-test"]);
+        assert_eq!(args.sub_lfx, None);
+        let args = Args::parse_from(["autoeq-test", "--sub-lfx", "25"]);
+        assert_eq!(args.sub_lfx, Some(25.0));
+        assert!(Args::try_parse_from(["autoeq-test", "--sub-lfx", "0"]).is_err());
+    }
+
+    #[test]
+    fn default_matches_cli_defaults() {
+        let args = Args::default();
+        assert_eq!(args.num_filters, 7);
+        assert_eq!(args.sub_crossover, 80.0);
+        assert_eq!(args.export, ExportFormat::defaults());
+        assert!(args.target.is_none() && args.target_builtin.is_none());
+    }
+
+    #[test]
+    fn biquad_design_parsing() {
+        let args = Args::parse_from(["autoeq-test"]);
+        assert_eq!(args.biquad_design, BiquadDesign::Rbj);
+        let args = Args::parse_from(["autoeq-test", "--biquad-design", "vicanek"]);
+        assert_eq!(args.biquad_design, BiquadDesign::Vicanek);
+        let res = Args::try_parse_from(["autoeq-test", "--biquad-design", "bilinear"]);
+        assert!(res.is_err());
     }
 
     #[test]
@@ -1053,6 +1528,313 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_args_curve_right() {
+        let mut args = Args::parse_from(["autoeq-test", "--algo", "autoeq:de"]);
+        args.curve_right = Some(PathBuf::from("right.csv"));
+        let result = validate_args(&args);
+        assert!(result.unwrap_err().contains("requires --curve"));
+
+        args.curve = Some(PathBuf::from("left.csv"));
+        assert!(validate_args(&args).is_ok());
+
+        args.qa = Some(0.5);
+        assert!(validate_args(&args).is_err());
+    }
+
+    #[test]
+    fn validate_args_positions() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--positions",
+            "p1.csv,p2.csv,p3.csv",
+            "--position-weights",
+            "2,1,1",
+        ]);
+        assert_eq!(args.positions.len(), 3);
+        assert_eq!(args.spatial_average, SpatialAverage::Power);
+        assert!(validate_args(&args).is_ok());
+
+        args.position_weights = vec![1.0, 1.0];
+        assert!(
+            validate_args(&args)
+                .unwrap_err()
+                .contains("position weights")
+        );
+
+        args.position_weights.clear();
+        args.curve = Some(PathBuf::from("main.csv"));
+        assert!(validate_args(&args).is_err());
+
+        args.curve = None;
+        args.loss = LossType::SpeakerScore;
+        assert!(validate_args(&args).unwrap_err().contains("flat loss"));
+    }
+
+    #[test]
+    fn validate_args_sub_mains() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--loss",
+            "sub-mains-flat",
+            "--mains",
+            "mains.csv",
+            "--sub",
+            "sub.csv",
+        ]);
+        assert_eq!(args.sub_num_filters, 3);
+        assert!(validate_args(&args).is_ok());
+
+        args.sub = None;
+        assert!(validate_args(&args).unwrap_err().contains("--sub"));
+
+        args.sub = Some(PathBuf::from("sub.csv"));
+        args.crossover_type = "bessel".to_string();
+        assert!(validate_args(&args).is_err());
+
+        args.crossover_type = "linkwitzriley4".to_string();
+        args.loss = LossType::SpeakerFlat;
+        assert!(validate_args(&args).unwrap_err().contains("sub-mains-flat"));
+    }
+
+    #[test]
+    fn validate_args_drivers() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--loss",
+            "drivers-flat",
+            "--driver",
+            "w1.csv:woofer",
+            "--driver",
+            "w2.csv",
+            "--driver",
+            "mid.csv:mid",
+            "--driver",
+            "tw.csv:tweeter",
+            "--driver",
+            "st.csv",
+            "--crossover-type",
+            "lr4,lr4,lr4/bw3,bw3",
+        ]);
+        assert_eq!(args.drivers.len(), 5);
+        assert_eq!(args.drivers[0].path, PathBuf::from("w1.csv"));
+        assert_eq!(args.drivers[0].name.as_deref(), Some("woofer"));
+        assert_eq!(args.drivers[1].name, None);
+        assert!(validate_args(&args).is_ok());
+
+        // one crossover type per pair, or one for all pairs
+        args.crossover_type = "lr4,bw3".to_string();
+        assert!(
+            validate_args(&args)
+                .unwrap_err()
+                .contains("expected 1 or 4")
+        );
+        args.crossover_type = "bw2".to_string();
+        assert!(validate_args(&args).is_ok());
+        args.crossover_type = "lr4/bessel".to_string();
+        assert!(validate_args(&args).is_err());
+
+        // --driver1.. are still accepted and counted with --driver
+        args.crossover_type = "lr4".to_string();
+        args.drivers.truncate(1);
+        assert!(validate_args(&args).is_err());
+        args.driver1 = Some(PathBuf::from("w.csv"));
+        assert_eq!(args.driver_specs().len(), 2);
+        assert!(validate_args(&args).is_ok());
+
+        args.loss = LossType::SpeakerFlat;
+        assert!(validate_args(&args).unwrap_err().contains("drivers-flat"));
+    }
+
+    #[test]
+    fn validate_args_pareto() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--pareto",
+            "--num-filters",
+            "10",
+        ]);
+        assert!(validate_args(&args).is_ok());
+
+        args.peq_model = PeqModel::HpPk;
+        assert!(validate_args(&args).unwrap_err().contains("peq-model pk"));
+
+        args.peq_model = PeqModel::Pk;
+        args.refine = true;
+        assert!(validate_args(&args).is_err());
+    }
+
+    #[test]
+    fn validate_args_spin_dir() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--spin-dir",
+            "measurements",
+            "--loss",
+            "speaker-score",
+        ]);
+        assert!(validate_args(&args).is_ok());
+
+        args.curve = Some(PathBuf::from("curve.csv"));
+        assert!(validate_args(&args).unwrap_err().contains("--spin-dir"));
+    }
+
+    #[test]
+    fn validate_args_auto_filters() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--auto-filters",
+            "--auto-filters-min-gain",
+            "0.1",
+        ]);
+        assert!(validate_args(&args).is_ok());
+        assert_eq!(args.auto_filters_min_gain, 0.1);
+
+        args.pareto = true;
+        assert!(validate_args(&args).unwrap_err().contains("--pareto"));
+
+        args.pareto = false;
+        args.curve_right = Some(PathBuf::from("right.csv"));
+        assert!(validate_args(&args).is_err());
+    }
+
+    #[test]
+    fn validate_args_score_sensitivity() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--score-sensitivity",
+            "--loss",
+            "speaker-score",
+        ]);
+        assert!(validate_args(&args).is_ok());
+
+        args.loss = LossType::HeadphoneFlat;
+        assert!(
+            validate_args(&args)
+                .unwrap_err()
+                .contains("--score-sensitivity")
+        );
+    }
+
+    #[test]
+    fn validate_args_vicanek_easyeffects() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--biquad-design",
+            "vicanek",
+            "--export",
+            "apo,camilladsp",
+        ]);
+        assert!(validate_args(&args).is_ok());
+        args.export.push(ExportFormat::EasyEffects);
+        assert!(validate_args(&args).unwrap_err().contains("easyeffects"));
+    }
+
+    #[test]
+    fn validate_args_sub_crossover() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--loss",
+            "speaker-score-with-sub",
+            "--sub-crossover",
+            "100",
+        ]);
+        assert!(validate_args(&args).is_ok());
+        assert_eq!(args.effective_min_freq(), 100.0);
+
+        args.sub_crossover = 1000.0;
+        assert!(
+            validate_args(&args)
+                .unwrap_err()
+                .contains("--sub-crossover")
+        );
+
+        args.loss = LossType::SpeakerScore;
+        assert!(validate_args(&args).is_ok());
+        assert_eq!(args.effective_min_freq(), args.min_freq);
+    }
+
+    #[test]
+    fn effective_headphone_type_from_target() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--target",
+            "data_tests/targets/harman-in-ear-2019.csv",
+        ]);
+        assert_eq!(args.effective_headphone_type(), HeadphoneType::InEar);
+        args.headphone_type = Some(HeadphoneType::OverEar);
+        assert_eq!(args.effective_headphone_type(), HeadphoneType::OverEar);
+        args.target = None;
+        args.headphone_type = None;
+        assert_eq!(args.effective_headphone_type(), HeadphoneType::OverEar);
+    }
+
+    #[test]
+    fn validate_args_target_modifiers() {
+        let mut args = Args::parse_from([
+            "autoeq-test",
+            "--algo",
+            "autoeq:de",
+            "--target-builtin",
+            "harman-in-ear-2019",
+            "--target-tilt",
+            "-0.5",
+            "--target-bass-gain",
+            "4",
+        ]);
+        assert!(validate_args(&args).is_ok());
+        assert_eq!(args.effective_headphone_type(), HeadphoneType::InEar);
+        let modifiers = args.target_modifiers();
+        assert_eq!(modifiers.tilt_db_per_octave, -0.5);
+        assert_eq!(modifiers.bass_shelf_gain, 4.0);
+
+        args.target_treble_freq = 30000.0;
+        assert!(
+            validate_args(&args)
+                .unwrap_err()
+                .contains("target_treble_freq")
+        );
+
+        assert!(
+            Args::try_parse_from([
+                "autoeq-test",
+                "--target",
+                "t.csv",
+                "--target-builtin",
+                "free-field"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_driver_spec_names() {
+        let spec = parse_driver_spec("C:\\drivers\\woofer.csv").unwrap();
+        assert_eq!(spec.name, None);
+        let spec = parse_driver_spec("C:\\drivers\\woofer.csv:woofer").unwrap();
+        assert_eq!(spec.path, PathBuf::from("C:\\drivers\\woofer.csv"));
+        assert_eq!(spec.name.as_deref(), Some("woofer"));
+        assert!(parse_driver_spec("").is_err());
+    }
+
     #[test]
     fn parse_recombination_probability_valid() {
         assert_eq!(parse_recombination_probability("0.0").unwrap(), 0.0);
@@ -1090,6 +1872,28 @@ fn parse_strictly_positive_f64(s: &str) -> Result<f64, String> {
     }
 }
 
+// Custom value parser for `path[:name]` driver files; a suffix containing a path
+// separator is part of the path (e.g. a Windows drive letter)
+fn parse_driver_spec(s: &str) -> Result<DriverSpec, String> {
+    if s.is_empty() {
+        return Err("driver path cannot be empty".to_string());
+    }
+    match s.rsplit_once(':') {
+        Some((path, name))
+            if !path.is_empty() && !name.is_empty() && !name.contains(['/', '\\']) =>
+        {
+            Ok(DriverSpec {
+                path: PathBuf::from(path),
+                name: Some(name.to_string()),
+            })
+        }
+        _ => Ok(DriverSpec {
+            path: PathBuf::from(s),
+            name: None,
+        }),
+    }
+}
+
 // Custom value parser to enforce non-negative f64 (>= 0)
 fn parse_nonnegative_f64(s: &str) -> Result<f64, String> {
     let v: f64 = s.parse().map_err(|_| format!("invalid float: {s}"))?;
@@ -1100,6 +1904,17 @@ fn parse_nonnegative_f64(s: &str) -> Result<f64, String> {
     }
 }
 
+// Custom value parser for the biquad coefficient design
+fn parse_biquad_design(s: &str) -> Result<BiquadDesign, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "rbj" => Ok(BiquadDesign::Rbj),
+        "vicanek" => Ok(BiquadDesign::Vicanek),
+        _ => Err(format!(
+            "unknown biquad design '{s}' (expected rbj or vicanek)"
+        )),
+    }
+}
+
 // Custom value parser to enforce recombination probability (0.0 to 1.0)
 fn parse_recombination_probability(s: &str) -> Result<f64, String> {
     let v: f64 = s.parse().map_err(|_| format!("invalid float: {s}"))?;
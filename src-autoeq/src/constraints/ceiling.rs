@@ -1,5 +1,6 @@
 use super::super::cli::PeqModel;
-use super::super::x2peq::x2spl;
+use super::super::iir::BiquadDesign;
+use super::super::x2peq::x2spl_with_design;
 use ndarray::Array1;
 
 /// Data needed by the nonlinear ceiling constraint callback.
@@ -13,6 +14,8 @@ pub struct CeilingConstraintData {
     pub max_db: f64,
     /// PEQ model that defines the filter structure
     pub peq_model: PeqModel,
+    /// Coefficient design of the PEQ filters
+    pub biquad_design: BiquadDesign,
 }
 
 /// Inequality constraint: combined response must not exceed max_db.
@@ -22,7 +25,13 @@ pub fn constraint_ceiling(
     _grad: Option<&mut [f64]>,
     data: &mut CeilingConstraintData,
 ) -> f64 {
-    let peq_spl = x2spl(&data.freqs, x, data.srate, data.peq_model);
+    let peq_spl = x2spl_with_design(
+        &data.freqs,
+        x,
+        data.srate,
+        data.peq_model,
+        data.biquad_design,
+    );
 
     viol_ceiling_from_spl(&peq_spl, data.max_db, data.peq_model)
 }
@@ -210,7 +210,11 @@ pub fn create_smart_initial_guesses(
                 | crate::cli::PeqModel::HpPk
                 | crate::cli::PeqModel::HpPkLp
                 | crate::cli::PeqModel::LsPk
-                | crate::cli::PeqModel::LsPkHs => {
+                | crate::cli::PeqModel::LsPkHs
+                | crate::cli::PeqModel::Hp1Pk
+                | crate::cli::PeqModel::Hp1PkLp1
+                | crate::cli::PeqModel::Ls1PkHs1
+                | crate::cli::PeqModel::LtPk => {
                     // Fixed filter types: [freq, Q, gain]
                     let base_idx = i * 3;
                     let log_freq = freq_var
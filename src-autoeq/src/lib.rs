@@ -32,6 +32,8 @@ pub use autoeq_iir as iir;
 // Re-export Curve from CEA2034 crate to ensure type compatibility
 pub use autoeq_cea2034::Curve;
 
+/// Automatic selection of the number of filters
+pub mod auto_filters;
 /// Common CLI argument definitions shared across binaries
 pub mod cli;
 /// Constraint functions for optimization
@@ -53,12 +55,18 @@ pub mod optim_mh;
 pub mod optim_nlopt;
 /// Parameter vector utilities for different PEQ models
 pub mod param_utils;
+/// Multi-objective optimization of loss, filter count and positive gain
+pub mod pareto;
 /// Plotting and visualization functions
 pub mod plot;
 /// Data reading and parsing functions
 pub mod read;
+/// Sensitivity analysis of the CEA2034 preference score
+pub mod score_sensitivity;
 /// Signal processing utilities
 pub mod signal;
+/// Built-in target curves and target modifiers
+pub mod targets;
 /// Shared workflow steps used by binaries
 pub mod workflow;
 /// Mapping
@@ -66,7 +74,11 @@ pub mod x2peq;
 
 // Re-export commonly used items
 pub use cli::*;
-pub use loss::{HeadphoneLossData, LossType, SpeakerLossData};
+pub use loss::{
+    DriversParams, FrequencyWeighting, HeadphoneLossData, HeadphoneScores, HeadphoneType, LossType,
+    MultiPositionLossData, PositionLoss, SpatialAverage, SpeakerLossData, SubMainsLossData,
+    SubMainsParams,
+};
 pub use optim::*;
 pub use plot::*;
 pub use read::*;
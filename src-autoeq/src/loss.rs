@@ -18,9 +18,11 @@
 
 use crate::Curve;
 use crate::cea2034 as score;
+use crate::iir::{BiquadDesign, Peq};
 use crate::read;
 use clap::ValueEnum;
 use ndarray::Array1;
+use num_complex::Complex64;
 use std::collections::HashMap;
 
 /// The type of loss function to use during optimization
@@ -36,6 +38,23 @@ pub enum LossType {
     HeadphoneScore,
     /// Multi-driver crossover optimization (flatten combined response)
     DriversFlat,
+    /// Subwoofer + mains integration (crossover, delay, polarity and PEQs)
+    SubMainsFlat,
+    /// Flat loss with perceptual frequency weighting and asymmetric peak/dip penalties
+    PerceptualFlat,
+    /// Preference score with an ideal subwoofer (LFX pinned), bass below the sub crossover ignored
+    SpeakerScoreWithSub,
+}
+
+impl LossType {
+    /// True if the parameter vector is a plain list of PEQ filters
+    ///
+    /// Crossover optimizations prepend or replace the filters with their own
+    /// parameters, which smart initialization, spacing reports and the PEQ
+    /// penalties and constraints do not understand.
+    pub fn uses_peq_layout(&self) -> bool {
+        !matches!(self, LossType::DriversFlat | LossType::SubMainsFlat)
+    }
 }
 
 /// Data required for computing speaker score-based loss
@@ -100,28 +119,486 @@ pub struct HeadphoneLossData {
     pub smooth: bool,
     /// Smoothing level as 1/N octave (N in [1..24])
     pub smooth_n: usize,
+    /// Preference model used for the score
+    pub headphone_type: HeadphoneType,
 }
 
 impl HeadphoneLossData {
-    /// Create a new HeadphoneLossData instance
+    /// Create a new HeadphoneLossData instance using the over-ear model
     ///
     /// # Arguments
     /// * `smooth` - Enable smoothing
     /// * `smooth_n` - Smoothing level as 1/N octave
     pub fn new(smooth: bool, smooth_n: usize) -> Self {
-        Self { smooth, smooth_n }
+        Self {
+            smooth,
+            smooth_n,
+            headphone_type: HeadphoneType::OverEar,
+        }
+    }
+
+    /// Select the preference model used for the score
+    pub fn with_headphone_type(mut self, headphone_type: HeadphoneType) -> Self {
+        self.headphone_type = headphone_type;
+        self
+    }
+}
+
+/// Kind of headphone, which selects the preference model of the headphone score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HeadphoneType {
+    /// Around-ear and on-ear headphones (Olive et al. 2013)
+    OverEar,
+    /// In-ear monitors (Olive et al. 2019)
+    InEar,
+}
+
+impl HeadphoneType {
+    /// Guess the headphone type from the name of the target curve
+    ///
+    /// Targets whose file name contains "in-ear", "inear" or "iem" select the
+    /// in-ear model, all others the over-ear model.
+    pub fn from_target_path(target: &std::path::Path) -> Self {
+        let name = target
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if ["in-ear", "inear", "in_ear", "iem"]
+            .iter()
+            .any(|key| name.contains(key))
+        {
+            HeadphoneType::InEar
+        } else {
+            HeadphoneType::OverEar
+        }
+    }
+
+    /// Command line name of the headphone type
+    pub fn name(self) -> &'static str {
+        match self {
+            HeadphoneType::OverEar => "over-ear",
+            HeadphoneType::InEar => "in-ear",
+        }
+    }
+
+    /// Predicted preference rating of a deviation curve with this model
+    ///
+    /// # Arguments
+    /// * `curve` - Deviation from the Harman target matching the headphone type
+    ///
+    /// # Returns
+    /// * Predicted preference rating (higher is better)
+    pub fn score(self, curve: &Curve) -> f64 {
+        match self {
+            HeadphoneType::OverEar => headphone_loss(curve),
+            HeadphoneType::InEar => headphone_loss_in_ear(curve),
+        }
+    }
+}
+
+/// Predicted preference ratings of a deviation curve with both headphone models
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadphoneScores {
+    /// Over-ear / on-ear model (Olive et al. 2013)
+    pub over_ear: f64,
+    /// In-ear model (Olive et al. 2019)
+    pub in_ear: f64,
+}
+
+impl HeadphoneScores {
+    /// Score a deviation curve with both models
+    pub fn new(curve: &Curve) -> Self {
+        Self {
+            over_ear: headphone_loss(curve),
+            in_ear: headphone_loss_in_ear(curve),
+        }
+    }
+
+    /// Score of the given headphone type
+    pub fn get(&self, headphone_type: HeadphoneType) -> f64 {
+        match headphone_type {
+            HeadphoneType::OverEar => self.over_ear,
+            HeadphoneType::InEar => self.in_ear,
+        }
+    }
+}
+
+/// How measurements taken at several listening positions are averaged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SpatialAverage {
+    /// Average of the energy (linear power) at each frequency
+    Power,
+    /// Average of the SPL in dB at each frequency
+    Db,
+}
+
+/// Frequency weighting of the perceptual flat loss
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FrequencyWeighting {
+    /// Every frequency point has the same weight
+    None,
+    /// Weight proportional to the number of ERB bands covered by each point
+    Erb,
+    /// Weight following the sensitivity of hearing (ISO 226 equal-loudness contour)
+    EqualLoudness,
+}
+
+/// Data required for the perceptual flat loss
+#[derive(Debug, Clone)]
+pub struct PerceptualLossData {
+    /// Weight of each frequency point, normalized to a mean of 1
+    pub weights: Array1<f64>,
+    /// Weight of positive errors (peaks above the target)
+    pub peak_weight: f64,
+    /// Weight of negative errors (dips below the target)
+    pub dip_weight: f64,
+    /// Dips narrower than this width in octaves are not counted
+    pub dip_min_width_oct: Option<f64>,
+}
+
+impl PerceptualLossData {
+    /// Create a new PerceptualLossData instance
+    ///
+    /// # Arguments
+    /// * `freqs` - Frequency grid of the loss
+    /// * `weighting` - Frequency weighting
+    /// * `peak_weight` - Weight of peaks (positive errors)
+    /// * `dip_weight` - Weight of dips (negative errors)
+    /// * `dip_min_width_oct` - Optional width in octaves below which dips are ignored
+    pub fn new(
+        freqs: &Array1<f64>,
+        weighting: FrequencyWeighting,
+        peak_weight: f64,
+        dip_weight: f64,
+        dip_min_width_oct: Option<f64>,
+    ) -> Self {
+        let weights = match weighting {
+            FrequencyWeighting::None => Array1::ones(freqs.len()),
+            FrequencyWeighting::Erb => erb_weights(freqs),
+            FrequencyWeighting::EqualLoudness => equal_loudness_weights(freqs, EQUAL_LOUDNESS_PHON),
+        };
+        let mean = weights.mean().unwrap_or(1.0);
+        Self {
+            weights: if mean > 0.0 { weights / mean } else { weights },
+            peak_weight,
+            dip_weight,
+            dip_min_width_oct,
+        }
     }
 }
 
+/// Loudness level of the equal-loudness contour used for weighting, in phon
+const EQUAL_LOUDNESS_PHON: f64 = 80.0;
+
+/// ERB-number scale (Glasberg & Moore 1990)
+fn erb_number(f: f64) -> f64 {
+    21.4 * (1.0 + 0.00437 * f).log10()
+}
+
+/// Number of ERB bands covered by each point of a frequency grid
+///
+/// Each point covers the interval between the geometric means with its
+/// neighbours, so the weights do not depend on the density of the grid.
+fn erb_weights(freqs: &Array1<f64>) -> Array1<f64> {
+    let n = freqs.len();
+    if n < 2 {
+        return Array1::ones(n);
+    }
+    let edge = |i: usize, j: usize| (freqs[i].max(1e-6) * freqs[j].max(1e-6)).sqrt();
+    Array1::from_shape_fn(n, |i| {
+        let lo = if i == 0 {
+            freqs[0] * freqs[0] / edge(0, 1)
+        } else {
+            edge(i - 1, i)
+        };
+        let hi = if i == n - 1 {
+            freqs[n - 1] * freqs[n - 1] / edge(n - 2, n - 1)
+        } else {
+            edge(i, i + 1)
+        };
+        (erb_number(hi) - erb_number(lo)).abs()
+    })
+}
+
+/// ISO 226:2003 third-octave frequencies and equal-loudness parameters
+const ISO226_FREQ: [f64; 29] = [
+    20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+    500.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3150.0, 4000.0, 5000.0, 6300.0,
+    8000.0, 10000.0, 12500.0,
+];
+const ISO226_AF: [f64; 29] = [
+    0.532, 0.506, 0.480, 0.455, 0.432, 0.409, 0.387, 0.367, 0.349, 0.330, 0.315, 0.301, 0.288,
+    0.276, 0.267, 0.259, 0.253, 0.250, 0.246, 0.244, 0.243, 0.243, 0.243, 0.242, 0.242, 0.245,
+    0.254, 0.271, 0.301,
+];
+const ISO226_LU: [f64; 29] = [
+    -31.6, -27.2, -23.0, -19.1, -15.9, -13.0, -10.3, -8.1, -6.2, -4.5, -3.1, -2.0, -1.1, -0.4, 0.0,
+    0.3, 0.5, 0.0, -2.7, -4.1, -1.0, 1.7, 2.5, 1.2, -2.1, -7.1, -11.2, -10.7, -3.1,
+];
+const ISO226_TF: [f64; 29] = [
+    78.5, 68.7, 59.5, 51.1, 44.0, 37.5, 31.5, 26.5, 22.1, 17.9, 14.4, 11.4, 8.6, 6.2, 4.4, 3.0,
+    2.2, 2.4, 3.5, 1.7, -1.3, -4.2, -6.0, -5.4, -1.5, 6.0, 12.6, 13.9, 12.3,
+];
+
+/// SPL of the ISO 226:2003 equal-loudness contour at `phon`, at the ISO frequencies
+fn iso226_contour(phon: f64) -> [f64; 29] {
+    std::array::from_fn(|i| {
+        let af = ISO226_AF[i];
+        let lu = ISO226_LU[i];
+        let tf = ISO226_TF[i];
+        let a = 4.47e-3 * (10f64.powf(0.025 * phon) - 1.15)
+            + (0.4 * 10f64.powf((tf + lu) / 10.0 - 9.0)).powf(af);
+        10.0 / af * a.log10() - lu + 94.0
+    })
+}
+
+/// Sensitivity of hearing relative to 1 kHz at each frequency
+///
+/// A frequency that needs `x` dB more SPL than 1 kHz to sound as loud gets a
+/// weight of `10^(-x/20)`. The contour is interpolated in log frequency and
+/// held constant outside 20 Hz to 12.5 kHz.
+fn equal_loudness_weights(freqs: &Array1<f64>, phon: f64) -> Array1<f64> {
+    let contour = iso226_contour(phon);
+    let log_f: Vec<f64> = ISO226_FREQ.iter().map(|f| f.log10()).collect();
+    let reference = contour[17]; // 1 kHz
+    freqs.mapv(|f| {
+        let x = f.max(1e-6).log10();
+        let spl = if x <= log_f[0] {
+            contour[0]
+        } else if x >= log_f[28] {
+            contour[28]
+        } else {
+            let k = log_f.iter().position(|&v| v > x).unwrap();
+            let t = (x - log_f[k - 1]) / (log_f[k] - log_f[k - 1]);
+            contour[k - 1] + t * (contour[k] - contour[k - 1])
+        };
+        10f64.powf(-(spl - reference) / 20.0)
+    })
+}
+
+/// How the errors at several listening positions are combined into one loss
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PositionLoss {
+    /// Flat loss of the spatial average only
+    Average,
+    /// Flat loss of the spatial average plus the spread of the per position losses
+    MeanVariance,
+    /// Largest flat loss over all positions
+    Minimax,
+}
+
+/// Data required for multi-position room EQ
+#[derive(Debug, Clone)]
+pub struct MultiPositionLossData {
+    /// Deviation (target - measurement) at each position
+    pub deviations: Vec<Array1<f64>>,
+    /// Weight of each position, normalized to a sum of 1
+    pub weights: Vec<f64>,
+    /// How the per position losses are combined
+    pub position_loss: PositionLoss,
+    /// Weight of the spread (standard deviation of the per position losses)
+    /// for `PositionLoss::MeanVariance`
+    pub spread_weight: f64,
+}
+
+impl MultiPositionLossData {
+    /// Create a new MultiPositionLossData instance
+    ///
+    /// # Arguments
+    /// * `deviations` - Deviation (target - measurement) at each position
+    /// * `weights` - Weight of each position; empty means equal weights
+    /// * `position_loss` - How the per position losses are combined
+    /// * `spread_weight` - Weight of the spread for `PositionLoss::MeanVariance`
+    pub fn new(
+        deviations: Vec<Array1<f64>>,
+        weights: &[f64],
+        position_loss: PositionLoss,
+        spread_weight: f64,
+    ) -> Self {
+        assert!(!deviations.is_empty(), "Need at least one position");
+        let weights = normalized_weights(weights, deviations.len());
+        Self {
+            deviations,
+            weights,
+            position_loss,
+            spread_weight,
+        }
+    }
+}
+
+/// Normalize position weights to a sum of 1 (equal weights when empty)
+pub(crate) fn normalized_weights(weights: &[f64], count: usize) -> Vec<f64> {
+    if weights.is_empty() {
+        return vec![1.0 / count as f64; count];
+    }
+    assert_eq!(weights.len(), count, "Need one weight per position");
+    let total: f64 = weights.iter().sum();
+    assert!(total > 0.0, "Position weights must have a positive sum");
+    weights.iter().map(|w| w / total).collect()
+}
+
 /// Crossover filter type for multi-driver optimization
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CrossoverType {
+    /// 1st order Butterworth (6 dB/octave)
+    Butterworth1,
     /// 2nd order Butterworth (12 dB/octave)
     Butterworth2,
+    /// 3rd order Butterworth (18 dB/octave)
+    Butterworth3,
+    /// 4th order Butterworth (24 dB/octave)
+    Butterworth4,
     /// 2nd order Linkwitz-Riley (12 dB/octave)
     LinkwitzRiley2,
     /// 4th order Linkwitz-Riley (24 dB/octave)
     LinkwitzRiley4,
+    /// 8th order Linkwitz-Riley (48 dB/octave)
+    LinkwitzRiley8,
+}
+
+impl CrossoverType {
+    /// All crossover types with their CLI name and short alias
+    pub const ALL: [(CrossoverType, &'static str, &'static str); 7] = [
+        (CrossoverType::Butterworth1, "butterworth1", "bw1"),
+        (CrossoverType::Butterworth2, "butterworth2", "bw2"),
+        (CrossoverType::Butterworth3, "butterworth3", "bw3"),
+        (CrossoverType::Butterworth4, "butterworth4", "bw4"),
+        (CrossoverType::LinkwitzRiley2, "linkwitzriley2", "lr2"),
+        (CrossoverType::LinkwitzRiley4, "linkwitzriley4", "lr4"),
+        (CrossoverType::LinkwitzRiley8, "linkwitzriley8", "lr8"),
+    ];
+
+    /// Look up a crossover type by its CLI name or short alias (e.g. `linkwitzriley4` or `lr4`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL
+            .iter()
+            .find(|(_, long, short)| name == *long || name == *short)
+            .map(|(t, _, _)| *t)
+    }
+
+    /// CLI name of the crossover type
+    pub fn name(&self) -> &'static str {
+        Self::ALL.iter().find(|(t, _, _)| t == self).unwrap().1
+    }
+
+    /// Human readable description, e.g. "4th order Linkwitz-Riley"
+    pub fn description(&self) -> &'static str {
+        match self {
+            CrossoverType::Butterworth1 => "1st order Butterworth",
+            CrossoverType::Butterworth2 => "2nd order Butterworth",
+            CrossoverType::Butterworth3 => "3rd order Butterworth",
+            CrossoverType::Butterworth4 => "4th order Butterworth",
+            CrossoverType::LinkwitzRiley2 => "2nd order Linkwitz-Riley",
+            CrossoverType::LinkwitzRiley4 => "4th order Linkwitz-Riley",
+            CrossoverType::LinkwitzRiley8 => "8th order Linkwitz-Riley",
+        }
+    }
+
+    /// Lowpass section of the crossover at `freq`
+    pub fn lowpass(&self, freq: f64, sample_rate: f64) -> Peq {
+        use crate::iir::{peq_butterworth_lowpass, peq_linkwitzriley_lowpass};
+        match self {
+            CrossoverType::Butterworth1 => peq_butterworth_lowpass(1, freq, sample_rate),
+            CrossoverType::Butterworth2 => peq_butterworth_lowpass(2, freq, sample_rate),
+            CrossoverType::Butterworth3 => peq_butterworth_lowpass(3, freq, sample_rate),
+            CrossoverType::Butterworth4 => peq_butterworth_lowpass(4, freq, sample_rate),
+            CrossoverType::LinkwitzRiley2 => peq_linkwitzriley_lowpass(2, freq, sample_rate),
+            CrossoverType::LinkwitzRiley4 => peq_linkwitzriley_lowpass(4, freq, sample_rate),
+            CrossoverType::LinkwitzRiley8 => peq_linkwitzriley_lowpass(8, freq, sample_rate),
+        }
+    }
+
+    /// Highpass section of the crossover at `freq`
+    pub fn highpass(&self, freq: f64, sample_rate: f64) -> Peq {
+        use crate::iir::{peq_butterworth_highpass, peq_linkwitzriley_highpass};
+        match self {
+            CrossoverType::Butterworth1 => peq_butterworth_highpass(1, freq, sample_rate),
+            CrossoverType::Butterworth2 => peq_butterworth_highpass(2, freq, sample_rate),
+            CrossoverType::Butterworth3 => peq_butterworth_highpass(3, freq, sample_rate),
+            CrossoverType::Butterworth4 => peq_butterworth_highpass(4, freq, sample_rate),
+            CrossoverType::LinkwitzRiley2 => peq_linkwitzriley_highpass(2, freq, sample_rate),
+            CrossoverType::LinkwitzRiley4 => peq_linkwitzriley_highpass(4, freq, sample_rate),
+            CrossoverType::LinkwitzRiley8 => peq_linkwitzriley_highpass(8, freq, sample_rate),
+        }
+    }
+}
+
+/// Crossover between two adjacent drivers
+///
+/// The lower driver gets the lowpass and the upper driver the highpass. The two
+/// sides may use different types to build asymmetric slopes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crossover {
+    /// Lowpass applied to the lower driver
+    pub lowpass: CrossoverType,
+    /// Highpass applied to the upper driver
+    pub highpass: CrossoverType,
+}
+
+impl From<CrossoverType> for Crossover {
+    fn from(crossover_type: CrossoverType) -> Self {
+        Self {
+            lowpass: crossover_type,
+            highpass: crossover_type,
+        }
+    }
+}
+
+impl std::fmt::Display for Crossover {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.lowpass == self.highpass {
+            write!(f, "{}", self.lowpass.name())
+        } else {
+            write!(f, "{}/{}", self.lowpass.name(), self.highpass.name())
+        }
+    }
+}
+
+impl Crossover {
+    /// Parse a crossover, either `type` or `lowpass/highpass` for asymmetric slopes
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let parse_type = |name: &str| {
+            CrossoverType::from_name(name).ok_or_else(|| {
+                let names: Vec<_> = CrossoverType::ALL.iter().map(|(_, n, _)| *n).collect();
+                format!(
+                    "Invalid crossover type '{}'. Valid types: {}",
+                    name.trim(),
+                    names.join(", ")
+                )
+            })
+        };
+        match spec.split_once('/') {
+            Some((lowpass, highpass)) => Ok(Self {
+                lowpass: parse_type(lowpass)?,
+                highpass: parse_type(highpass)?,
+            }),
+            None => parse_type(spec).map(Self::from),
+        }
+    }
+
+    /// Parse a comma separated list of crossovers
+    ///
+    /// # Arguments
+    /// * `spec` - One crossover for all driver pairs, or one per pair from low to high
+    /// * `n_crossovers` - Number of driver pairs
+    ///
+    /// # Returns
+    /// * One crossover per driver pair, or an error message
+    pub fn parse_list(spec: &str, n_crossovers: usize) -> Result<Vec<Self>, String> {
+        let crossovers = spec
+            .split(',')
+            .map(Self::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        match crossovers.len() {
+            1 => Ok(vec![crossovers[0]; n_crossovers]),
+            n if n == n_crossovers => Ok(crossovers),
+            n => Err(format!(
+                "Got {} crossover types for {} crossovers, expected 1 or {}",
+                n, n_crossovers, n_crossovers
+            )),
+        }
+    }
 }
 
 /// Measurement data for a single driver
@@ -133,6 +610,8 @@ pub struct DriverMeasurement {
     pub spl: Array1<f64>,
     /// Phase measurements in degrees (optional for now)
     pub phase: Option<Array1<f64>>,
+    /// Optional display name (e.g. "woofer")
+    pub name: Option<String>,
 }
 
 impl DriverMeasurement {
@@ -142,7 +621,18 @@ impl DriverMeasurement {
         if let Some(ref p) = phase {
             assert_eq!(freq.len(), p.len(), "freq and phase must have same length");
         }
-        Self { freq, spl, phase }
+        Self {
+            freq,
+            spl,
+            phase,
+            name: None,
+        }
+    }
+
+    /// Set the display name of the driver
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
     }
 
     /// Get the frequency range covered by this driver
@@ -157,6 +647,28 @@ impl DriverMeasurement {
         let (min_freq, max_freq) = self.freq_range();
         (min_freq * max_freq).sqrt()
     }
+
+    /// Unwrapped phase in degrees interpolated on `freq_grid`, if measured
+    pub fn interpolate_phase(&self, freq_grid: &Array1<f64>) -> Option<Array1<f64>> {
+        self.phase.as_ref().map(|phase| {
+            crate::read::interpolate_log_space(
+                freq_grid,
+                &Curve {
+                    freq: self.freq.clone(),
+                    spl: crate::iir::unwrap_phase(phase),
+                },
+            )
+            .spl
+        })
+    }
+}
+
+/// Complex response from a magnitude in dB and a phase in degrees
+fn complex_response(spl: &Array1<f64>, phase: &Array1<f64>) -> Array1<Complex64> {
+    spl.iter()
+        .zip(phase.iter())
+        .map(|(db, deg)| Complex64::from_polar(10.0_f64.powf(db / 20.0), deg.to_radians()))
+        .collect()
 }
 
 /// Data required for multi-driver crossover optimization
@@ -164,24 +676,46 @@ impl DriverMeasurement {
 pub struct DriversLossData {
     /// Measurements for each driver (sorted by frequency range, lowest first)
     pub drivers: Vec<DriverMeasurement>,
-    /// Crossover type to use between driver pairs
-    pub crossover_type: CrossoverType,
+    /// Crossover between each pair of adjacent drivers, lowest pair first
+    pub crossovers: Vec<Crossover>,
     /// Common frequency grid for evaluation
     pub freq_grid: Array1<f64>,
+    /// Complex response of each driver on the grid, normalized like the SPL
+    /// (zero phase when the measurement has no phase column)
+    pub responses: Vec<Array1<Complex64>>,
 }
 
 impl DriversLossData {
-    /// Create a new DriversLossData instance
+    /// Create a new DriversLossData instance with the same crossover between all drivers
     ///
     /// # Arguments
     /// * `drivers` - Vector of driver measurements (will be sorted by frequency)
     /// * `crossover_type` - Type of crossover filter to use
-    pub fn new(mut drivers: Vec<DriverMeasurement>, crossover_type: CrossoverType) -> Self {
+    pub fn new(drivers: Vec<DriverMeasurement>, crossover_type: CrossoverType) -> Self {
+        let n_crossovers = drivers.len().saturating_sub(1);
+        Self::with_crossovers(drivers, vec![crossover_type.into(); n_crossovers])
+    }
+
+    /// Create a new DriversLossData instance with one crossover per driver pair
+    ///
+    /// # Arguments
+    /// * `drivers` - Vector of driver measurements (will be sorted by frequency)
+    /// * `crossovers` - Crossover between each pair of adjacent drivers after sorting,
+    ///   lowest pair first
+    pub fn with_crossovers(
+        mut drivers: Vec<DriverMeasurement>,
+        crossovers: Vec<Crossover>,
+    ) -> Self {
         assert!(
-            drivers.len() >= 2 && drivers.len() <= 4,
-            "Must have 2-4 drivers, got {}",
+            drivers.len() >= 2,
+            "Must have at least 2 drivers, got {}",
             drivers.len()
         );
+        assert_eq!(
+            crossovers.len(),
+            drivers.len() - 1,
+            "Must have one crossover per driver pair"
+        );
 
         // Sort drivers by their mean frequency (woofer -> midrange -> tweeter)
         drivers.sort_by(|a, b| {
@@ -208,10 +742,235 @@ impl DriversLossData {
             max_freq.min(20000.0),
         );
 
+        let responses = drivers
+            .iter()
+            .map(|driver| {
+                let spl = crate::read::normalize_and_interpolate_response(
+                    &freq_grid,
+                    &Curve {
+                        freq: driver.freq.clone(),
+                        spl: driver.spl.clone(),
+                    },
+                )
+                .spl;
+                let phase = driver
+                    .interpolate_phase(&freq_grid)
+                    .unwrap_or_else(|| Array1::zeros(freq_grid.len()));
+                complex_response(&spl, &phase)
+            })
+            .collect();
+
         Self {
             drivers,
-            crossover_type,
+            crossovers,
             freq_grid,
+            responses,
+        }
+    }
+
+    /// Display name of driver `i`: its own name or "Driver i+1"
+    pub fn driver_name(&self, i: usize) -> String {
+        self.drivers[i]
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Driver {}", i + 1))
+    }
+}
+
+/// Gains, crossovers and alignment decoded from a multi-driver parameter vector
+///
+/// Layout for N drivers: `[gain1..gainN, log10(xover1)..log10(xover(N-1)),
+/// delay1..delayN (ms), polarity1..polarityN]`, a polarity >= 0.5 meaning inverted.
+#[derive(Debug, Clone)]
+pub struct DriversParams {
+    /// Gain in dB for each driver
+    pub gains: Vec<f64>,
+    /// Crossover frequencies in Hz between successive driver pairs
+    pub crossover_freqs: Vec<f64>,
+    /// Delay in ms for each driver
+    pub delays_ms: Vec<f64>,
+    /// True for each driver whose polarity is inverted
+    pub inverted: Vec<bool>,
+}
+
+impl DriversParams {
+    /// Drivers with gains and crossovers only (no delay, normal polarity)
+    pub fn new(gains: Vec<f64>, crossover_freqs: Vec<f64>) -> Self {
+        let n_drivers = gains.len();
+        Self {
+            gains,
+            crossover_freqs,
+            delays_ms: vec![0.0; n_drivers],
+            inverted: vec![false; n_drivers],
+        }
+    }
+
+    /// Number of optimization parameters for `n_drivers` drivers
+    pub fn num_params(n_drivers: usize) -> usize {
+        4 * n_drivers - 1
+    }
+
+    /// Decode a parameter vector for `n_drivers` drivers
+    pub fn from_x(n_drivers: usize, x: &[f64]) -> Self {
+        assert_eq!(
+            x.len(),
+            Self::num_params(n_drivers),
+            "Expected {} parameters for {} drivers, got {}",
+            Self::num_params(n_drivers),
+            n_drivers,
+            x.len()
+        );
+        let (gains, rest) = x.split_at(n_drivers);
+        let (xovers, rest) = rest.split_at(n_drivers - 1);
+        let (delays, polarities) = rest.split_at(n_drivers);
+        Self {
+            gains: gains.to_vec(),
+            crossover_freqs: xovers.iter().map(|f| 10.0_f64.powf(*f)).collect(),
+            delays_ms: delays.to_vec(),
+            inverted: polarities.iter().map(|p| *p >= 0.5).collect(),
+        }
+    }
+}
+
+/// Number of alignment parameters at the start of a sub + mains parameter vector
+///
+/// Layout: `[log10(crossover), sub gain (dB), sub delay (ms), polarity, mains PEQ, sub PEQ]`
+/// where both PEQs are peak filters stored as `[log10(freq), Q, gain]` triplets.
+pub const SUB_MAINS_ALIGNMENT_PARAMS: usize = 4;
+
+/// Data required for subwoofer + mains integration at the listening position
+#[derive(Debug, Clone)]
+pub struct SubMainsLossData {
+    /// Common frequency grid for evaluation
+    pub freq_grid: Array1<f64>,
+    /// Complex response of the mains on the grid
+    pub mains: Array1<Complex64>,
+    /// Complex response of the subwoofer on the grid
+    pub sub: Array1<Complex64>,
+    /// Crossover between the sub (lowpass) and the mains (highpass)
+    pub crossover: Crossover,
+    /// Number of PEQ filters on the mains
+    pub num_mains_filters: usize,
+    /// Number of PEQ filters on the subwoofer
+    pub num_sub_filters: usize,
+    /// Coefficient design of the PEQ filters
+    pub biquad_design: BiquadDesign,
+}
+
+impl SubMainsLossData {
+    /// Create a new SubMainsLossData instance
+    ///
+    /// # Arguments
+    /// * `mains` - Mains measurement at the listening position, with phase
+    /// * `sub` - Subwoofer measurement at the listening position, with phase
+    /// * `crossover` - Crossover between the sub and the mains
+    /// * `num_mains_filters` - Number of PEQ filters on the mains
+    /// * `num_sub_filters` - Number of PEQ filters on the subwoofer
+    ///
+    /// # Returns
+    /// * The loss data on a log grid covering both measurements, or an error
+    ///   if a measurement has no phase
+    pub fn new(
+        mains: &DriverMeasurement,
+        sub: &DriverMeasurement,
+        crossover: Crossover,
+        num_mains_filters: usize,
+        num_sub_filters: usize,
+    ) -> Result<Self, String> {
+        let (mains_min, mains_max) = mains.freq_range();
+        let (sub_min, _) = sub.freq_range();
+        let freq_grid = crate::read::create_log_frequency_grid(
+            200,
+            mains_min.min(sub_min).max(20.0),
+            mains_max.min(20000.0),
+        );
+        let to_complex = |m: &DriverMeasurement, name: &str| -> Result<Array1<Complex64>, String> {
+            let phase = m
+                .interpolate_phase(&freq_grid)
+                .ok_or_else(|| format!("{} measurement has no phase column", name))?;
+            let spl = crate::read::interpolate_log_space(
+                &freq_grid,
+                &Curve {
+                    freq: m.freq.clone(),
+                    spl: m.spl.clone(),
+                },
+            );
+            Ok(complex_response(&spl.spl, &phase))
+        };
+        Ok(Self {
+            mains: to_complex(mains, "Mains")?,
+            sub: to_complex(sub, "Sub")?,
+            freq_grid,
+            crossover,
+            num_mains_filters,
+            num_sub_filters,
+            biquad_design: BiquadDesign::Rbj,
+        })
+    }
+
+    /// Select the coefficient design of the mains and sub PEQs
+    pub fn with_biquad_design(mut self, biquad_design: BiquadDesign) -> Self {
+        self.biquad_design = biquad_design;
+        self
+    }
+
+    /// Total number of optimization parameters
+    pub fn num_params(&self) -> usize {
+        SUB_MAINS_ALIGNMENT_PARAMS + 3 * (self.num_mains_filters + self.num_sub_filters)
+    }
+}
+
+/// Crossover, alignment and PEQs decoded from a sub + mains parameter vector
+#[derive(Debug, Clone)]
+pub struct SubMainsParams {
+    /// Crossover frequency in Hz
+    pub crossover_freq: f64,
+    /// Gain of the subwoofer relative to the mains in dB
+    pub sub_gain_db: f64,
+    /// Delay of the subwoofer relative to the mains in ms (negative delays the mains)
+    pub sub_delay_ms: f64,
+    /// True if the subwoofer polarity is inverted
+    pub sub_inverted: bool,
+    /// PEQ applied to the mains (without the crossover highpass)
+    pub mains_peq: Peq,
+    /// PEQ applied to the subwoofer (without the crossover lowpass)
+    pub sub_peq: Peq,
+}
+
+impl SubMainsParams {
+    /// Decode a parameter vector
+    ///
+    /// # Arguments
+    /// * `data` - Sub + mains loss data (gives the number of filters)
+    /// * `x` - Parameter vector, see [`SUB_MAINS_ALIGNMENT_PARAMS`] for the layout
+    /// * `sample_rate` - Sample rate for filter design
+    pub fn from_x(data: &SubMainsLossData, x: &[f64], sample_rate: f64) -> Self {
+        assert_eq!(
+            x.len(),
+            data.num_params(),
+            "Expected {} sub + mains parameters, got {}",
+            data.num_params(),
+            x.len()
+        );
+        let split = SUB_MAINS_ALIGNMENT_PARAMS + 3 * data.num_mains_filters;
+        let model = crate::cli::PeqModel::Pk;
+        Self {
+            crossover_freq: 10.0_f64.powf(x[0]),
+            sub_gain_db: x[1],
+            sub_delay_ms: x[2],
+            sub_inverted: x[3] >= 0.5,
+            mains_peq: crate::x2peq::x2peq_with_design(
+                &x[SUB_MAINS_ALIGNMENT_PARAMS..split],
+                sample_rate,
+                model,
+                data.biquad_design,
+            ),
+            sub_peq: crate::x2peq::x2peq_with_design(
+                &x[split..],
+                sample_rate,
+                model,
+                data.biquad_design,
+            ),
         }
     }
 }
@@ -230,6 +989,164 @@ pub fn flat_loss(freqs: &Array1<f64>, error: &Array1<f64>, min_freq: f64, max_fr
     weighted_mse(freqs, error, min_freq, max_freq)
 }
 
+/// Compute the perceptual flat loss within a specified frequency range
+///
+/// Weighted RMS of the error where each point is weighted by the frequency
+/// weighting of `data`, and peaks and dips by their own weights. Dips narrower
+/// than `data.dip_min_width_oct` octaves are not counted: they are hard to hear
+/// and filling them needs large boosts.
+///
+/// # Arguments
+/// * `data` - Perceptual weights (computed on `freqs`)
+/// * `freqs` - Frequency points in Hz
+/// * `error` - Error values at each frequency point (corrected - target)
+/// * `min_freq` - Minimum frequency in Hz (inclusive)
+/// * `max_freq` - Maximum frequency in Hz (inclusive)
+///
+/// # Returns
+/// * Weighted RMS error, 0.0 if no point is in range
+pub fn perceptual_flat_loss(
+    data: &PerceptualLossData,
+    freqs: &Array1<f64>,
+    error: &Array1<f64>,
+    min_freq: f64,
+    max_freq: f64,
+) -> f64 {
+    debug_assert_eq!(freqs.len(), error.len());
+    debug_assert_eq!(freqs.len(), data.weights.len());
+    let ignored = match data.dip_min_width_oct {
+        Some(width) if width > 0.0 => narrow_dips(freqs, error, width),
+        _ => vec![false; freqs.len()],
+    };
+
+    let mut sum = 0.0;
+    let mut total_weight = 0.0;
+    for i in 0..freqs.len() {
+        if freqs[i] < min_freq || freqs[i] > max_freq {
+            continue;
+        }
+        let w = data.weights[i];
+        total_weight += w;
+        if ignored[i] {
+            continue;
+        }
+        let e = error[i];
+        let sign_weight = if e >= 0.0 {
+            data.peak_weight
+        } else {
+            data.dip_weight
+        };
+        sum += w * sign_weight * e * e;
+    }
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+    (sum / total_weight).sqrt()
+}
+
+/// Flag the points that belong to a dip narrower than `min_width_oct` octaves
+///
+/// A dip is a run of consecutive negative errors; its width is measured between
+/// the zero crossings interpolated in log frequency.
+fn narrow_dips(freqs: &Array1<f64>, error: &Array1<f64>, min_width_oct: f64) -> Vec<bool> {
+    let n = freqs.len();
+    let mut ignored = vec![false; n];
+    let crossing = |i: usize, j: usize| {
+        // log2 frequency where the error crosses zero between points i and j
+        let (a, b) = (error[i], error[j]);
+        let t = if a != b { a / (a - b) } else { 0.5 };
+        let (la, lb) = (freqs[i].max(1e-6).log2(), freqs[j].max(1e-6).log2());
+        la + t * (lb - la)
+    };
+    let mut i = 0;
+    while i < n {
+        if error[i] >= 0.0 {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < n && error[i] < 0.0 {
+            i += 1;
+        }
+        let end = i - 1;
+        // Dips that touch the ends of the grid are considered wide
+        if start == 0 || i == n {
+            continue;
+        }
+        let width = crossing(end, end + 1) - crossing(start - 1, start);
+        if width < min_width_oct {
+            ignored[start..=end].fill(true);
+        }
+    }
+    ignored
+}
+
+/// Compute the flat loss at each listening position
+///
+/// # Arguments
+/// * `data` - Multi-position data
+/// * `freqs` - Frequency points in Hz
+/// * `peq_spl` - Response of the candidate PEQ in dB
+/// * `min_freq` - Minimum frequency in Hz (inclusive)
+/// * `max_freq` - Maximum frequency in Hz (inclusive)
+///
+/// # Returns
+/// * One flat loss per position, in the order of `data.deviations`
+pub fn position_losses(
+    data: &MultiPositionLossData,
+    freqs: &Array1<f64>,
+    peq_spl: &Array1<f64>,
+    min_freq: f64,
+    max_freq: f64,
+) -> Vec<f64> {
+    data.deviations
+        .iter()
+        .map(|deviation| flat_loss(freqs, &(peq_spl - deviation), min_freq, max_freq))
+        .collect()
+}
+
+/// Compute the flat loss over several listening positions
+///
+/// # Arguments
+/// * `data` - Multi-position data
+/// * `freqs` - Frequency points in Hz
+/// * `average_error` - PEQ response minus the deviation of the spatial average
+/// * `peq_spl` - Response of the candidate PEQ in dB
+/// * `min_freq` - Minimum frequency in Hz (inclusive)
+/// * `max_freq` - Maximum frequency in Hz (inclusive)
+///
+/// # Returns
+/// * `Average`: flat loss of the spatial average
+/// * `MeanVariance`: the same plus `spread_weight` times the weighted standard
+///   deviation of the per position losses
+/// * `Minimax`: the largest per position loss
+pub fn multi_position_flat_loss(
+    data: &MultiPositionLossData,
+    freqs: &Array1<f64>,
+    average_error: &Array1<f64>,
+    peq_spl: &Array1<f64>,
+    min_freq: f64,
+    max_freq: f64,
+) -> f64 {
+    let average = flat_loss(freqs, average_error, min_freq, max_freq);
+    match data.position_loss {
+        PositionLoss::Average => average,
+        PositionLoss::MeanVariance => {
+            let losses = position_losses(data, freqs, peq_spl, min_freq, max_freq);
+            let mean: f64 = losses.iter().zip(&data.weights).map(|(l, w)| l * w).sum();
+            let variance: f64 = losses
+                .iter()
+                .zip(&data.weights)
+                .map(|(l, w)| w * (l - mean).powi(2))
+                .sum();
+            average + data.spread_weight * variance.sqrt()
+        }
+        PositionLoss::Minimax => position_losses(data, freqs, peq_spl, min_freq, max_freq)
+            .into_iter()
+            .fold(0.0, f64::max),
+    }
+}
+
 /// Compute the score-based loss.
 /// `peq_response` must be computed for the candidate parameters.
 pub fn speaker_score_loss(
@@ -264,6 +1181,32 @@ pub fn speaker_score_loss(
     metrics.pref_score
 }
 
+/// Compute the preference score of a speaker crossed over to a subwoofer
+///
+/// # Arguments
+/// * `score_data` - On axis, listening window, sound power and in-room curves
+/// * `freq` - Frequency grid of the curves
+/// * `peq_response` - PEQ response on `freq`
+/// * `sub_crossover` - Crossover frequency in Hz; the response below it is not scored
+///
+/// # Returns
+/// * Preference score with an ideal subwoofer below the crossover
+pub fn speaker_score_with_sub(
+    score_data: &SpeakerLossData,
+    freq: &Array1<f64>,
+    peq_response: &Array1<f64>,
+    sub_crossover: f64,
+) -> f64 {
+    let intervals = score::octave_intervals(2, freq);
+    score::pref_score_with_sub(
+        freq,
+        &intervals,
+        &(&score_data.on + peq_response),
+        &(&score_data.pir + peq_response),
+        sub_crossover,
+    )
+}
+
 /// Compute a mixed loss based on flatness on lw and pir
 pub fn mixed_loss(
     score_data: &SpeakerLossData,
@@ -393,117 +1336,101 @@ pub fn curve_slope_per_octave_in_range(curve: &crate::Curve, fmin: f64, fmax: f6
     regression_slope_per_octave_in_range(&curve.freq, &curve.spl, fmin, fmax)
 }
 
-/// Compute the combined frequency response of multiple drivers with crossovers and gains
+/// Compute the complex response of each driver after gain, crossover, delay and polarity
 ///
 /// # Arguments
-/// * `data` - DriversLossData containing driver measurements and crossover type
-/// * `gains` - Gain in dB for each driver
-/// * `crossover_freqs` - Crossover frequencies between successive driver pairs
+/// * `data` - DriversLossData containing driver measurements and crossovers
+/// * `params` - Gains, crossover frequencies, delays and polarities
 /// * `sample_rate` - Sample rate for filter design
 ///
 /// # Returns
-/// * Combined frequency response in dB on the common frequency grid
-pub fn compute_drivers_combined_response(
+/// * One complex response per driver on the common frequency grid
+pub fn compute_drivers_responses(
     data: &DriversLossData,
-    gains: &[f64],
-    crossover_freqs: &[f64],
+    params: &DriversParams,
     sample_rate: f64,
-) -> Array1<f64> {
-    use crate::iir::{
-        compute_peq_response, peq_butterworth_highpass, peq_butterworth_lowpass,
-        peq_linkwitzriley_highpass, peq_linkwitzriley_lowpass,
-    };
+) -> Vec<Array1<Complex64>> {
+    use crate::iir::compute_peq_complex_response;
 
     let n_drivers = data.drivers.len();
     assert_eq!(
-        gains.len(),
+        params.gains.len(),
         n_drivers,
         "Must have one gain per driver (got {} gains for {} drivers)",
-        gains.len(),
+        params.gains.len(),
         n_drivers
     );
     assert_eq!(
-        crossover_freqs.len(),
+        params.crossover_freqs.len(),
         n_drivers - 1,
         "Must have {} crossover frequencies for {} drivers (got {})",
         n_drivers - 1,
         n_drivers,
-        crossover_freqs.len()
+        params.crossover_freqs.len()
     );
 
-    // Interpolate each driver's response to the common frequency grid
-    let mut driver_responses = Vec::new();
-    for driver in &data.drivers {
-        let interpolated = crate::read::normalize_and_interpolate_response(
-            &data.freq_grid,
-            &Curve {
-                freq: driver.freq.clone(),
-                spl: driver.spl.clone(),
-            },
-        );
-        driver_responses.push(interpolated.spl);
-    }
-
-    // Apply gain and crossover filters to each driver
-    let mut filtered_responses = Vec::new();
+    let freqs = &data.freq_grid;
+    (0..n_drivers)
+        .map(|i| {
+            let polarity = if params.inverted[i] { -1.0 } else { 1.0 };
+            let gain = polarity * 10.0_f64.powf(params.gains[i] / 20.0);
+            let delay_s = params.delays_ms[i] / 1000.0;
+            let mut response = &data.responses[i]
+                * &freqs.mapv(|f| {
+                    Complex64::from_polar(gain, -2.0 * std::f64::consts::PI * f * delay_s)
+                });
 
-    for i in 0..n_drivers {
-        let mut response = &driver_responses[i] + gains[i]; // Apply gain
-
-        // Apply crossover filters based on position
-        if i > 0 {
             // Apply highpass from crossover with previous driver
-            let xover_freq = crossover_freqs[i - 1];
-            let hp_filter = match data.crossover_type {
-                CrossoverType::Butterworth2 => peq_butterworth_highpass(2, xover_freq, sample_rate),
-                CrossoverType::LinkwitzRiley2 => {
-                    peq_linkwitzriley_highpass(2, xover_freq, sample_rate)
-                }
-                CrossoverType::LinkwitzRiley4 => {
-                    peq_linkwitzriley_highpass(4, xover_freq, sample_rate)
-                }
-            };
-            let hp_response = compute_peq_response(&data.freq_grid, &hp_filter, sample_rate);
-            response = response + hp_response;
-        }
+            if i > 0 {
+                let hp_filter = data.crossovers[i - 1]
+                    .highpass
+                    .highpass(params.crossover_freqs[i - 1], sample_rate);
+                response = response * compute_peq_complex_response(freqs, &hp_filter);
+            }
 
-        if i < n_drivers - 1 {
             // Apply lowpass from crossover with next driver
-            let xover_freq = crossover_freqs[i];
-            let lp_filter = match data.crossover_type {
-                CrossoverType::Butterworth2 => peq_butterworth_lowpass(2, xover_freq, sample_rate),
-                CrossoverType::LinkwitzRiley2 => {
-                    peq_linkwitzriley_lowpass(2, xover_freq, sample_rate)
-                }
-                CrossoverType::LinkwitzRiley4 => {
-                    peq_linkwitzriley_lowpass(4, xover_freq, sample_rate)
-                }
-            };
-            let lp_response = compute_peq_response(&data.freq_grid, &lp_filter, sample_rate);
-            response = response + lp_response;
-        }
+            if i < n_drivers - 1 {
+                let lp_filter = data.crossovers[i]
+                    .lowpass
+                    .lowpass(params.crossover_freqs[i], sample_rate);
+                response = response * compute_peq_complex_response(freqs, &lp_filter);
+            }
 
-        filtered_responses.push(response);
-    }
+            response
+        })
+        .collect()
+}
 
-    // Sum the filtered responses in linear domain (convert dB to linear, sum, convert back)
-    let mut combined_linear = Array1::zeros(data.freq_grid.len());
-    for response in &filtered_responses {
-        // Convert dB to linear amplitude
-        let linear = response.mapv(|db| 10.0_f64.powf(db / 20.0));
-        combined_linear = combined_linear + linear;
+/// Compute the combined frequency response of multiple drivers with crossovers and gains
+///
+/// Drivers are summed as complex values, so the measured phase, the phase of
+/// the crossover filters, the delays and the polarities all shape the result.
+///
+/// # Arguments
+/// * `data` - DriversLossData containing driver measurements and crossovers
+/// * `params` - Gains, crossover frequencies, delays and polarities
+/// * `sample_rate` - Sample rate for filter design
+///
+/// # Returns
+/// * Combined frequency response in dB on the common frequency grid
+pub fn compute_drivers_combined_response(
+    data: &DriversLossData,
+    params: &DriversParams,
+    sample_rate: f64,
+) -> Array1<f64> {
+    let responses = compute_drivers_responses(data, params, sample_rate);
+    let mut combined = Array1::from_elem(data.freq_grid.len(), Complex64::new(0.0, 0.0));
+    for response in &responses {
+        combined += response;
     }
-
-    // Convert back to dB
-    combined_linear.mapv(|lin: f64| 20.0 * lin.log10())
+    combined.mapv(|h| 20.0 * h.norm().max(1e-12).log10())
 }
 
 /// Compute the loss for multi-driver crossover optimization
 ///
 /// # Arguments
 /// * `data` - DriversLossData containing driver measurements
-/// * `gains` - Gain in dB for each driver
-/// * `crossover_freqs` - Crossover frequencies between successive driver pairs
+/// * `params` - Gains, crossover frequencies, delays and polarities
 /// * `sample_rate` - Sample rate for filter design
 /// * `min_freq` - Minimum frequency for loss evaluation
 /// * `max_freq` - Maximum frequency for loss evaluation
@@ -512,15 +1439,13 @@ pub fn compute_drivers_combined_response(
 /// * Loss value (lower is better)
 pub fn drivers_flat_loss(
     data: &DriversLossData,
-    gains: &[f64],
-    crossover_freqs: &[f64],
+    params: &DriversParams,
     sample_rate: f64,
     min_freq: f64,
     max_freq: f64,
 ) -> f64 {
     // Compute combined response
-    let combined_response =
-        compute_drivers_combined_response(data, gains, crossover_freqs, sample_rate);
+    let combined_response = compute_drivers_combined_response(data, params, sample_rate);
 
     // Normalize the response (subtract the mean in the evaluation range)
     let mut sum = 0.0;
@@ -539,6 +1464,109 @@ pub fn drivers_flat_loss(
     flat_loss(&data.freq_grid, &normalized, min_freq, max_freq)
 }
 
+/// Compute the complex responses of the mains and the sub after crossover, PEQ and alignment
+///
+/// # Arguments
+/// * `data` - Sub + mains loss data
+/// * `params` - Decoded crossover, alignment and PEQ parameters
+/// * `sample_rate` - Sample rate for filter design
+///
+/// # Returns
+/// * Tuple of (mains, sub) complex responses on the common frequency grid
+pub fn compute_sub_mains_branches(
+    data: &SubMainsLossData,
+    params: &SubMainsParams,
+    sample_rate: f64,
+) -> (Array1<Complex64>, Array1<Complex64>) {
+    use crate::iir::compute_peq_complex_response;
+
+    let freqs = &data.freq_grid;
+    let highpass = data
+        .crossover
+        .highpass
+        .highpass(params.crossover_freq, sample_rate);
+    let lowpass = data
+        .crossover
+        .lowpass
+        .lowpass(params.crossover_freq, sample_rate);
+
+    let mains = &data.mains
+        * &compute_peq_complex_response(freqs, &params.mains_peq)
+        * &compute_peq_complex_response(freqs, &highpass);
+
+    let polarity = if params.sub_inverted { -1.0 } else { 1.0 };
+    let gain = polarity * 10.0_f64.powf(params.sub_gain_db / 20.0);
+    let delay_s = params.sub_delay_ms / 1000.0;
+    let alignment =
+        freqs.mapv(|f| Complex64::from_polar(gain, -2.0 * std::f64::consts::PI * f * delay_s));
+    let sub = &data.sub
+        * &compute_peq_complex_response(freqs, &params.sub_peq)
+        * &compute_peq_complex_response(freqs, &lowpass)
+        * &alignment;
+
+    (mains, sub)
+}
+
+/// Compute the summed sub + mains response in dB
+///
+/// # Arguments
+/// * `data` - Sub + mains loss data
+/// * `params` - Decoded crossover, alignment and PEQ parameters
+/// * `sample_rate` - Sample rate for filter design
+///
+/// # Returns
+/// * Magnitude of the complex sum in dB on the common frequency grid
+pub fn compute_sub_mains_response(
+    data: &SubMainsLossData,
+    params: &SubMainsParams,
+    sample_rate: f64,
+) -> Array1<f64> {
+    let (mains, sub) = compute_sub_mains_branches(data, params, sample_rate);
+    (&mains + &sub).mapv(|h| 20.0 * h.norm().max(1e-12).log10())
+}
+
+/// Compute the loss for subwoofer + mains integration
+///
+/// The mains and the sub are summed as complex responses, so the loss sees the
+/// cancellations caused by a wrong delay or polarity around the crossover.
+///
+/// # Arguments
+/// * `data` - Sub + mains loss data
+/// * `x` - Parameter vector, see [`SUB_MAINS_ALIGNMENT_PARAMS`] for the layout
+/// * `sample_rate` - Sample rate for filter design
+/// * `min_freq` - Minimum frequency for loss evaluation
+/// * `max_freq` - Maximum frequency for loss evaluation
+///
+/// # Returns
+/// * Loss value (lower is better)
+pub fn sub_mains_flat_loss(
+    data: &SubMainsLossData,
+    x: &[f64],
+    sample_rate: f64,
+    min_freq: f64,
+    max_freq: f64,
+) -> f64 {
+    let params = SubMainsParams::from_x(data, x, sample_rate);
+    let combined_response = compute_sub_mains_response(data, &params, sample_rate);
+
+    // Normalize the response (subtract the mean in the evaluation range)
+    let in_range: Vec<f64> = data
+        .freq_grid
+        .iter()
+        .zip(combined_response.iter())
+        .filter(|(f, _)| **f >= min_freq && **f <= max_freq)
+        .map(|(_, db)| *db)
+        .collect();
+    let mean = if in_range.is_empty() {
+        0.0
+    } else {
+        in_range.iter().sum::<f64>() / in_range.len() as f64
+    };
+    let normalized = &combined_response - mean;
+
+    flat_loss(&data.freq_grid, &normalized, min_freq, max_freq)
+}
+
 /// Calculate the standard deviation (SD) of the deviation error over the specified frequency range.
 ///
 /// This function filters the input curve to include only frequencies within the specified range,
@@ -671,10 +1699,68 @@ pub fn headphone_loss(curve: &Curve) -> f64 {
     114.49 - (12.62 * sd) - (15.52 * as_value)
 }
 
+/// Compute the in-ear headphone preference score based on frequency response deviations
+///
+/// This implements the in-ear monitor preference prediction model from:
+/// Olive, S. E., Welti, T., & Khonsaripour, O. (2019). "A Statistical Model that
+/// Predicts Listeners' Preference Ratings of In-Ear Headphones: Part 2 - Development
+/// and Validation of the Model"
+///
+/// The model predicts preference using the equation:
+/// **Predicted Preference Rating = 100.0795 - (8.5 × SD) - (6.796 × AS) - (3.475 × AE)**
+///
+/// Where:
+/// - **SD** = Standard deviation of the deviation error over 20 Hz to 10 kHz
+/// - **AS** = Absolute value of slope of the deviation over 20 Hz to 10 kHz
+/// - **AE** = Mean absolute deviation error over 20 Hz to 10 kHz
+///
+/// # Arguments
+/// * `curve` - Frequency response curve representing deviation from the Harman in-ear target
+///
+/// # Returns
+/// * Predicted preference rating (higher values indicate better preference)
+///
+/// # Important Note
+/// The input curve should represent deviation from the Harman in-ear target
+/// (for example `harman-in-ear-2019.csv`), not from the over-ear target.
+pub fn headphone_loss_in_ear(curve: &Curve) -> f64 {
+    let freq = &curve.freq;
+    let deviation = &curve.spl;
+
+    // In-ear measurements are reliable down to 20 Hz, so the model covers 20 Hz to 10 kHz
+    const FMIN: f64 = 20.0;
+    const FMAX: f64 = 10000.0;
+
+    let sd = calculate_standard_deviation_in_range(freq, deviation, FMIN, FMAX);
+    let as_value = calculate_absolute_slope_in_range(freq, deviation, FMIN, FMAX);
+    let ae = calculate_mean_absolute_error_in_range(freq, deviation, FMIN, FMAX);
+
+    100.0795 - (8.5 * sd) - (6.796 * as_value) - (3.475 * ae)
+}
+
+/// Mean absolute value of the deviation over the specified frequency range
+fn calculate_mean_absolute_error_in_range(
+    freq: &Array1<f64>,
+    deviation: &Array1<f64>,
+    fmin: f64,
+    fmax: f64,
+) -> f64 {
+    let values: Vec<f64> = freq
+        .iter()
+        .zip(deviation.iter())
+        .filter(|(f, _)| **f >= fmin && **f <= fmax)
+        .map(|(_, d)| d.abs())
+        .collect();
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
 /// Compute headphone preference score with additional target curve
 ///
 /// # Arguments
-/// * `data` - Headphone loss data containing smoothing parameters
+/// * `data` - Headphone loss data containing smoothing parameters and headphone type
 /// * `response` - Measured frequency response in dB
 /// * `target` - Target frequency response in dB
 ///
@@ -702,7 +1788,7 @@ pub fn headphone_loss_with_target(
         deviation.clone()
     };
 
-    headphone_loss(&smooth_deviation)
+    data.headphone_type.score(&smooth_deviation)
 }
 
 #[cfg(test)]
@@ -712,6 +1798,166 @@ mod tests {
     use ndarray::array;
     use std::collections::HashMap;
 
+    fn flat_measurement(delay_ms: f64) -> DriverMeasurement {
+        let freq = Array1::logspace(10.0, 1.0, 20000.0_f64.log10(), 300);
+        let spl = Array1::zeros(freq.len());
+        let phase = freq.mapv(|f: f64| -360.0 * f * delay_ms / 1000.0);
+        DriverMeasurement::new(freq, spl, Some(phase))
+    }
+
+    fn sub_mains_x(data: &SubMainsLossData, delay_ms: f64, polarity: f64) -> Vec<f64> {
+        let mut x = vec![80.0_f64.log10(), 0.0, delay_ms, polarity];
+        // zero gain peaks
+        for _ in 0..(data.num_mains_filters + data.num_sub_filters) {
+            x.extend_from_slice(&[1000.0_f64.log10(), 1.0, 0.0]);
+        }
+        x
+    }
+
+    #[test]
+    fn crossover_parse() {
+        let c = Crossover::parse("lr4/BW3").unwrap();
+        assert_eq!(c.lowpass, CrossoverType::LinkwitzRiley4);
+        assert_eq!(c.highpass, CrossoverType::Butterworth3);
+        assert_eq!(c.to_string(), "linkwitzriley4/butterworth3");
+        assert_eq!(
+            Crossover::parse("linkwitzriley8").unwrap(),
+            CrossoverType::LinkwitzRiley8.into()
+        );
+        assert!(Crossover::parse("bessel").is_err());
+
+        assert_eq!(Crossover::parse_list("bw2", 3).unwrap().len(), 3);
+        let list = Crossover::parse_list("lr4, lr2", 2).unwrap();
+        assert_eq!(list[1], CrossoverType::LinkwitzRiley2.into());
+        assert!(Crossover::parse_list("lr4,lr2", 3).is_err());
+    }
+
+    #[test]
+    fn drivers_five_way_asymmetric() {
+        // five ideal flat drivers, each covering two octaves
+        let drivers: Vec<_> = (0..5)
+            .map(|i| {
+                let lo = 20.0 * 4.0_f64.powi(i);
+                let freq = Array1::from_vec(
+                    (0..=40)
+                        .map(|k| lo * 16.0_f64.powf(k as f64 / 40.0))
+                        .collect(),
+                );
+                let spl = Array1::zeros(freq.len());
+                DriverMeasurement::new(freq, spl, None).with_name(format!("d{}", i))
+            })
+            .collect();
+        let crossovers = Crossover::parse_list("lr4,lr4,lr4/bw3,bw3", 4).unwrap();
+        let data = DriversLossData::with_crossovers(drivers, crossovers);
+        assert_eq!(data.driver_name(4), "d4");
+
+        let xovers = vec![80.0, 320.0, 1280.0, 5120.0];
+        let params = DriversParams::new(vec![0.0; 5], xovers.clone());
+        let responses = compute_drivers_responses(&data, &params, 48000.0);
+        let db = |h: Complex64| 20.0 * h.norm().log10();
+        let at = |f: f64| data.freq_grid.iter().position(|&g| g >= f).unwrap();
+
+        // one octave above the asymmetric crossover, LR4 (24 dB/oct) on the lower driver
+        // and BW3 (18 dB/oct) on the upper driver leave different attenuations
+        let i = at(2560.0);
+        let j = at(640.0);
+        let lower_stop = db(responses[2][i]);
+        let upper_stop = db(responses[3][j]);
+        assert!(
+            lower_stop < upper_stop - 3.0,
+            "{} {}",
+            lower_stop,
+            upper_stop
+        );
+        assert!(drivers_flat_loss(&data, &params, 48000.0, 40.0, 15000.0).is_finite());
+    }
+
+    #[test]
+    fn sub_mains_polarity_and_delay() {
+        let mains = flat_measurement(0.0);
+        let sub = flat_measurement(4.0);
+        let data = SubMainsLossData::new(&mains, &sub, CrossoverType::LinkwitzRiley4.into(), 1, 1)
+            .unwrap();
+        assert_eq!(data.num_params(), 4 + 6);
+        let loss = |delay: f64, polarity: f64| {
+            sub_mains_flat_loss(
+                &data,
+                &sub_mains_x(&data, delay, polarity),
+                48000.0,
+                20.0,
+                500.0,
+            )
+        };
+
+        // the sub arrives 4 ms late: delaying the mains by 4 ms realigns them
+        let aligned = loss(-4.0, 0.0);
+        assert!(aligned < 0.05, "aligned loss {}", aligned);
+        assert!(loss(0.0, 0.0) > 5.0 * aligned);
+        // inverting the polarity of an aligned sub digs a hole at the crossover
+        assert!(loss(-4.0, 1.0) > 5.0 * aligned);
+
+        let params = SubMainsParams::from_x(&data, &sub_mains_x(&data, -4.0, 1.0), 48000.0);
+        assert!(params.sub_inverted);
+        assert!((params.crossover_freq - 80.0).abs() < 1e-9);
+        assert_eq!(params.mains_peq.len(), 1);
+        assert_eq!(params.sub_peq.len(), 1);
+    }
+
+    #[test]
+    fn sub_mains_requires_phase() {
+        let mains = flat_measurement(0.0);
+        let sub = DriverMeasurement::new(mains.freq.clone(), mains.spl.clone(), None);
+        let err = SubMainsLossData::new(&mains, &sub, CrossoverType::LinkwitzRiley4.into(), 1, 1)
+            .unwrap_err();
+        assert!(err.contains("Sub"));
+    }
+
+    #[test]
+    fn multi_position_loss_modes() {
+        let freqs = Array1::from(vec![100.0, 200.0, 400.0, 800.0]);
+        // two positions with opposite deviations around a common average
+        let near = array![2.0, 2.0, 0.0, 0.0];
+        let far = array![-2.0, -2.0, 0.0, 0.0];
+        let peq = Array1::zeros(4);
+        let average_error = Array1::zeros(4);
+        let mut data = MultiPositionLossData::new(vec![near, far], &[], PositionLoss::Average, 1.0);
+        assert_eq!(data.weights, vec![0.5, 0.5]);
+
+        let loss = |data: &MultiPositionLossData| {
+            multi_position_flat_loss(data, &freqs, &average_error, &peq, 20.0, 20000.0)
+        };
+        assert_eq!(loss(&data), 0.0);
+
+        // both positions are equally bad: no spread
+        data.position_loss = PositionLoss::MeanVariance;
+        assert!(loss(&data).abs() < 1e-12);
+
+        data.position_loss = PositionLoss::Minimax;
+        let per_position = position_losses(&data, &freqs, &peq, 20.0, 20000.0);
+        assert!((per_position[0] - 2.0_f64.sqrt()).abs() < 1e-12);
+        assert!((loss(&data) - per_position[0]).abs() < 1e-12);
+
+        // a PEQ matching the near position only creates a spread
+        let near_fix = array![2.0, 2.0, 0.0, 0.0];
+        data.position_loss = PositionLoss::MeanVariance;
+        let spread = multi_position_flat_loss(&data, &freqs, &near_fix, &near_fix, 20.0, 20000.0);
+        let per_position = position_losses(&data, &freqs, &near_fix, 20.0, 20000.0);
+        assert_eq!(per_position[0], 0.0);
+        let expected = flat_loss(&freqs, &near_fix, 20.0, 20000.0) + per_position[1] / 2.0;
+        assert!((spread - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn multi_position_weights_are_normalized() {
+        let data = MultiPositionLossData::new(
+            vec![Array1::zeros(2), Array1::zeros(2), Array1::zeros(2)],
+            &[2.0, 1.0, 1.0],
+            PositionLoss::Minimax,
+            1.0,
+        );
+        assert_eq!(data.weights, vec![0.5, 0.25, 0.25]);
+    }
+
     #[test]
     fn score_loss_matches_score_when_peq_zero() {
         // Simple synthetic data
@@ -1228,4 +2474,99 @@ mod tests {
             perfect_score
         );
     }
+
+    #[test]
+    fn test_headphone_loss_in_ear() {
+        let freq = Array1::logspace(10.0, 1.0, 4.3, 200);
+        let perfect = Curve {
+            freq: freq.clone(),
+            spl: Array1::zeros(200),
+        };
+        assert!((headphone_loss_in_ear(&perfect) - 100.0795).abs() < 1e-10);
+
+        // a constant offset only costs the mean absolute error term
+        let offset = Curve {
+            freq: freq.clone(),
+            spl: Array1::from_elem(200, 2.0),
+        };
+        assert!((headphone_loss_in_ear(&offset) - (100.0795 - 3.475 * 2.0)).abs() < 1e-9);
+
+        // a tilt is penalized by both models
+        let tilt = Curve {
+            freq: freq.clone(),
+            spl: freq.mapv(|f| 2.0 * (f / 1000.0).log2()),
+        };
+        let scores = HeadphoneScores::new(&tilt);
+        assert!(scores.in_ear < 100.0795 && scores.over_ear < 114.49);
+        assert_eq!(
+            scores.get(HeadphoneType::InEar),
+            headphone_loss_in_ear(&tilt)
+        );
+        assert_eq!(HeadphoneType::OverEar.score(&tilt), headphone_loss(&tilt));
+    }
+
+    #[test]
+    fn perceptual_loss_peaks_cost_more_than_dips() {
+        let freqs = Array1::logspace(10.0, 1.3, 4.3, 200);
+        let data = PerceptualLossData::new(&freqs, FrequencyWeighting::None, 1.0, 0.25, None);
+        let bump =
+            |sign: f64| freqs.mapv(|f: f64| sign * 3.0 * (-(f / 1000.0).log2().powi(2)).exp());
+        let peak = perceptual_flat_loss(&data, &freqs, &bump(1.0), 20.0, 20000.0);
+        let dip = perceptual_flat_loss(&data, &freqs, &bump(-1.0), 20.0, 20000.0);
+        assert!((dip - 0.5 * peak).abs() < 1e-9, "peak {} dip {}", peak, dip);
+
+        // symmetric weights and no frequency weighting give a plain RMS
+        let plain = PerceptualLossData::new(&freqs, FrequencyWeighting::None, 1.0, 1.0, None);
+        let error = Array1::from_elem(200, 2.0);
+        assert!((perceptual_flat_loss(&plain, &freqs, &error, 20.0, 20000.0) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn perceptual_loss_ignores_narrow_dips() {
+        let freqs = Array1::logspace(10.0, 1.3, 4.3, 400);
+        // 1/10 octave wide dip at 2 kHz and 2 octave wide dip at 200 Hz
+        let error = freqs.mapv(|f: f64| {
+            let narrow = -10.0 * (-((f / 2000.0).log2() / 0.03).powi(2)).exp();
+            let wide = -2.0 * (-((f / 200.0).log2() / 0.6).powi(2)).exp();
+            narrow + wide + 0.01
+        });
+        let keep = PerceptualLossData::new(&freqs, FrequencyWeighting::None, 1.0, 1.0, None);
+        let skip = PerceptualLossData::new(&freqs, FrequencyWeighting::None, 1.0, 1.0, Some(0.5));
+        let only_wide =
+            freqs.mapv(|f: f64| -2.0 * (-((f / 200.0).log2() / 0.6).powi(2)).exp() + 0.01);
+        let with_narrow = perceptual_flat_loss(&keep, &freqs, &error, 20.0, 20000.0);
+        let without_narrow = perceptual_flat_loss(&skip, &freqs, &error, 20.0, 20000.0);
+        let reference = perceptual_flat_loss(&keep, &freqs, &only_wide, 20.0, 20000.0);
+        assert!(without_narrow < with_narrow);
+        assert!((without_narrow - reference).abs() < 0.05 * reference);
+    }
+
+    #[test]
+    fn perceptual_weightings() {
+        let freqs = Array1::from(vec![30.0, 100.0, 1000.0, 3500.0, 10000.0]);
+        // ERB bands are narrower in octaves at low frequencies
+        let erb = erb_weights(&Array1::logspace(10.0, 1.3, 4.3, 100));
+        assert!(erb[0] < erb[50] && erb[50] < erb[99]);
+        // hearing is most sensitive around 3-4 kHz and much less at 30 Hz
+        let el = equal_loudness_weights(&freqs, 80.0);
+        assert!((el[2] - 1.0).abs() < 1e-12);
+        assert!(el[3] > el[2] && el[0] < el[1] && el[1] < el[2]);
+        let contour = iso226_contour(80.0);
+        assert!((contour[17] - 80.0).abs() < 0.1, "{}", contour[17]);
+    }
+
+    #[test]
+    fn test_headphone_type_from_target() {
+        use std::path::Path;
+        let in_ear = Path::new("data_tests/targets/harman-in-ear-2019.csv");
+        let over_ear = Path::new("data_tests/targets/harman-over-ear-2018.csv");
+        assert_eq!(
+            HeadphoneType::from_target_path(in_ear),
+            HeadphoneType::InEar
+        );
+        assert_eq!(
+            HeadphoneType::from_target_path(over_ear),
+            HeadphoneType::OverEar
+        );
+    }
 }
@@ -18,15 +18,18 @@
 use super::cli::PeqModel;
 use super::constraints::{viol_ceiling_from_spl, viol_min_gain_from_xs, viol_spacing_from_xs};
 use super::loss::{
-    DriversLossData, HeadphoneLossData, LossType, SpeakerLossData, drivers_flat_loss, flat_loss,
-    headphone_loss, speaker_score_loss,
+    DriversLossData, DriversParams, HeadphoneLossData, LossType, MultiPositionLossData,
+    PerceptualLossData, SpeakerLossData, SubMainsLossData, drivers_flat_loss, flat_loss,
+    multi_position_flat_loss, perceptual_flat_loss, speaker_score_loss, speaker_score_with_sub,
+    sub_mains_flat_loss,
 };
 use super::optim_de::optimize_filters_autoeq;
 use super::optim_mh::optimize_filters_mh;
 #[cfg(feature = "nlopt")]
 use super::optim_nlopt::optimize_filters_nlopt;
-use super::x2peq::x2spl;
+use super::x2peq::x2spl_with_design;
 use crate::Curve;
+use crate::iir::BiquadDesign;
 use ndarray::Array1;
 #[cfg(feature = "nlopt")]
 use nlopt::Algorithm;
@@ -281,8 +284,12 @@ pub struct ObjectiveData {
     pub min_freq: f64,
     /// Maximum frequency in Hz for loss function evaluation
     pub max_freq: f64,
+    /// Crossover frequency in Hz to the subwoofer; the with-sub score ignores the response below it
+    pub sub_crossover: f64,
     /// PEQ model that defines the filter structure
     pub peq_model: PeqModel,
+    /// Coefficient design of the PEQ filters
+    pub biquad_design: BiquadDesign,
     /// Type of loss function to use
     pub loss_type: LossType,
     /// Optional score data for SpeakerScore loss type
@@ -293,6 +300,12 @@ pub struct ObjectiveData {
     pub input_curve: Option<Curve>,
     /// Optional data for multi-driver crossover optimization
     pub drivers_data: Option<DriversLossData>,
+    /// Optional data for multi-position room EQ (flat loss types only)
+    pub multi_position_data: Option<MultiPositionLossData>,
+    /// Optional data for subwoofer + mains integration
+    pub sub_mains_data: Option<SubMainsLossData>,
+    /// Optional weights for the perceptual flat loss
+    pub perceptual_data: Option<PerceptualLossData>,
     /// Penalty weights used when the optimizer does not support nonlinear constraints
     /// If zero, penalties are disabled and true constraints (if any) are used.
     /// Penalty for ceiling constraint
@@ -365,21 +378,11 @@ pub fn compute_base_fitness(x: &[f64], data: &ObjectiveData) -> f64 {
         LossType::DriversFlat => {
             // Multi-driver crossover optimization
             if let Some(ref drivers_data) = data.drivers_data {
-                let n_drivers = drivers_data.drivers.len();
-                // Parameter layout: [gain1, gain2, ..., gainN, xover_freq1, xover_freq2, ..., xover_freq(N-1)]
-                let gains = &x[0..n_drivers];
-                let xover_freqs_log10 = &x[n_drivers..];
-
-                // Convert crossover frequencies from log10 to Hz
-                let xover_freqs: Vec<f64> = xover_freqs_log10
-                    .iter()
-                    .map(|f| 10.0_f64.powf(*f))
-                    .collect();
-
+                // Parameter layout: [gains, log10 crossovers, delays, polarities]
+                let params = DriversParams::from_x(drivers_data.drivers.len(), x);
                 drivers_flat_loss(
                     drivers_data,
-                    gains,
-                    &xover_freqs,
+                    &params,
                     data.srate,
                     data.min_freq,
                     data.max_freq,
@@ -389,13 +392,60 @@ pub fn compute_base_fitness(x: &[f64], data: &ObjectiveData) -> f64 {
                 process::exit(1);
             }
         }
+        LossType::SubMainsFlat => {
+            if let Some(ref sub_mains_data) = data.sub_mains_data {
+                sub_mains_flat_loss(sub_mains_data, x, data.srate, data.min_freq, data.max_freq)
+            } else {
+                eprintln!("Error: sub-mains-flat loss requested but sub and mains data is missing");
+                process::exit(1);
+            }
+        }
         LossType::HeadphoneFlat | LossType::SpeakerFlat => {
-            let peq_spl = x2spl(&data.freqs, x, data.srate, data.peq_model);
+            let peq_spl = x2spl_with_design(
+                &data.freqs,
+                x,
+                data.srate,
+                data.peq_model,
+                data.biquad_design,
+            );
             let error = &peq_spl - &data.deviation;
-            flat_loss(&data.freqs, &error, data.min_freq, data.max_freq)
+            if let Some(ref mp) = data.multi_position_data {
+                multi_position_flat_loss(
+                    mp,
+                    &data.freqs,
+                    &error,
+                    &peq_spl,
+                    data.min_freq,
+                    data.max_freq,
+                )
+            } else {
+                flat_loss(&data.freqs, &error, data.min_freq, data.max_freq)
+            }
+        }
+        LossType::PerceptualFlat => {
+            if let Some(ref pd) = data.perceptual_data {
+                let peq_spl = x2spl_with_design(
+                    &data.freqs,
+                    x,
+                    data.srate,
+                    data.peq_model,
+                    data.biquad_design,
+                );
+                let error = &peq_spl - &data.deviation;
+                perceptual_flat_loss(pd, &data.freqs, &error, data.min_freq, data.max_freq)
+            } else {
+                eprintln!("Error: perceptual-flat loss requested but perceptual data is missing");
+                process::exit(1);
+            }
         }
         LossType::SpeakerScore => {
-            let peq_spl = x2spl(&data.freqs, x, data.srate, data.peq_model);
+            let peq_spl = x2spl_with_design(
+                &data.freqs,
+                x,
+                data.srate,
+                data.peq_model,
+                data.biquad_design,
+            );
             if let Some(ref sd) = data.speaker_score_data {
                 let error = &peq_spl - &data.deviation;
                 let s = speaker_score_loss(sd, &data.freqs, &peq_spl);
@@ -406,19 +456,44 @@ pub fn compute_base_fitness(x: &[f64], data: &ObjectiveData) -> f64 {
                 process::exit(1);
             }
         }
+        LossType::SpeakerScoreWithSub => {
+            let peq_spl = x2spl_with_design(
+                &data.freqs,
+                x,
+                data.srate,
+                data.peq_model,
+                data.biquad_design,
+            );
+            if let Some(ref sd) = data.speaker_score_data {
+                // the bass is left to the sub: data.min_freq is at or above the crossover
+                let error = &peq_spl - &data.deviation;
+                let s = speaker_score_with_sub(sd, &data.freqs, &peq_spl, data.sub_crossover);
+                let p = flat_loss(&data.freqs, &error, data.min_freq, data.max_freq) / 3.0;
+                100.0 - s + p
+            } else {
+                eprintln!("Error: speaker score loss requested but score data is missing");
+                process::exit(1);
+            }
+        }
         LossType::HeadphoneScore => {
-            let peq_spl = x2spl(&data.freqs, x, data.srate, data.peq_model);
-            if let Some(ref _hd) = data.headphone_score_data {
+            let peq_spl = x2spl_with_design(
+                &data.freqs,
+                x,
+                data.srate,
+                data.peq_model,
+                data.biquad_design,
+            );
+            if let Some(ref hd) = data.headphone_score_data {
                 // Compute remaining deviation: target - (input + peq) = deviation - peq
                 // where deviation = target - input
                 let error = &data.deviation - &peq_spl;
 
-                // Use headphone_loss on the remaining deviation
+                // Score the remaining deviation with the model of the headphone type
                 let error_curve = Curve {
                     freq: data.freqs.clone(),
                     spl: error.clone(),
                 };
-                let s = headphone_loss(&error_curve);
+                let s = hd.headphone_type.score(&error_curve);
                 // compute flat error
                 let p = flat_loss(&data.freqs, &error, data.min_freq, data.max_freq);
                 // wants to maximize the score and improve the flatness
@@ -435,7 +510,8 @@ pub fn compute_base_fitness(x: &[f64], data: &ObjectiveData) -> f64 {
 /// Compute objective function value including penalty terms for constraints
 ///
 /// This function adds penalty terms to the base fitness when using algorithms
-/// that don't support native constraint handling.
+/// that don't support native constraint handling. The penalties read the
+/// parameters as PEQ filters and are skipped for crossover losses.
 ///
 /// # Arguments
 /// * `x` - Parameter vector
@@ -455,9 +531,16 @@ pub fn compute_fitness_penalties(
     // optimizers without nonlinear constraints can still respect our limits.
     let mut penalized = fit;
     let mut penalty_terms = Vec::new();
+    let peq_layout = data.loss_type.uses_peq_layout();
 
-    if data.penalty_w_ceiling > 0.0 {
-        let peq_spl = x2spl(&data.freqs, x, data.srate, data.peq_model);
+    if peq_layout && data.penalty_w_ceiling > 0.0 {
+        let peq_spl = x2spl_with_design(
+            &data.freqs,
+            x,
+            data.srate,
+            data.peq_model,
+            data.biquad_design,
+        );
         let viol = viol_ceiling_from_spl(&peq_spl, data.max_db, data.peq_model);
         let penalty = data.penalty_w_ceiling * viol * viol;
         penalized += penalty;
@@ -469,7 +552,7 @@ pub fn compute_fitness_penalties(
         }
     }
 
-    if data.penalty_w_spacing > 0.0 {
+    if peq_layout && data.penalty_w_spacing > 0.0 {
         let viol = viol_spacing_from_xs(x, data.peq_model, data.min_spacing_oct);
         let penalty = data.penalty_w_spacing * viol * viol;
         penalized += penalty;
@@ -481,7 +564,7 @@ pub fn compute_fitness_penalties(
         }
     }
 
-    if data.penalty_w_mingain > 0.0 && data.min_db > 0.0 {
+    if peq_layout && data.penalty_w_mingain > 0.0 && data.min_db > 0.0 {
         let viol = viol_min_gain_from_xs(x, data.peq_model, data.min_db);
         let penalty = data.penalty_w_mingain * viol * viol;
         penalized += penalty;
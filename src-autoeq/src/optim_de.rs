@@ -70,7 +70,7 @@ pub fn setup_de_common(
 
     // Log setup configuration (unless in QA mode)
     if !qa_mode {
-        let params_desc = if penalty_data.loss_type == crate::LossType::DriversFlat {
+        let params_desc = if !penalty_data.loss_type.uses_peq_layout() {
             format!("{} parameters", bounds.len())
         } else {
             let params_per_filter = crate::param_utils::params_per_filter(penalty_data.peq_model);
@@ -260,8 +260,8 @@ pub fn optimize_filters_autoeq_with_callback(
     let base_objective_fn = create_de_objective(setup.penalty_data.clone());
 
     // Create smart initialization based on frequency response analysis
-    // Skip for drivers-flat loss as it uses a different parameter layout
-    let smart_guesses = if setup.penalty_data.loss_type == crate::LossType::DriversFlat {
+    // Skip for crossover losses as they use a different parameter layout
+    let smart_guesses = if !setup.penalty_data.loss_type.uses_peq_layout() {
         Vec::new()
     } else {
         let params_per_filter =
@@ -322,9 +322,25 @@ pub fn optimize_filters_autoeq_with_callback(
         Array1::from(x.to_vec())
     };
 
-    if cli_args.qa.is_none() {
-        eprintln!("🚀 Using smart initial guess with Sobol population initialization");
-    }
+    // Keep the caller's guess when it is feasible and better, e.g. a warm start
+    // from a previous solution with fewer filters
+    let x_in_bounds = x
+        .iter()
+        .zip(lower_bounds.iter().zip(upper_bounds))
+        .all(|(v, (lo, hi))| *v >= *lo && *v <= *hi);
+    let best_initial_guess = if x_in_bounds
+        && base_objective_fn(&Array1::from(x.to_vec())) < base_objective_fn(&best_initial_guess)
+    {
+        if cli_args.qa.is_none() {
+            eprintln!("🚀 Using provided initial guess with Sobol population initialization");
+        }
+        Array1::from(x.to_vec())
+    } else {
+        if cli_args.qa.is_none() {
+            eprintln!("🚀 Using smart initial guess with Sobol population initialization");
+        }
+        best_initial_guess
+    };
 
     // Parse strategy from CLI args
     use std::str::FromStr;
@@ -417,13 +433,17 @@ pub fn optimize_filters_autoeq_with_callback(
     // Add native nonlinear constraints
     let mut config = config_builder.build();
 
+    // The constraints below read the parameters as a list of PEQ filters
+    let peq_layout = setup.penalty_data.loss_type.uses_peq_layout();
+
     // Ceiling constraint (applies when max_db is set)
-    if setup.penalty_data.max_db > 0.0 {
+    if peq_layout && setup.penalty_data.max_db > 0.0 {
         let ceiling_data = CeilingConstraintData {
             freqs: setup.penalty_data.freqs.clone(),
             srate: setup.penalty_data.srate,
             max_db: setup.penalty_data.max_db,
             peq_model: setup.penalty_data.peq_model,
+            biquad_design: setup.penalty_data.biquad_design,
         };
 
         // Create nonlinear constraint helper for ceiling constraint
@@ -443,7 +463,7 @@ pub fn optimize_filters_autoeq_with_callback(
     }
 
     // Minimum gain constraint (applies in all modes)
-    if setup.penalty_data.min_db > 0.0 {
+    if peq_layout && setup.penalty_data.min_db > 0.0 {
         let min_gain_data = MinGainConstraintData {
             min_db: setup.penalty_data.min_db,
             peq_model: setup.penalty_data.peq_model,
@@ -466,7 +486,7 @@ pub fn optimize_filters_autoeq_with_callback(
     }
 
     // Minimum spacing constraint (applies in all modes)
-    if setup.penalty_data.min_spacing_oct > 0.0 {
+    if peq_layout && setup.penalty_data.min_spacing_oct > 0.0 {
         let spacing_data = SpacingConstraintData {
             min_spacing_oct: setup.penalty_data.min_spacing_oct,
             peq_model: setup.penalty_data.peq_model,
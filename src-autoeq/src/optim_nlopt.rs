@@ -39,12 +39,16 @@ pub fn optimize_filters_nlopt(
         srate: objective_data.srate,
         max_db: objective_data.max_db,
         peq_model: objective_data.peq_model,
+        biquad_design: objective_data.biquad_design,
     };
     let min_gain_data = MinGainConstraintData {
         min_db: objective_data.min_db,
         peq_model: objective_data.peq_model,
     };
 
+    // The constraints read the parameters as a list of PEQ filters
+    let peq_layout = objective_data.loss_type.uses_peq_layout();
+
     // Configure penalty weights when needed
     let mut objective_data = objective_data;
     if use_penalties {
@@ -70,7 +74,7 @@ pub fn optimize_filters_nlopt(
     let _ = optimizer.set_upper_bounds(upper_bounds).unwrap();
 
     // Register inequality constraints when not using penalties.
-    if !use_penalties {
+    if !use_penalties && peq_layout {
         let _ = optimizer.add_inequality_constraint(constraint_ceiling, ceiling_data, 1e-6);
         // let _ = optimizer.add_inequality_constraint(constraint_spacing, spacing_data, 1e-9);
         let _ = optimizer.add_inequality_constraint(constraint_min_gain, min_gain_data, 1e-6);
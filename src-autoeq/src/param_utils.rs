@@ -10,7 +10,15 @@ use crate::iir::BiquadFilterType;
 pub fn params_per_filter(peq_model: PeqModel) -> usize {
     match peq_model {
         // Fixed filter types use 3 parameters: freq, Q, gain
-        PeqModel::Pk | PeqModel::HpPk | PeqModel::HpPkLp | PeqModel::LsPk | PeqModel::LsPkHs => 3,
+        PeqModel::Pk
+        | PeqModel::HpPk
+        | PeqModel::HpPkLp
+        | PeqModel::LsPk
+        | PeqModel::LsPkHs
+        | PeqModel::Hp1Pk
+        | PeqModel::Hp1PkLp1
+        | PeqModel::Ls1PkHs1
+        | PeqModel::LtPk => 3,
         // Free filter types use 4 parameters: type, freq, Q, gain
         PeqModel::FreePkFree | PeqModel::Free => 4,
     }
@@ -27,7 +35,15 @@ pub fn get_filter_params(x: &[f64], i: usize, peq_model: PeqModel) -> FilterPara
     let offset = i * ppf;
 
     match peq_model {
-        PeqModel::Pk | PeqModel::HpPk | PeqModel::HpPkLp | PeqModel::LsPk | PeqModel::LsPkHs => {
+        PeqModel::Pk
+        | PeqModel::HpPk
+        | PeqModel::HpPkLp
+        | PeqModel::LsPk
+        | PeqModel::LsPkHs
+        | PeqModel::Hp1Pk
+        | PeqModel::Hp1PkLp1
+        | PeqModel::Ls1PkHs1
+        | PeqModel::LtPk => {
             // Fixed filter types: parameters are [freq, Q, gain]
             FilterParams {
                 filter_type: None,
@@ -54,7 +70,15 @@ pub fn set_filter_params(x: &mut [f64], i: usize, params: &FilterParams, peq_mod
     let offset = i * ppf;
 
     match peq_model {
-        PeqModel::Pk | PeqModel::HpPk | PeqModel::HpPkLp | PeqModel::LsPk | PeqModel::LsPkHs => {
+        PeqModel::Pk
+        | PeqModel::HpPk
+        | PeqModel::HpPkLp
+        | PeqModel::LsPk
+        | PeqModel::LsPkHs
+        | PeqModel::Hp1Pk
+        | PeqModel::Hp1PkLp1
+        | PeqModel::Ls1PkHs1
+        | PeqModel::LtPk => {
             // Fixed filter types: parameters are [freq, Q, gain]
             x[offset] = params.freq;
             x[offset + 1] = params.q;
@@ -124,6 +148,38 @@ pub fn determine_filter_type(
                 BiquadFilterType::Peak
             }
         }
+        PeqModel::Hp1Pk => {
+            if i == 0 {
+                BiquadFilterType::HighpassFirstOrder
+            } else {
+                BiquadFilterType::Peak
+            }
+        }
+        PeqModel::Hp1PkLp1 => {
+            if i == 0 {
+                BiquadFilterType::HighpassFirstOrder
+            } else if i == num_filters - 1 {
+                BiquadFilterType::LowpassFirstOrder
+            } else {
+                BiquadFilterType::Peak
+            }
+        }
+        PeqModel::Ls1PkHs1 => {
+            if i == 0 {
+                BiquadFilterType::LowshelfFirstOrder
+            } else if i == num_filters - 1 {
+                BiquadFilterType::HighshelfFirstOrder
+            } else {
+                BiquadFilterType::Peak
+            }
+        }
+        PeqModel::LtPk => {
+            if i == 0 {
+                BiquadFilterType::LinkwitzTransform
+            } else {
+                BiquadFilterType::Peak
+            }
+        }
         PeqModel::FreePkFree => {
             // First and last filters are free, middle are peak
             if i == 0 || i == num_filters - 1 {
@@ -150,6 +206,8 @@ pub fn decode_filter_type(type_value: f64) -> BiquadFilterType {
     // Map [5, 6) -> HighpassVariableQ
     // Map [6, 7) -> Bandpass
     // Map [7, 8) -> Notch
+    // Map [8, 15) -> first-order, all-pass and Linkwitz transform types, which
+    // are outside of filter_type_bounds() and only reached through encode_filter_type
 
     let idx = type_value.floor() as i32;
     match idx {
@@ -161,6 +219,13 @@ pub fn decode_filter_type(type_value: f64) -> BiquadFilterType {
         5 => BiquadFilterType::HighpassVariableQ,
         6 => BiquadFilterType::Bandpass,
         7 => BiquadFilterType::Notch,
+        8 => BiquadFilterType::LowpassFirstOrder,
+        9 => BiquadFilterType::HighpassFirstOrder,
+        10 => BiquadFilterType::LowshelfFirstOrder,
+        11 => BiquadFilterType::HighshelfFirstOrder,
+        12 => BiquadFilterType::AllpassFirstOrder,
+        13 => BiquadFilterType::Allpass,
+        14 => BiquadFilterType::LinkwitzTransform,
         _ => BiquadFilterType::Peak, // Default
     }
 }
@@ -176,6 +241,13 @@ pub fn encode_filter_type(filter_type: BiquadFilterType) -> f64 {
         BiquadFilterType::HighpassVariableQ => 5.0,
         BiquadFilterType::Bandpass => 6.0,
         BiquadFilterType::Notch => 7.0,
+        BiquadFilterType::LowpassFirstOrder => 8.0,
+        BiquadFilterType::HighpassFirstOrder => 9.0,
+        BiquadFilterType::LowshelfFirstOrder => 10.0,
+        BiquadFilterType::HighshelfFirstOrder => 11.0,
+        BiquadFilterType::AllpassFirstOrder => 12.0,
+        BiquadFilterType::Allpass => 13.0,
+        BiquadFilterType::LinkwitzTransform => 14.0,
     }
 }
 
@@ -0,0 +1,188 @@
+// Multi-objective optimization: loss vs. number of filters vs. total positive gain
+
+use ndarray::Array1;
+
+use super::optim::{ObjectiveData, compute_fitness_penalties};
+use super::optim_de::setup_de_common;
+use crate::de::{MultiObjectiveConfig, multi_objective_differential_evolution};
+
+/// One point of the Pareto set
+#[derive(Debug, Clone)]
+pub struct ParetoSolution {
+    /// Parameters of the active filters only (`[log10(freq), Q, gain]` triplets)
+    pub x: Vec<f64>,
+    /// Number of active filters
+    pub num_filters: usize,
+    /// Loss of the EQ made of the active filters (flat loss or score based loss)
+    pub loss: f64,
+    /// Sum of the positive gains in dB
+    pub total_positive_gain: f64,
+}
+
+/// Keep the peak filters whose absolute gain is at least `min_db`
+///
+/// Filters below `min_db` are considered switched off, which lets a fixed
+/// size parameter vector describe any number of filters up to its size.
+///
+/// # Arguments
+/// * `x` - Parameter vector of `[log10(freq), Q, gain]` triplets
+/// * `min_db` - Minimum absolute gain of an active filter
+///
+/// # Returns
+/// * Parameter vector of the active filters only
+pub fn active_filters(x: &[f64], min_db: f64) -> Vec<f64> {
+    x.chunks_exact(3)
+        .filter(|filter| filter[2].abs() >= min_db)
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Sum of the positive gains of a parameter vector of peak filters
+pub fn total_positive_gain(x: &[f64]) -> f64 {
+    x.chunks_exact(3).map(|filter| filter[2].max(0.0)).sum()
+}
+
+/// Evaluate the three objectives of a parameter vector
+///
+/// # Arguments
+/// * `x` - Full parameter vector (`--num-filters` triplets)
+/// * `data` - Objective data with penalty weights configured
+///
+/// # Returns
+/// * The solution made of the active filters and its objectives
+pub fn evaluate_pareto(x: &[f64], data: &ObjectiveData) -> ParetoSolution {
+    let active = active_filters(x, data.min_db);
+    let mut data_copy = data.clone();
+    let loss = compute_fitness_penalties(&active, None, &mut data_copy);
+    ParetoSolution {
+        num_filters: active.len() / 3,
+        total_positive_gain: total_positive_gain(&active),
+        loss,
+        x: active,
+    }
+}
+
+/// Optimize the loss, the number of filters and the total positive gain together
+///
+/// Uses the multi-objective DE of `autoeq_de` on `--num-filters` peak filters;
+/// filters with a gain below `--min-db` are switched off. Ceiling and spacing
+/// limits are added to the loss as penalties.
+///
+/// # Arguments
+/// * `x0` - Initial guess with all filters active
+/// * `lower_bounds` - Lower bounds for each parameter
+/// * `upper_bounds` - Upper bounds for each parameter
+/// * `objective_data` - Base objective configuration
+/// * `cli_args` - CLI arguments (population, maxeval, seed)
+///
+/// # Returns
+/// * The Pareto set sorted by number of filters, then by loss
+pub fn optimize_pareto(
+    x0: &[f64],
+    lower_bounds: &[f64],
+    upper_bounds: &[f64],
+    objective_data: ObjectiveData,
+    cli_args: &crate::cli::Args,
+) -> Vec<ParetoSolution> {
+    let setup = setup_de_common(
+        lower_bounds,
+        upper_bounds,
+        objective_data,
+        cli_args.population,
+        cli_args.maxeval,
+        cli_args.qa.is_some(),
+    );
+    let mut penalty_data = setup.penalty_data;
+    penalty_data.penalty_w_ceiling = 1e4;
+    penalty_data.penalty_w_spacing = penalty_data.spacing_weight.max(0.0) * 1e3;
+
+    // Seed the population with the full initial guess and with the empty EQ
+    let mut empty = x0.to_vec();
+    for filter in empty.chunks_exact_mut(3) {
+        filter[2] = 0.0;
+    }
+    let config = MultiObjectiveConfig {
+        maxiter: setup.max_iter,
+        popsize: setup.pop_size,
+        recombination: cli_args.recombination,
+        seed: cli_args.seed,
+        init_population: vec![Array1::from(x0.to_vec()), Array1::from(empty)],
+        ..MultiObjectiveConfig::default()
+    };
+
+    let objectives = |x: &Array1<f64>| {
+        let solution = evaluate_pareto(x.as_slice().unwrap(), &penalty_data);
+        vec![
+            solution.loss,
+            solution.num_filters as f64,
+            solution.total_positive_gain,
+        ]
+    };
+    let report = multi_objective_differential_evolution(&objectives, &setup.bounds, config);
+
+    let mut front: Vec<ParetoSolution> = report
+        .front
+        .iter()
+        .map(|member| evaluate_pareto(member.x.as_slice().unwrap(), &penalty_data))
+        .collect();
+    front.sort_by(|a, b| {
+        a.num_filters
+            .cmp(&b.num_filters)
+            .then(a.loss.total_cmp(&b.loss))
+    });
+    front
+}
+
+/// Lowest loss solution for each number of filters, keeping only the counts
+/// that improve on every smaller count
+pub fn best_per_filter_count(front: &[ParetoSolution]) -> Vec<&ParetoSolution> {
+    let mut best: Vec<&ParetoSolution> = Vec::new();
+    for solution in front {
+        match best.last() {
+            Some(last) if last.num_filters == solution.num_filters => {}
+            Some(last) if last.loss <= solution.loss => {}
+            _ => best.push(solution),
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solution(num_filters: usize, loss: f64) -> ParetoSolution {
+        ParetoSolution {
+            x: [3.0, 1.0, 1.0].repeat(num_filters),
+            num_filters,
+            loss,
+            total_positive_gain: num_filters as f64,
+        }
+    }
+
+    #[test]
+    fn active_filters_drop_small_gains() {
+        let x = [2.0, 1.0, 0.5, 3.0, 2.0, -4.0, 3.5, 1.0, 2.0];
+        let active = active_filters(&x, 1.0);
+        assert_eq!(active, vec![3.0, 2.0, -4.0, 3.5, 1.0, 2.0]);
+        assert_eq!(total_positive_gain(&active), 2.0);
+    }
+
+    #[test]
+    fn best_per_filter_count_is_monotonic() {
+        // sorted by filter count then loss, as returned by optimize_pareto
+        let front = vec![
+            solution(0, 5.0),
+            solution(1, 3.0),
+            solution(1, 3.5),
+            solution(2, 3.2),
+            solution(3, 1.0),
+        ];
+        let best: Vec<_> = best_per_filter_count(&front)
+            .iter()
+            .map(|s| (s.num_filters, s.loss))
+            .collect();
+        assert_eq!(best, vec![(0, 5.0), (1, 3.0), (3, 1.0)]);
+    }
+}
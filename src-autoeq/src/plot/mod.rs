@@ -18,14 +18,20 @@
 mod filter_color;
 mod plot_drivers;
 mod plot_filters;
+mod plot_pareto;
 mod plot_results;
 mod plot_spin;
+mod plot_sub_mains;
 mod ref_lines;
 mod trend_lines;
 
 pub use filter_color::filter_color;
 pub use plot_drivers::{plot_drivers, plot_drivers_results};
 pub use plot_filters::plot_filters;
+pub use plot_pareto::plot_pareto;
 pub use plot_results::plot_results;
-pub use plot_spin::{plot_spin, plot_spin_details, plot_spin_tonal};
+pub use plot_spin::{
+    plot_spin, plot_spin_beamwidth, plot_spin_contour, plot_spin_details, plot_spin_tonal,
+};
+pub use plot_sub_mains::{plot_sub_mains, plot_sub_mains_results};
 pub use trend_lines::*;
@@ -2,22 +2,34 @@ use plotly::common::Mode;
 use plotly::layout::{Axis, AxisType};
 use plotly::{Layout, Plot, Scatter};
 
-use crate::loss::{DriversLossData, compute_drivers_combined_response};
+use crate::loss::{
+    DriversLossData, DriversParams, compute_drivers_combined_response, compute_drivers_responses,
+};
+
+/// Trace colors for the drivers, from the lowest to the highest
+const DRIVER_COLORS: [&str; 8] = [
+    "rgb(31, 119, 180)",  // Blue
+    "rgb(255, 127, 14)",  // Orange
+    "rgb(44, 160, 44)",   // Green
+    "rgb(214, 39, 40)",   // Red
+    "rgb(148, 103, 189)", // Purple
+    "rgb(140, 86, 75)",   // Brown
+    "rgb(227, 119, 194)", // Pink
+    "rgb(188, 189, 34)",  // Olive
+];
 
 /// Create a plot showing individual driver responses and the combined response
 ///
 /// # Arguments
 /// * `drivers_data` - Multi-driver measurement data
-/// * `gains` - Optimized gain values for each driver (in dB)
-/// * `crossover_freqs` - Optimized crossover frequencies (in Hz)
+/// * `params` - Optimized gains, crossover frequencies, delays and polarities
 /// * `sample_rate` - Sample rate for filter design
 ///
 /// # Returns
 /// * Plot object showing all drivers and their combined response
 pub fn plot_drivers(
     drivers_data: &DriversLossData,
-    gains: &[f64],
-    crossover_freqs: &[f64],
+    params: &DriversParams,
     sample_rate: f64,
 ) -> Plot {
     let mut plot = Plot::new();
@@ -25,8 +37,7 @@ pub fn plot_drivers(
     let freq_grid = &drivers_data.freq_grid;
 
     // First, compute the combined response to get a reference normalization
-    let combined_response =
-        compute_drivers_combined_response(drivers_data, gains, crossover_freqs, sample_rate);
+    let combined_response = compute_drivers_combined_response(drivers_data, params, sample_rate);
     let combined_mean = combined_response.mean().unwrap_or(0.0);
 
     // Plot individual drivers (raw responses)
@@ -40,17 +51,11 @@ pub fn plot_drivers(
             },
         );
 
-        let color = match i {
-            0 => "rgb(31, 119, 180)",  // Blue (woofer)
-            1 => "rgb(255, 127, 14)",  // Orange (tweeter)
-            2 => "rgb(44, 160, 44)",   // Green (midrange)
-            3 => "rgb(214, 39, 40)",   // Red (super tweeter)
-            _ => "rgb(128, 128, 128)", // Gray (fallback)
-        };
+        let color = DRIVER_COLORS[i % DRIVER_COLORS.len()];
 
         let trace = Scatter::new(freq_grid.to_vec(), interpolated.spl.to_vec())
             .mode(Mode::Lines)
-            .name(format!("Driver {} (raw)", i + 1))
+            .name(format!("{} (raw)", drivers_data.driver_name(i)))
             .line(
                 plotly::common::Line::new()
                     .color(color)
@@ -61,72 +66,22 @@ pub fn plot_drivers(
         plot.add_trace(trace);
     }
 
-    // Plot individual drivers with gains and crossovers applied
-    // We need to compute the response for each driver on THEIR frequency range only
-    for (i, driver) in drivers_data.drivers.iter().enumerate() {
-        // Use the driver's own frequency range
-        let driver_freq_grid = &driver.freq;
-
-        // Normalize the driver's SPL first (subtract mean to center around 0)
-        let driver_spl_mean = driver.spl.mean().unwrap_or(0.0);
-        let driver_spl_normalized = &driver.spl - driver_spl_mean;
-
-        // Start with the normalized driver's SPL + gain
-        let mut response = &driver_spl_normalized + gains[i];
-
-        // Apply crossover filters on the driver's frequency grid
-        if i > 0 {
-            // Apply highpass from crossover with previous driver
-            let xover_freq = crossover_freqs[i - 1];
-            let hp_filter = match drivers_data.crossover_type {
-                crate::loss::CrossoverType::Butterworth2 => {
-                    crate::iir::peq_butterworth_highpass(2, xover_freq, sample_rate)
-                }
-                crate::loss::CrossoverType::LinkwitzRiley2 => {
-                    crate::iir::peq_linkwitzriley_highpass(2, xover_freq, sample_rate)
-                }
-                crate::loss::CrossoverType::LinkwitzRiley4 => {
-                    crate::iir::peq_linkwitzriley_highpass(4, xover_freq, sample_rate)
-                }
-            };
-            let hp_response =
-                crate::iir::compute_peq_response(driver_freq_grid, &hp_filter, sample_rate);
-            response = response + hp_response;
-        }
+    // Plot individual drivers with gains, crossovers, delays and polarities applied
+    let responses = compute_drivers_responses(drivers_data, params, sample_rate);
+    for (i, h) in responses.iter().enumerate() {
+        let response = h.mapv(|h| 20.0 * h.norm().max(1e-12).log10() - combined_mean);
 
-        if i < drivers_data.drivers.len() - 1 {
-            // Apply lowpass from crossover with next driver
-            let xover_freq = crossover_freqs[i];
-            let lp_filter = match drivers_data.crossover_type {
-                crate::loss::CrossoverType::Butterworth2 => {
-                    crate::iir::peq_butterworth_lowpass(2, xover_freq, sample_rate)
-                }
-                crate::loss::CrossoverType::LinkwitzRiley2 => {
-                    crate::iir::peq_linkwitzriley_lowpass(2, xover_freq, sample_rate)
-                }
-                crate::loss::CrossoverType::LinkwitzRiley4 => {
-                    crate::iir::peq_linkwitzriley_lowpass(4, xover_freq, sample_rate)
-                }
-            };
-            let lp_response =
-                crate::iir::compute_peq_response(driver_freq_grid, &lp_filter, sample_rate);
-            response = response + lp_response;
-        }
+        let color = DRIVER_COLORS[i % DRIVER_COLORS.len()];
 
-        // No need to normalize again - we already normalized the driver SPL at the start
-        // The response is already centered around the gain value
-
-        let color = match i {
-            0 => "rgb(31, 119, 180)",  // Blue (woofer)
-            1 => "rgb(255, 127, 14)",  // Orange (tweeter)
-            2 => "rgb(44, 160, 44)",   // Green (midrange)
-            3 => "rgb(214, 39, 40)",   // Red (super tweeter)
-            _ => "rgb(128, 128, 128)", // Gray (fallback)
-        };
-
-        let trace = Scatter::new(driver_freq_grid.to_vec(), response.to_vec())
+        let trace = Scatter::new(freq_grid.to_vec(), response.to_vec())
             .mode(Mode::Lines)
-            .name(format!("Driver {} ({:+.1} dB)", i + 1, gains[i]))
+            .name(format!(
+                "{} ({:+.1} dB, {:.3} ms{})",
+                drivers_data.driver_name(i),
+                params.gains[i],
+                params.delays_ms[i],
+                if params.inverted[i] { ", inverted" } else { "" }
+            ))
             .line(plotly::common::Line::new().color(color).width(2.0));
 
         plot.add_trace(trace);
@@ -145,7 +100,7 @@ pub fn plot_drivers(
     // Add vertical lines for crossover frequencies
     let mut shapes = Vec::new();
     let mut annotations = Vec::new();
-    for (i, &xover_freq) in crossover_freqs.iter().enumerate() {
+    for (i, &xover_freq) in params.crossover_freqs.iter().enumerate() {
         let shape = plotly::layout::Shape::new()
             .shape_type(plotly::layout::ShapeType::Line)
             .x_ref("x")
@@ -168,7 +123,10 @@ pub fn plot_drivers(
             .y(1.02)
             .x_ref("x")
             .y_ref("paper")
-            .text(format!("Crossover {}: {:.0} Hz", i + 1, xover_freq))
+            .text(format!(
+                "{:.0} Hz {}",
+                xover_freq, drivers_data.crossovers[i]
+            ))
             .show_arrow(false)
             .font(
                 plotly::common::Font::new()
@@ -181,11 +139,13 @@ pub fn plot_drivers(
     }
 
     // Create layout
-    let crossover_type_str = match drivers_data.crossover_type {
-        crate::loss::CrossoverType::Butterworth2 => "2nd order Butterworth",
-        crate::loss::CrossoverType::LinkwitzRiley2 => "2nd order Linkwitz-Riley",
-        crate::loss::CrossoverType::LinkwitzRiley4 => "4th order Linkwitz-Riley",
-    };
+    let first = drivers_data.crossovers[0];
+    let crossover_type_str =
+        if drivers_data.crossovers.iter().all(|c| *c == first) && first.lowpass == first.highpass {
+            first.lowpass.description()
+        } else {
+            "mixed crossovers"
+        };
 
     let layout = Layout::new()
         .title(format!(
@@ -219,8 +179,7 @@ pub fn plot_drivers(
 ///
 /// # Arguments
 /// * `drivers_data` - Multi-driver measurement data
-/// * `gains` - Optimized gain values for each driver (in dB)
-/// * `crossover_freqs` - Optimized crossover frequencies (in Hz)
+/// * `params` - Optimized gains, crossover frequencies, delays and polarities
 /// * `sample_rate` - Sample rate for filter design
 /// * `output_path` - Path to save the HTML file
 ///
@@ -228,8 +187,7 @@ pub fn plot_drivers(
 /// * Result indicating success or failure
 pub fn plot_drivers_results(
     drivers_data: &DriversLossData,
-    gains: &[f64],
-    crossover_freqs: &[f64],
+    params: &DriversParams,
     sample_rate: f64,
     output_path: &std::path::Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -237,7 +195,7 @@ pub fn plot_drivers_results(
     use std::fs::File;
     use std::io::Write;
 
-    let plot = plot_drivers(drivers_data, gains, crossover_freqs, sample_rate);
+    let plot = plot_drivers(drivers_data, params, sample_rate);
 
     let title_text = format!(
         "{}-Way Speaker Crossover Optimization",
@@ -6,7 +6,7 @@ use plotly::layout::{
 };
 use plotly::{Layout, Plot, Scatter};
 
-use crate::iir::{Biquad, BiquadFilterType};
+use crate::iir::{Biquad, BiquadFilterType, Peq, compute_peq_group_delay, compute_peq_phase};
 use crate::param_utils::determine_filter_type;
 use crate::plot::filter_color::filter_color;
 use crate::plot::ref_lines::make_ref_lines;
@@ -89,13 +89,16 @@ pub fn plot_filters(
 
     // For the first subplot (individual filters), compute responses on plot_freqs
     let mut combined_response: Array1<f64> = Array1::zeros(freqs.len());
+    let mut peq: Peq = Vec::with_capacity(filters.len());
     let peq_model = args.effective_peq_model();
     for (display_idx, (orig_i, f0, q, gain)) in filters.iter().enumerate() {
         let ftype = determine_filter_type(*orig_i, args.num_filters, peq_model, None);
-        let filter = Biquad::new(ftype, *f0, args.sample_rate, *q, *gain);
+        let filter =
+            Biquad::new_with_design(ftype, *f0, args.sample_rate, *q, *gain, args.biquad_design);
         // Compute filter response on plot_freqs for the first subplot
         let filter_response = filter.np_log_result(&freqs);
         combined_response += &filter_response;
+        peq.push((1.0, filter));
 
         let label = match ftype {
             BiquadFilterType::Highpass | BiquadFilterType::HighpassVariableQ => "HPQ",
@@ -189,6 +192,30 @@ pub fn plot_filters(
     );
     plot.add_trace(input_plus_eq_trace);
 
+    // Phase and group delay of the EQ (third row)
+    let phase_trace = Scatter::new(freqs.to_vec(), compute_peq_phase(&freqs, &peq).to_vec())
+        .mode(Mode::Lines)
+        .name("Phase")
+        .x_axis("x5")
+        .y_axis("y5")
+        .line(plotly::common::Line::new().color("#000000").width(2.0));
+    plot.add_trace(phase_trace);
+
+    let group_delay_trace = Scatter::new(
+        freqs.to_vec(),
+        compute_peq_group_delay(&freqs, &peq).to_vec(),
+    )
+    .mode(Mode::Lines)
+    .name("Group delay")
+    .x_axis("x6")
+    .y_axis("y6")
+    .line(
+        plotly::common::Line::new()
+            .color(filter_color(3))
+            .width(2.0),
+    );
+    plot.add_trace(group_delay_trace);
+
     // Add reference lines
     let ref_lines3 = make_ref_lines("x3", "y3");
     for ref_line in ref_lines3 {
@@ -199,13 +226,13 @@ pub fn plot_filters(
     let mut layout = Layout::new()
         .grid(
             LayoutGrid::new()
-                .rows(2)
+                .rows(3)
                 .columns(2)
                 .pattern(GridPattern::Independent)
                 .row_order(RowOrder::TopToBottom),
         )
         .width(1024)
-        .height(800)
+        .height(1200)
         .x_axis(
             plotly::layout::Axis::new()
                 .title("Frequency (Hz)".to_string())
@@ -257,7 +284,23 @@ pub fn plot_filters(
                 .title("SPL (dB)".to_string())
                 .dtick(1.0)
                 .range(vec![-10.0, 10.0]),
-        );
+        )
+        .x_axis5(
+            plotly::layout::Axis::new()
+                .title("Frequency (Hz)".to_string())
+                .type_(AxisType::Log)
+                .range(vec![1.301, 4.301])
+                .domain(&[0., 0.45]),
+        )
+        .y_axis5(plotly::layout::Axis::new().title("Phase (deg)".to_string()))
+        .x_axis6(
+            plotly::layout::Axis::new()
+                .title("Frequency (Hz)".to_string())
+                .type_(AxisType::Log)
+                .range(vec![1.301, 4.301])
+                .domain(&[0.55, 1.0]),
+        )
+        .y_axis6(plotly::layout::Axis::new().title("Group delay (ms)".to_string()));
 
     layout.add_annotation(
         Annotation::new()
@@ -307,6 +350,30 @@ pub fn plot_filters(
             .show_arrow(false),
     );
 
+    layout.add_annotation(
+        Annotation::new()
+            .y_ref("y5 domain")
+            .y_anchor(Anchor::Bottom)
+            .y(1)
+            .text("Phase of autoEQ")
+            .x_ref("x5 domain")
+            .x_anchor(Anchor::Center)
+            .x(0.5)
+            .show_arrow(false),
+    );
+
+    layout.add_annotation(
+        Annotation::new()
+            .y_ref("y6 domain")
+            .y_anchor(Anchor::Bottom)
+            .y(1)
+            .text("Group delay of autoEQ")
+            .x_ref("x6 domain")
+            .x_anchor(Anchor::Center)
+            .x(0.5)
+            .show_arrow(false),
+    );
+
     // Add frequency range shapes to highlight regions outside optimization bounds
     let freq_shapes = make_freq_range_shapes(args.min_freq, args.max_freq);
     for shape in freq_shapes {
@@ -0,0 +1,72 @@
+use plotly::common::{ColorScale, ColorScalePalette, Marker, Mode};
+use plotly::layout::Axis;
+use plotly::{Layout, Plot, Scatter};
+
+use crate::pareto::{ParetoSolution, best_per_filter_count};
+
+/// Create a plot of the Pareto set: loss against the number of filters
+///
+/// # Arguments
+/// * `front` - Pareto set sorted by number of filters, then by loss
+///
+/// # Returns
+/// * Plot object with one marker per solution, colored by total positive gain,
+///   and a line through the best solution for each filter count
+pub fn plot_pareto(front: &[ParetoSolution]) -> Plot {
+    let mut plot = Plot::new();
+
+    let text: Vec<String> = front
+        .iter()
+        .map(|s| format!("+{:.1} dB total boost", s.total_positive_gain))
+        .collect();
+    plot.add_trace(
+        Scatter::new(
+            front.iter().map(|s| s.num_filters).collect(),
+            front.iter().map(|s| s.loss).collect(),
+        )
+        .mode(Mode::Markers)
+        .name("Pareto set")
+        .text_array(text)
+        .marker(
+            Marker::new()
+                .size(10)
+                .color_array(front.iter().map(|s| s.total_positive_gain).collect())
+                .color_scale(ColorScale::Palette(ColorScalePalette::Viridis))
+                .show_scale(true),
+        ),
+    );
+
+    let best = best_per_filter_count(front);
+    plot.add_trace(
+        Scatter::new(
+            best.iter().map(|s| s.num_filters).collect(),
+            best.iter().map(|s| s.loss).collect(),
+        )
+        .mode(Mode::Lines)
+        .name("Best per filter count")
+        .line(
+            plotly::common::Line::new()
+                .color("rgb(128, 128, 128)")
+                .width(1.5),
+        ),
+    );
+
+    let layout = Layout::new()
+        .title("Pareto Set: Loss vs Number of Filters (color: total positive gain in dB)")
+        .x_axis(
+            Axis::new()
+                .title("Number of filters".to_string())
+                .dtick(1.0)
+                .grid_color("rgba(128, 128, 128, 0.2)"),
+        )
+        .y_axis(
+            Axis::new()
+                .title("Loss".to_string())
+                .grid_color("rgba(128, 128, 128, 0.2)"),
+        )
+        .height(500)
+        .show_legend(false);
+    plot.set_layout(layout);
+
+    plot
+}
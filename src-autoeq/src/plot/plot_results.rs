@@ -10,8 +10,11 @@ use plotly::Plot;
 use plotly_static::{ImageFormat, StaticExporterBuilder};
 
 use crate::plot::plot_filters::plot_filters;
-use crate::plot::plot_spin::{plot_spin, plot_spin_details, plot_spin_tonal};
-use crate::x2peq::compute_peq_response_from_x;
+use crate::plot::plot_pareto::plot_pareto;
+use crate::plot::plot_spin::{
+    plot_spin, plot_spin_beamwidth, plot_spin_contour, plot_spin_details, plot_spin_tonal,
+};
+use crate::x2peq::x2spl_with_design;
 
 pub async fn plot_compute(
     args: &crate::cli::Args,
@@ -32,11 +35,12 @@ pub async fn plot_compute(
         optimized_params,
     );
 
-    let eq_response = compute_peq_response_from_x(
+    let eq_response = x2spl_with_design(
         &freqs,
         optimized_params,
         args.sample_rate,
         args.effective_peq_model(),
+        args.biquad_design,
     );
     let plot_spin_details = if cea2034_curves.is_some() {
         Some(plot_spin_details(
@@ -67,6 +71,23 @@ pub async fn plot_compute(
     )
 }
 
+/// Contour and beamwidth plots when the H/V measurements are available (`--spin-dir`)
+fn plot_directivity(args: &crate::cli::Args) -> Option<(Plot, Plot)> {
+    let dir = args.spin_dir.as_ref()?;
+    let m = match crate::read::load_spin_measurements(dir) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("⚠️ Warning: Failed to load directivity data: {}", e);
+            return None;
+        }
+    };
+    let metrics = crate::cea2034::directivity_metrics(&m.spl_h, &m.spl_v);
+    Some((
+        plot_spin_contour(&m.freq, &metrics),
+        plot_spin_beamwidth(&m.freq, &metrics),
+    ))
+}
+
 /// Generate and save an HTML plot comparing the input curve with the optimized EQ response.
 ///
 /// # Arguments
@@ -77,10 +98,11 @@ pub async fn plot_compute(
 /// * `optimized_params` - The optimized filter parameters
 /// * `output_path` - The path to save the HTML output file
 /// * `cea2034_curves` - Optional CEA2034 curves to include in the plot
-/// * `eq_response` - Optional EQ response to include in the plot
+/// * `pareto_front` - Optional Pareto set (`--pareto`) to plot after the filters
 ///
 /// # Returns
 /// * Result indicating success or failure
+#[allow(clippy::too_many_arguments)]
 pub async fn plot_results(
     args: &crate::cli::Args,
     optimized_params: &[f64],
@@ -88,6 +110,7 @@ pub async fn plot_results(
     target_curve: &crate::Curve,
     deviation_curve: &crate::Curve,
     cea2034_curves: &Option<HashMap<String, crate::Curve>>,
+    pareto_front: Option<&[crate::pareto::ParetoSolution]>,
     output_path: &Path,
 ) -> Result<(), Box<dyn Error>> {
     let speaker = args.speaker.as_deref();
@@ -100,6 +123,7 @@ pub async fn plot_results(
         cea2034_curves,
     )
     .await;
+    let plot_directivity = plot_directivity(args);
 
     // Title with optional speaker name
     let title_text = match speaker {
@@ -112,6 +136,11 @@ pub async fn plot_results(
             .with_title(title_text)
             .with_script_link("https://cdn.plot.ly/plotly-3.2.0.min.js")
             .with_raw(plot_filters.to_inline_html(Some("filters")));
+        let base = if let Some(front) = pareto_front {
+            base.with_raw(plot_pareto(front).to_inline_html(Some("pareto")))
+        } else {
+            base
+        };
         let page = if let Some(ref plot_spin) = plot_spin_details {
             base.with_raw(plot_spin.to_inline_html(Some("details")))
         } else {
@@ -127,7 +156,14 @@ pub async fn plot_results(
         } else {
             page2
         };
-        page3.to_html_string()
+        let page4 = if let Some((ref contour, ref beamwidth)) = plot_directivity {
+            page3
+                .with_raw(contour.to_inline_html(Some("contour")))
+                .with_raw(beamwidth.to_inline_html(Some("beamwidth")))
+        } else {
+            page3
+        };
+        page4.to_html_string()
     };
 
     // Ensure parent directory exists before writing files
@@ -168,6 +204,12 @@ pub async fn plot_results(
         plots.push((plot_spin, "spinorama", 1280, 450));
     }
 
+    #[cfg(feature = "plotly_static")]
+    if let Some((contour, beamwidth)) = plot_directivity {
+        plots.push((contour, "contour", 1280, 450));
+        plots.push((beamwidth, "beamwidth", 1280, 450));
+    }
+
     // PNG export functionality - only available with plotly_static feature
     #[cfg(feature = "plotly_static")]
     {
@@ -1,7 +1,8 @@
 use ndarray::Array1;
-use plotly::common::{AxisSide, Mode};
+use plotly::common::{AxisSide, ColorScale, ColorScalePalette, Mode};
+use plotly::contour::{Coloring, Contours};
 use plotly::layout::{AxisType, GridPattern, LayoutGrid, RowOrder};
-use plotly::{Layout, Plot, Scatter};
+use plotly::{Contour, Layout, Plot, Scatter};
 use std::collections::HashMap;
 
 use crate::plot::filter_color::filter_color;
@@ -546,12 +547,152 @@ pub fn plot_spin(
     plot
 }
 
+/// Plot the horizontal and vertical directivity as contours normalized to on axis
+///
+/// # Arguments
+/// * `freq` - Frequency grid of the measurements
+/// * `metrics` - Directivity metrics computed from the H/V measurements
+pub fn plot_spin_contour(
+    freq: &Array1<f64>,
+    metrics: &crate::cea2034::DirectivityMetrics,
+) -> plotly::Plot {
+    let mut plot = Plot::new();
+    let angles: Vec<f64> = crate::cea2034::CEA2034_ANGLES
+        .iter()
+        .map(|a| *a as f64)
+        .collect();
+    let contours = Contours::new()
+        .start(-30.0)
+        .end(0.0)
+        .size(3.0)
+        .coloring(Coloring::HeatMap);
+
+    for (i, (contour, x_axis, y_axis)) in [
+        (&metrics.contour_h, "x", "y"),
+        (&metrics.contour_v, "x2", "y2"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let z: Vec<Vec<f64>> = contour.rows().into_iter().map(|r| r.to_vec()).collect();
+        let trace = Contour::new(freq.to_vec(), angles.clone(), z)
+            .contours(contours.clone())
+            .color_scale(ColorScale::Palette(ColorScalePalette::Jet))
+            .show_scale(i == 0)
+            .x_axis(x_axis)
+            .y_axis(y_axis);
+        plot.add_trace(trace);
+    }
+
+    let angle_axis = |title: &str| {
+        plotly::layout::Axis::new()
+            .title(plotly::common::Title::with_text(title))
+            .range(vec![-180.0, 180.0])
+            .dtick(30.0)
+    };
+    let layout = Layout::new()
+        .grid(
+            LayoutGrid::new()
+                .rows(1)
+                .columns(2)
+                .pattern(GridPattern::Independent),
+        )
+        .width(1024)
+        .height(450)
+        .x_axis(
+            plotly::layout::Axis::new()
+                .title(plotly::common::Title::with_text("Horizontal contour (Hz)"))
+                .type_(AxisType::Log)
+                .range(vec![1.301, 4.301])
+                .domain(&[0., 0.42]),
+        )
+        .y_axis(angle_axis("Angle (°)"))
+        .x_axis2(
+            plotly::layout::Axis::new()
+                .title(plotly::common::Title::with_text("Vertical contour (Hz)"))
+                .type_(AxisType::Log)
+                .range(vec![1.301, 4.301])
+                .domain(&[0.52, 0.94]),
+        )
+        .y_axis2(angle_axis("Angle (°)").anchor("x2"));
+    plot.set_layout(layout);
+
+    plot
+}
+
+/// Plot the -6 dB beamwidths and the directivity indices versus frequency
+///
+/// # Arguments
+/// * `freq` - Frequency grid of the measurements
+/// * `metrics` - Directivity metrics computed from the H/V measurements
+pub fn plot_spin_beamwidth(
+    freq: &Array1<f64>,
+    metrics: &crate::cea2034::DirectivityMetrics,
+) -> plotly::Plot {
+    let mut plot = Plot::new();
+    let curves = [
+        ("Horizontal beamwidth", &metrics.beamwidth_h, "x", "y"),
+        ("Vertical beamwidth", &metrics.beamwidth_v, "x", "y"),
+        ("DI", &metrics.di, "x2", "y2"),
+        ("ERDI", &metrics.erdi, "x2", "y2"),
+        ("SPDI", &metrics.spdi, "x2", "y2"),
+    ];
+    for (i, (name, values, x_axis, y_axis)) in curves.into_iter().enumerate() {
+        let trace = Scatter::new(freq.to_vec(), values.to_vec())
+            .mode(Mode::Lines)
+            .name(name)
+            .x_axis(x_axis)
+            .y_axis(y_axis)
+            .line(plotly::common::Line::new().color(filter_color(i)));
+        plot.add_trace(trace);
+    }
+
+    let layout = Layout::new()
+        .grid(
+            LayoutGrid::new()
+                .rows(1)
+                .columns(2)
+                .pattern(GridPattern::Independent),
+        )
+        .width(1024)
+        .height(450)
+        .x_axis(
+            plotly::layout::Axis::new()
+                .title(plotly::common::Title::with_text("Beamwidth -6 dB (Hz)"))
+                .type_(AxisType::Log)
+                .range(vec![1.301, 4.301])
+                .domain(&[0., 0.45]),
+        )
+        .y_axis(
+            plotly::layout::Axis::new()
+                .title(plotly::common::Title::with_text("Angle (°)"))
+                .range(vec![0.0, 360.0])
+                .dtick(60.0),
+        )
+        .x_axis2(
+            plotly::layout::Axis::new()
+                .title(plotly::common::Title::with_text("Directivity index (Hz)"))
+                .type_(AxisType::Log)
+                .range(vec![1.301, 4.301])
+                .domain(&[0.55, 1.0]),
+        )
+        .y_axis2(
+            plotly::layout::Axis::new()
+                .title(plotly::common::Title::with_text("DI (dB)"))
+                .range(vec![-5.0, 20.0])
+                .anchor("x2"),
+        );
+    plot.set_layout(layout);
+
+    plot
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         create_cea2034_combined_traces, create_cea2034_traces,
         create_cea2034_with_eq_combined_traces, create_cea2034_with_eq_traces, make_ref_lines,
-        shorten_curve_name,
+        plot_spin_beamwidth, plot_spin_contour, shorten_curve_name,
     };
     use ndarray::Array1;
     use serde_json::json;
@@ -799,4 +940,38 @@ mod tests {
         assert_eq!(shorten_curve_name("on axis"), "on axis");
         assert_eq!(shorten_curve_name("ON AXIS"), "ON AXIS");
     }
+
+    #[test]
+    fn test_plot_spin_directivity_traces() {
+        let freq = Array1::from(vec![100.0, 1000.0, 10000.0]);
+        let spl = ndarray::Array2::from_shape_fn((36, 3), |(i, j)| {
+            85.0 - crate::cea2034::CEA2034_ANGLES[i].unsigned_abs() as f64 / 10.0 * j as f64
+        });
+        let metrics = crate::cea2034::directivity_metrics(&spl, &spl);
+
+        let contour = to_json(plot_spin_contour(&freq, &metrics)).unwrap();
+        let traces = contour["data"].as_array().unwrap();
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0]["type"], json!("contour"));
+        assert_eq!(traces[1]["xaxis"], json!("x2"));
+        assert_eq!(traces[0]["z"].as_array().unwrap().len(), 36);
+
+        let beamwidth = to_json(plot_spin_beamwidth(&freq, &metrics)).unwrap();
+        let names: Vec<_> = beamwidth["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Horizontal beamwidth",
+                "Vertical beamwidth",
+                "DI",
+                "ERDI",
+                "SPDI"
+            ]
+        );
+    }
 }
@@ -0,0 +1,151 @@
+use plotly::common::Mode;
+use plotly::layout::{Axis, AxisType};
+use plotly::{Layout, Plot, Scatter};
+
+use crate::loss::{SubMainsLossData, SubMainsParams, compute_sub_mains_branches};
+
+fn to_db(h: &ndarray::Array1<num_complex::Complex64>) -> Vec<f64> {
+    h.iter()
+        .map(|h| 20.0 * h.norm().max(1e-12).log10())
+        .collect()
+}
+
+/// Create a plot of the mains, the sub and their sum before and after integration
+///
+/// # Arguments
+/// * `data` - Sub + mains measurement data
+/// * `params` - Optimized crossover, alignment and PEQ parameters
+/// * `sample_rate` - Sample rate for filter design
+///
+/// # Returns
+/// * Plot object showing raw and processed responses and their sums
+pub fn plot_sub_mains(data: &SubMainsLossData, params: &SubMainsParams, sample_rate: f64) -> Plot {
+    let mut plot = Plot::new();
+    let freqs = data.freq_grid.to_vec();
+
+    let (mains, sub) = compute_sub_mains_branches(data, params, sample_rate);
+    let traces = [
+        (to_db(&data.mains), "Mains (raw)", "rgb(31, 119, 180)", true),
+        (to_db(&data.sub), "Sub (raw)", "rgb(255, 127, 14)", true),
+        (
+            to_db(&(&data.mains + &data.sub)),
+            "Sum (raw)",
+            "rgb(128, 128, 128)",
+            true,
+        ),
+        (
+            to_db(&mains),
+            "Mains (HP + PEQ)",
+            "rgb(31, 119, 180)",
+            false,
+        ),
+        (
+            to_db(&sub),
+            "Sub (LP + PEQ + alignment)",
+            "rgb(255, 127, 14)",
+            false,
+        ),
+        (to_db(&(&mains + &sub)), "Sum", "rgb(0, 0, 0)", false),
+    ];
+    for (spl, name, color, raw) in traces {
+        let line = if raw {
+            plotly::common::Line::new()
+                .color(color)
+                .width(1.5)
+                .dash(plotly::common::DashType::Dash)
+        } else {
+            plotly::common::Line::new().color(color).width(2.5)
+        };
+        plot.add_trace(
+            Scatter::new(freqs.clone(), spl)
+                .mode(Mode::Lines)
+                .name(name)
+                .line(line),
+        );
+    }
+
+    let shape = plotly::layout::Shape::new()
+        .shape_type(plotly::layout::ShapeType::Line)
+        .x_ref("x")
+        .y_ref("paper")
+        .x0(params.crossover_freq)
+        .x1(params.crossover_freq)
+        .y0(0.0)
+        .y1(1.0)
+        .line(
+            plotly::layout::ShapeLine::new()
+                .color("rgba(150, 150, 150, 0.6)")
+                .width(2.0)
+                .dash(plotly::common::DashType::Dot),
+        );
+
+    let layout = Layout::new()
+        .title(format!(
+            "Sub + Mains Integration (crossover {:.0} Hz, sub {:+.1} dB, delay {:+.2} ms, polarity {})",
+            params.crossover_freq,
+            params.sub_gain_db,
+            params.sub_delay_ms,
+            if params.sub_inverted {
+                "inverted"
+            } else {
+                "normal"
+            }
+        ))
+        .x_axis(
+            Axis::new()
+                .title("Frequency (Hz)".to_string())
+                .type_(AxisType::Log)
+                .grid_color("rgba(128, 128, 128, 0.2)"),
+        )
+        .y_axis(
+            Axis::new()
+                .title("SPL (dB)".to_string())
+                .grid_color("rgba(128, 128, 128, 0.2)"),
+        )
+        .shapes(vec![shape])
+        .height(600)
+        .hover_mode(plotly::layout::HoverMode::X);
+    plot.set_layout(layout);
+
+    plot
+}
+
+/// Generate and save an HTML plot for sub + mains integration results
+///
+/// # Arguments
+/// * `data` - Sub + mains measurement data
+/// * `params` - Optimized crossover, alignment and PEQ parameters
+/// * `sample_rate` - Sample rate for filter design
+/// * `output_path` - Path to save the HTML file
+///
+/// # Returns
+/// * Result indicating success or failure
+pub fn plot_sub_mains_results(
+    data: &SubMainsLossData,
+    params: &SubMainsParams,
+    sample_rate: f64,
+    output_path: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use build_html::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    let plot = plot_sub_mains(data, params, sample_rate);
+
+    let html = HtmlPage::new()
+        .with_title("Sub + Mains Integration")
+        .with_script_link("https://cdn.plot.ly/plotly-3.2.0.min.js")
+        .with_raw(plot.to_inline_html(Some("sub_mains")))
+        .to_html_string();
+
+    let html_output_path = output_path.with_extension("html");
+    if let Some(parent) = html_output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut file = File::create(&html_output_path)?;
+    file.write_all(html.as_bytes())?;
+    file.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::Curve;
+use crate::loss::{SpatialAverage, normalized_weights};
+use ndarray::Array1;
+
+/// Combine curves measured at several listening positions into one
+///
+/// # Arguments
+/// * `curves` - Curves sharing the same frequency grid
+/// * `weights` - Weight of each curve; empty means equal weights
+/// * `mode` - Average the power or the dB values
+///
+/// # Returns
+/// * The weighted spatial average, or an error if the curves do not share a
+///   frequency grid or the weights do not match
+pub fn spatial_average(
+    curves: &[Curve],
+    weights: &[f64],
+    mode: SpatialAverage,
+) -> Result<Curve, String> {
+    let first = curves
+        .first()
+        .ok_or_else(|| "spatial average needs at least one curve".to_string())?;
+    if !weights.is_empty() && weights.len() != curves.len() {
+        return Err(format!(
+            "got {} weights for {} curves",
+            weights.len(),
+            curves.len()
+        ));
+    }
+    if weights.iter().any(|w| *w < 0.0)
+        || (!weights.is_empty() && weights.iter().sum::<f64>() <= 0.0)
+    {
+        return Err("weights must be non negative with a positive sum".to_string());
+    }
+    if curves.iter().any(|c| c.freq != first.freq) {
+        return Err("curves must share the same frequency grid".to_string());
+    }
+
+    let weights = normalized_weights(weights, curves.len());
+    let spl = match mode {
+        SpatialAverage::Db => curves
+            .iter()
+            .zip(&weights)
+            .fold(Array1::zeros(first.freq.len()), |acc, (c, w)| {
+                acc + &c.spl * *w
+            }),
+        SpatialAverage::Power => curves
+            .iter()
+            .zip(&weights)
+            .fold(Array1::zeros(first.freq.len()), |acc, (c, w)| {
+                acc + c.spl.mapv(|db| 10.0_f64.powf(db / 10.0)) * *w
+            })
+            .mapv(|p: f64| 10.0 * p.log10()),
+    };
+
+    Ok(Curve {
+        freq: first.freq.clone(),
+        spl,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(spl: f64) -> Curve {
+        Curve {
+            freq: Array1::from(vec![100.0, 1000.0]),
+            spl: Array1::from(vec![spl, spl]),
+        }
+    }
+
+    #[test]
+    fn spatial_average_power_and_db() {
+        let curves = [curve(0.0), curve(-10.0)];
+        let db = spatial_average(&curves, &[], SpatialAverage::Db).unwrap();
+        assert!((db.spl[0] + 5.0).abs() < 1e-12);
+        // power average is dominated by the loudest position
+        let power = spatial_average(&curves, &[], SpatialAverage::Power).unwrap();
+        assert!((power.spl[0] - 10.0 * (1.1_f64 / 2.0).log10()).abs() < 1e-12);
+
+        let weighted = spatial_average(&curves, &[3.0, 1.0], SpatialAverage::Db).unwrap();
+        assert!((weighted.spl[1] + 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn spatial_average_errors() {
+        assert!(spatial_average(&[], &[], SpatialAverage::Db).is_err());
+        let curves = [curve(0.0), curve(-10.0)];
+        assert!(spatial_average(&curves, &[1.0], SpatialAverage::Db).is_err());
+        assert!(spatial_average(&curves, &[0.0, 0.0], SpatialAverage::Db).is_err());
+        let other = Curve {
+            freq: Array1::from(vec![200.0, 1000.0]),
+            spl: Array1::zeros(2),
+        };
+        assert!(spatial_average(&[curve(0.0), other], &[], SpatialAverage::Power).is_err());
+    }
+}
@@ -15,6 +15,7 @@
 //! You should have received a copy of the GNU General Public License
 //! along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod average;
 mod clamp;
 mod directory;
 mod interpolate;
@@ -22,10 +23,13 @@ mod normalize;
 mod plot;
 mod read_api;
 mod read_csv;
+mod read_spin_dir;
 mod smooth;
 mod speaker_suggestions;
+mod spinorama_dataset;
 
 // Re-export commonly used functions
+pub use average::spatial_average;
 pub use clamp::clamp_positive_only;
 pub use directory::data_dir_for;
 pub use directory::measurement_filename;
@@ -35,7 +39,10 @@ pub use normalize::*;
 pub use read_api::*;
 pub use read_csv::load_driver_measurement;
 pub use read_csv::load_frequency_response;
+pub use read_csv::parse_frequency_response;
 pub use read_csv::read_curve_from_csv;
+pub use read_spin_dir::{SpinMeasurements, load_spin_directory, load_spin_measurements};
 pub use smooth::smooth_gaussian;
 pub use smooth::smooth_one_over_n_octave;
 pub use speaker_suggestions::*;
+pub use spinorama_dataset::*;
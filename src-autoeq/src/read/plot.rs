@@ -2,6 +2,7 @@ use crate::read::plot;
 use crate::read::speaker_suggestions::{
     ApiError, fetch_available_speakers, find_similar_speakers, format_speaker_not_found_error,
 };
+use crate::read::spinorama_dataset::cached_speakers;
 use serde_json::Value;
 use std::error::Error;
 
@@ -41,8 +42,20 @@ pub async fn normalize_plotly_value_with_suggestions(v: &Value) -> Result<Value,
     // If it's not an array, check if it's a speaker error
     if let Some(api_error) = ApiError::from_json(v) {
         if let Some(invalid_speaker) = api_error.speaker_name {
-            // Try to fetch available speakers and provide suggestions
-            match fetch_available_speakers().await {
+            // Try to fetch available speakers and provide suggestions,
+            // from the local index when the API list is not available
+            let available_speakers = match fetch_available_speakers().await {
+                Ok(speakers) => Ok(speakers),
+                Err(e) => {
+                    let cached = cached_speakers();
+                    if cached.is_empty() {
+                        Err(e)
+                    } else {
+                        Ok(cached)
+                    }
+                }
+            };
+            match available_speakers {
                 Ok(available_speakers) => {
                     let suggestions =
                         find_similar_speakers(&invalid_speaker, &available_speakers, 5);
@@ -13,6 +13,7 @@ use crate::cea2034 as score;
 use crate::read::directory::{measurement_filename, sanitize_dir_name};
 use crate::read::interpolate::interpolate;
 use crate::read::plot::{normalize_plotly_json_from_str, normalize_plotly_value_with_suggestions};
+use crate::read::spinorama_dataset::offline_lookup_error;
 use autoeq_env::DATA_CACHED;
 
 /// Fetch a frequency response curve from the spinorama API
@@ -78,7 +79,9 @@ pub async fn fetch_measurement_plot_data(
 
     // println!("* Fetching data from {}", url);
 
-    let response = reqwest::get(&url).await?;
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| offline_lookup_error(speaker, measurement, &e.to_string()))?;
     if !response.status().is_success() {
         return Err(format!("API request failed with status: {}", response.status()).into());
     }
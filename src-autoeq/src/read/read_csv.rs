@@ -14,8 +14,15 @@ pub fn load_frequency_response(
     path: &PathBuf,
 ) -> Result<(Array1<f64>, Array1<f64>), Box<dyn std::error::Error>> {
     let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    parse_frequency_response(BufReader::new(file))
+}
 
+/// Parse frequency response data from any buffered reader
+///
+/// Accepts the same formats as [`load_frequency_response`].
+pub fn parse_frequency_response<R: BufRead>(
+    reader: R,
+) -> Result<(Array1<f64>, Array1<f64>), Box<dyn std::error::Error>> {
     let mut frequencies = Vec::new();
     let mut spl_values = Vec::new();
     let mut detected_columns = 0;
@@ -106,6 +113,8 @@ pub fn read_curve_from_csv(path: &PathBuf) -> Result<Curve, Box<dyn Error>> {
 /// Expected formats:
 /// - 2 columns: frequency, spl
 /// - 3 columns: frequency, spl, phase
+///
+/// Lines starting with `#`, `//` or `*` (REW exports) are comments.
 pub fn load_driver_measurement(
     path: &PathBuf,
 ) -> Result<(Array1<f64>, Array1<f64>, Option<Array1<f64>>), Box<dyn std::error::Error>> {
@@ -123,7 +132,11 @@ pub fn load_driver_measurement(
         let line = line.trim();
 
         // Skip empty lines and comments
-        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("//")
+            || line.starts_with('*')
+        {
             continue;
         }
 
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::Curve;
+use crate::cea2034::{CEA2034_ANGLES, spin_from_spl};
+use ndarray::{Array1, Array2};
+
+use super::interpolate::interpolate;
+use super::read_csv::load_driver_measurement;
+
+/// Parse the plane and angle of a per-angle measurement from its file name
+///
+/// Accepts names ending with `H` or `V`, an optional separator and a signed
+/// angle in degrees, e.g. `Speaker_H-30.txt`, `Speaker V 40.txt`,
+/// `H+10deg.frd` or `SPK_V_-20.csv`.
+///
+/// # Returns
+/// * `(vertical, angle)` or None if the name does not carry an angle
+fn parse_angle_file_name(path: &Path) -> Option<(bool, i32)> {
+    let stem = path.file_stem()?.to_str()?;
+    let stem = stem
+        .trim_end()
+        .trim_end_matches("deg")
+        .trim_end_matches('°')
+        .trim_end();
+
+    let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let (rest, value) = stem.split_at(stem.len() - digits);
+    let value: i32 = value.parse().ok()?;
+    let (rest, sign) = match rest.strip_suffix('-') {
+        Some(rest) => (rest, -1),
+        None => (rest.strip_suffix('+').unwrap_or(rest), 1),
+    };
+    let rest = rest.trim_end_matches([' ', '_']);
+
+    let mut chars = rest.chars().rev();
+    let vertical = match chars.next()?.to_ascii_uppercase() {
+        'H' => false,
+        'V' => true,
+        _ => return None,
+    };
+    if chars.next().is_some_and(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some((vertical, sign * value))
+}
+
+/// Horizontal and vertical measurements of a speaker on a common frequency grid
+#[derive(Debug, Clone)]
+pub struct SpinMeasurements {
+    /// Frequency grid of the horizontal on-axis measurement
+    pub freq: Array1<f64>,
+    /// Horizontal SPL, one row per angle of [`CEA2034_ANGLES`]
+    pub spl_h: Array2<f64>,
+    /// Vertical SPL, one row per angle of [`CEA2034_ANGLES`]
+    pub spl_v: Array2<f64>,
+}
+
+/// Load a directory of per-angle measurements into horizontal and vertical SPL matrices
+///
+/// The directory holds one file per angle for the horizontal and vertical
+/// planes, 10 degrees apart, named like `Speaker_H-30.txt` or `Speaker V 40.txt`.
+/// Each file is a frequency response with `freq spl [phase]` columns, as
+/// exported by Klippel or REW. A missing negative angle is taken from the
+/// positive one and vice versa (symmetric speaker); the vertical 0 and 180
+/// degrees default to the horizontal ones.
+///
+/// # Arguments
+/// * `dir` - Directory with the measurements
+///
+/// # Returns
+/// * The SPL matrices in the row order expected by [`spin_from_spl`]
+pub fn load_spin_measurements(dir: &Path) -> Result<SpinMeasurements, Box<dyn Error>> {
+    let mut measurements: HashMap<(bool, i32), Curve> = HashMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path: PathBuf = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let Some((vertical, angle)) = parse_angle_file_name(&path) else {
+            continue;
+        };
+        if angle % 10 != 0 || !(-180..=180).contains(&angle) {
+            continue;
+        }
+        let (freq, spl, _) = load_driver_measurement(&path)
+            .map_err(|e| format!("Failed to load '{}': {}", path.display(), e))?;
+        let angle = if angle == -180 { 180 } else { angle };
+        measurements.insert((vertical, angle), Curve { freq, spl });
+    }
+
+    let on_axis = measurements
+        .get(&(false, 0))
+        .ok_or_else(|| format!("No horizontal 0 degree measurement in '{}'", dir.display()))?;
+    let freq = on_axis.freq.clone();
+
+    let mut spl_h = Array2::zeros((CEA2034_ANGLES.len(), freq.len()));
+    let mut spl_v = Array2::zeros((CEA2034_ANGLES.len(), freq.len()));
+    for (vertical, spl) in [(false, &mut spl_h), (true, &mut spl_v)] {
+        for (row, angle) in CEA2034_ANGLES.iter().enumerate() {
+            let curve = measurements
+                .get(&(vertical, *angle))
+                .or_else(|| measurements.get(&(vertical, -angle)))
+                .or_else(|| {
+                    (vertical && (*angle == 0 || *angle == 180))
+                        .then(|| measurements.get(&(false, *angle)))
+                        .flatten()
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "Missing {} measurement at {} degrees in '{}'",
+                        if vertical { "vertical" } else { "horizontal" },
+                        angle,
+                        dir.display()
+                    )
+                })?;
+            spl.row_mut(row).assign(&interpolate(&freq, curve).spl);
+        }
+    }
+
+    Ok(SpinMeasurements { freq, spl_h, spl_v })
+}
+
+/// Load a directory of per-angle measurements and compute the CEA2034 curves
+///
+/// See [`load_spin_measurements`] for the expected files.
+///
+/// # Returns
+/// * On Axis, Listening Window, Early Reflections, Sound Power, the two
+///   directivity indices and the Estimated In-Room Response, on the frequency
+///   grid of the horizontal on-axis measurement
+pub fn load_spin_directory(dir: &Path) -> Result<HashMap<String, Curve>, Box<dyn Error>> {
+    let m = load_spin_measurements(dir)?;
+    Ok(spin_from_spl(&m.freq, &m.spl_h, &m.spl_v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn angle_from_file_name() {
+        let parse = |name: &str| parse_angle_file_name(Path::new(name));
+        assert_eq!(parse("Speaker_H-30.txt"), Some((false, -30)));
+        assert_eq!(parse("Speaker V 40.txt"), Some((true, 40)));
+        assert_eq!(parse("H+10deg.frd"), Some((false, 10)));
+        assert_eq!(parse("SPK_V_-20.csv"), Some((true, -20)));
+        assert_eq!(parse("Speaker H 180.txt"), Some((false, 180)));
+        assert_eq!(parse("Speaker.txt"), None);
+        assert_eq!(parse("Speaker2.txt"), None);
+        assert_eq!(parse("SPH10.txt"), None);
+    }
+
+    #[test]
+    fn load_spin_directory_from_half_orbits() {
+        let dir = std::env::temp_dir().join(format!("autoeq-spin-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // 0 to 180 degrees only, 1 dB less every 10 degrees off axis
+        for (plane, last) in [("H", 180), ("V", 170)] {
+            for angle in (0..=last).step_by(10) {
+                let mut file =
+                    std::fs::File::create(dir.join(format!("spk_{} {}.txt", plane, angle)))
+                        .unwrap();
+                writeln!(file, "* REW export").unwrap();
+                for freq in [100.0, 1000.0, 10000.0] {
+                    writeln!(file, "{} {} 0.0", freq, 85.0 - angle as f64 / 10.0).unwrap();
+                }
+            }
+        }
+
+        let spin = load_spin_directory(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!((spin["On Axis"].spl[1] - 85.0).abs() < 1e-9);
+        let lw = spin["Listening Window"].spl[1];
+        assert!(lw < 85.0 && lw > spin["Sound Power"].spl[1]);
+        assert!(spin["Sound Power DI"].spl[1] > 0.0);
+        assert_eq!(spin["Estimated In-Room Response"].freq.len(), 3);
+    }
+
+    #[test]
+    fn load_spin_directory_reports_missing_angle() {
+        let dir = std::env::temp_dir().join(format!("autoeq-spin-miss-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("spk_H 0.txt"), "100 85\n1000 85\n").unwrap();
+
+        let err = load_spin_directory(&dir).unwrap_err().to_string();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(err.contains("horizontal measurement at -170"));
+    }
+}
@@ -0,0 +1,565 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use autoeq_env::DATA_CACHED;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::read::directory::{measurement_filename, sanitize_dir_name};
+use crate::read::plot::normalize_plotly_json_from_str;
+use crate::read::speaker_suggestions::{find_similar_speakers, format_speaker_not_found_error};
+
+/// File name of the speaker index inside the cache directory
+pub const SPEAKER_INDEX_FILE: &str = "index.json";
+
+/// One speaker of the local cache
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerIndexEntry {
+    /// Speaker name as used by spinorama.org (`--speaker`)
+    pub speaker: String,
+    /// Directory of the speaker inside the cache
+    pub dir: String,
+    /// Cached measurements, e.g. "CEA2034"
+    pub measurements: Vec<String>,
+    /// Preference score of the default measurement from metadata.json, if any
+    pub pref_score: Option<f64>,
+}
+
+/// Summary of a dataset ingestion
+#[derive(Debug, Clone, Default)]
+pub struct IngestReport {
+    /// Number of speakers with at least one measurement in the dataset
+    pub speakers: usize,
+    /// Number of measurement and metadata files written to the cache
+    pub written: usize,
+    /// Number of files already in the cache and left untouched
+    pub skipped: usize,
+    /// Files that are not valid Plotly JSON, with the reason
+    pub invalid: Vec<(PathBuf, String)>,
+}
+
+/// Read the JSON files of a dataset whose file stem passes `keep`
+///
+/// The dataset is a directory, a zip archive or a tarball (`.tar`, `.tar.gz`, `.tgz`).
+fn read_dataset_files(
+    source: &Path,
+    keep: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<(PathBuf, String)>, Box<dyn Error>> {
+    let wanted = |path: &Path| {
+        path.extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+            && keep(path)
+    };
+    let mut files = Vec::new();
+
+    if source.is_dir() {
+        let mut dirs = vec![source.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if wanted(&path) {
+                    let content = std::fs::read_to_string(&path)?;
+                    files.push((path.strip_prefix(source)?.to_path_buf(), content));
+                }
+            }
+        }
+        return Ok(files);
+    }
+
+    let name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    if name.ends_with(".zip") {
+        let mut archive = zip::ZipArchive::new(File::open(source)?)?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            if entry.is_file() && wanted(&path) {
+                let mut content = String::new();
+                entry.read_to_string(&mut content)?;
+                files.push((path, content));
+            }
+        }
+    } else if name.ends_with(".tar") || name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        let file = File::open(source)?;
+        let reader: Box<dyn Read> = if name.ends_with(".tar") {
+            Box::new(file)
+        } else {
+            Box::new(flate2::read::GzDecoder::new(file))
+        };
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.into_owned();
+            if entry.header().entry_type().is_file() && wanted(&path) {
+                let mut content = String::new();
+                entry.read_to_string(&mut content)?;
+                files.push((path, content));
+            }
+        }
+    } else {
+        return Err(format!(
+            "'{}' is neither a directory, a zip archive nor a tarball",
+            source.display()
+        )
+        .into());
+    }
+    Ok(files)
+}
+
+/// Speaker a file belongs to: the closest parent directory named after a known
+/// speaker, or else the parent directory (the `data_cached/{speaker}/` layout)
+fn speaker_of(path: &Path, known: &HashMap<String, String>) -> Option<String> {
+    let parents: Vec<&str> = path
+        .parent()?
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    parents
+        .iter()
+        .rev()
+        .find_map(|c| known.get(&sanitize_dir_name(c)).cloned())
+        .or_else(|| parents.last().map(|c| c.to_string()))
+}
+
+/// Preference score of the default measurement in a speaker metadata
+fn metadata_pref_score(metadata: &Value) -> Option<f64> {
+    let default_measurement = metadata.get("default_measurement")?.as_str()?;
+    metadata
+        .get("measurements")?
+        .get(default_measurement)?
+        .get("pref_rating")?
+        .get("pref_score")?
+        .as_f64()
+}
+
+/// Copy a local spinorama dataset into the cache used by `--speaker`
+///
+/// Measurement files are named after the measurement (`CEA2034.json`,
+/// `Estimated In-Room Response.json`) and hold Plotly JSON, either as an object
+/// or in the API format. They are found at any depth: the speaker is the
+/// closest parent directory named after a speaker of a `metadata.json`, or else
+/// the parent directory. `metadata.json` is either the per-speaker metadata of
+/// the API or the spinorama.org map from speaker name to metadata. When a
+/// speaker has several versions of a measurement, the one of its default
+/// measurement is kept, otherwise the first one in path order.
+///
+/// Each measurement is validated with `normalize_plotly_json_from_str` and
+/// written to `{cache_dir}/{speaker}/{measurement}.json`, the speaker metadata
+/// to `{cache_dir}/{speaker}/metadata.json`. The speaker index is then updated
+/// (see [`update_speaker_index`]).
+///
+/// # Arguments
+/// * `source` - Directory, zip archive or tarball of the dataset
+/// * `cache_dir` - Cache directory, usually `data_cached`
+/// * `measurements` - Measurements to import
+/// * `speaker_filter` - Only import speakers containing this string (case-insensitive)
+/// * `force` - Overwrite files already in the cache
+///
+/// # Returns
+/// * Counts of written and skipped files and the invalid files
+pub fn ingest_spinorama_dataset(
+    source: &Path,
+    cache_dir: &Path,
+    measurements: &[String],
+    speaker_filter: Option<&str>,
+    force: bool,
+) -> Result<IngestReport, Box<dyn Error>> {
+    let is_metadata = |p: &Path| p.file_stem().is_some_and(|s| s == "metadata");
+    let files = read_dataset_files(source, &|p: &Path| {
+        is_metadata(p)
+            || p.file_stem()
+                .and_then(|s| s.to_str())
+                .is_some_and(|s| measurements.iter().any(|m| m == s))
+    })?;
+    let mut report = IngestReport::default();
+
+    // Metadata first: they name the speakers of nested layouts
+    let mut metadata: BTreeMap<String, Value> = BTreeMap::new();
+    let mut per_speaker: Vec<(&Path, Value)> = Vec::new();
+    for (path, content) in files.iter().filter(|(p, _)| is_metadata(p)) {
+        match serde_json::from_str::<Value>(content) {
+            Ok(v) if v.get("measurements").is_some() => per_speaker.push((path, v)),
+            Ok(Value::Object(map)) => metadata.extend(
+                map.into_iter()
+                    .filter(|(_, v)| v.get("measurements").is_some()),
+            ),
+            Ok(_) => report
+                .invalid
+                .push((path.clone(), "not a speaker metadata".to_string())),
+            Err(e) => report.invalid.push((path.clone(), e.to_string())),
+        }
+    }
+    let mut known: HashMap<String, String> = metadata
+        .keys()
+        .map(|name| (sanitize_dir_name(name), name.clone()))
+        .collect();
+    for (path, v) in per_speaker {
+        if let Some(speaker) = speaker_of(path, &known) {
+            known
+                .entry(sanitize_dir_name(&speaker))
+                .or_insert(speaker.clone());
+            metadata.entry(speaker).or_insert(v);
+        }
+    }
+
+    // Candidate files for each speaker and measurement, in path order
+    let mut candidates: BTreeMap<(String, String), Vec<(&Path, Value)>> = BTreeMap::new();
+    let mut sorted: Vec<&(PathBuf, String)> =
+        files.iter().filter(|(p, _)| !is_metadata(p)).collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    for (path, content) in sorted {
+        let Some(speaker) = speaker_of(path, &known) else {
+            continue;
+        };
+        if let Some(filter) = speaker_filter
+            && !speaker.to_lowercase().contains(&filter.to_lowercase())
+        {
+            continue;
+        }
+        let plot = normalize_plotly_json_from_str(content).and_then(|v| {
+            if v.get("data").is_some_and(|d| d.is_array()) {
+                Ok(v)
+            } else {
+                Err("no Plotly \"data\" array".into())
+            }
+        });
+        match plot {
+            Ok(plot) => {
+                let measurement = path.file_stem().unwrap().to_string_lossy().to_string();
+                candidates
+                    .entry((speaker, measurement))
+                    .or_default()
+                    .push((path, plot));
+            }
+            Err(e) => report.invalid.push((path.clone(), e.to_string())),
+        }
+    }
+
+    let mut ingested: Vec<String> = Vec::new();
+    for ((speaker, measurement), versions) in candidates {
+        let speaker_metadata = metadata.get(&speaker);
+        let default = speaker_metadata
+            .and_then(|m| m.get("default_measurement"))
+            .and_then(|d| d.as_str());
+        let (_, plot) = versions
+            .iter()
+            .find(|(p, _)| default.is_some_and(|d| p.iter().any(|c| c == d)))
+            .unwrap_or(&versions[0]);
+
+        let dir = cache_dir.join(sanitize_dir_name(&speaker));
+        std::fs::create_dir_all(&dir)?;
+        let mut outputs = vec![(dir.join(measurement_filename(&measurement)), plot)];
+        if ingested.last() != Some(&speaker) {
+            outputs.extend(speaker_metadata.map(|m| (dir.join("metadata.json"), m)));
+            ingested.push(speaker.clone());
+        }
+        for (file, value) in outputs {
+            if file.exists() && !force {
+                report.skipped += 1;
+            } else {
+                std::fs::write(&file, serde_json::to_string(value)?)?;
+                report.written += 1;
+            }
+        }
+    }
+    report.speakers = ingested.len();
+
+    update_speaker_index(cache_dir, &ingested)?;
+    Ok(report)
+}
+
+/// Rebuild the speaker index of a cache directory
+///
+/// Lists every speaker directory with its measurements and the preference
+/// score of its metadata, and writes it to `{cache_dir}/index.json`. Speaker
+/// names come from `speakers` and the previous index; other directories (e.g.
+/// filled by the `download` command) are listed under their directory name.
+///
+/// # Arguments
+/// * `cache_dir` - Cache directory, usually `data_cached`
+/// * `speakers` - Names of speakers just added to the cache
+///
+/// # Returns
+/// * The index, sorted by speaker name
+pub fn update_speaker_index(
+    cache_dir: &Path,
+    speakers: &[String],
+) -> Result<Vec<SpeakerIndexEntry>, Box<dyn Error>> {
+    let mut names: HashMap<String, String> = load_speaker_index(cache_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| (e.dir, e.speaker))
+        .collect();
+    names.extend(speakers.iter().map(|s| (sanitize_dir_name(s), s.clone())));
+
+    let mut index = Vec::new();
+    if cache_dir.is_dir() {
+        for entry in std::fs::read_dir(cache_dir)? {
+            let path = entry?.path();
+            let Some(dir) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !path.is_dir() {
+                continue;
+            }
+            let mut measurements: Vec<String> = std::fs::read_dir(&path)?
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|e| e == "json"))
+                .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(String::from))
+                .filter(|s| s != "metadata")
+                .collect();
+            if measurements.is_empty() {
+                continue;
+            }
+            measurements.sort();
+            let pref_score = std::fs::read_to_string(path.join("metadata.json"))
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .and_then(|m| metadata_pref_score(&m));
+            index.push(SpeakerIndexEntry {
+                speaker: names.get(dir).cloned().unwrap_or_else(|| dir.to_string()),
+                dir: dir.to_string(),
+                measurements,
+                pref_score,
+            });
+        }
+    }
+    index.sort_by(|a, b| a.speaker.cmp(&b.speaker));
+
+    std::fs::create_dir_all(cache_dir)?;
+    std::fs::write(
+        cache_dir.join(SPEAKER_INDEX_FILE),
+        serde_json::to_string_pretty(&index)?,
+    )?;
+    Ok(index)
+}
+
+/// Load the speaker index of a cache directory
+///
+/// # Arguments
+/// * `cache_dir` - Cache directory, usually `data_cached`
+///
+/// # Returns
+/// * The index written by [`update_speaker_index`]
+pub fn load_speaker_index(cache_dir: &Path) -> Result<Vec<SpeakerIndexEntry>, Box<dyn Error>> {
+    let content = std::fs::read_to_string(cache_dir.join(SPEAKER_INDEX_FILE))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Names of the speakers in the index of `data_cached`, empty without an index
+pub fn cached_speakers() -> Vec<String> {
+    load_speaker_index(Path::new(DATA_CACHED))
+        .map(|index| index.into_iter().map(|e| e.speaker).collect())
+        .unwrap_or_default()
+}
+
+/// Error message for a measurement that is neither cached nor reachable online
+///
+/// # Arguments
+/// * `speaker` - Requested speaker
+/// * `measurement` - Requested measurement
+/// * `cause` - Why the API could not be used
+///
+/// # Returns
+/// * The cause, with speaker suggestions from the local index when it has one
+pub fn offline_lookup_error(speaker: &str, measurement: &str, cause: &str) -> String {
+    let speakers = cached_speakers();
+    if speakers.is_empty() {
+        cause.to_string()
+    } else if speakers.iter().any(|s| s.eq_ignore_ascii_case(speaker)) {
+        format!(
+            "Measurement '{}' of '{}' is not in the local cache and the API is unreachable: {}",
+            measurement, speaker, cause
+        )
+    } else {
+        let suggestions = find_similar_speakers(speaker, &speakers, 5);
+        format!(
+            "{} (API unreachable: {})",
+            format_speaker_not_found_error(speaker, &suggestions),
+            cause
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn plot(name: &str) -> String {
+        json!({"data": [{"name": name, "x": [20.0, 20000.0], "y": [0.0, 0.0]}]}).to_string()
+    }
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autoeq-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn measurements() -> Vec<String> {
+        vec![
+            "CEA2034".to_string(),
+            "Estimated In-Room Response".to_string(),
+        ]
+    }
+
+    #[test]
+    fn ingest_directory_picks_default_version() {
+        let source = temp_dir("dataset-dir");
+        let cache = temp_dir("dataset-dir-cache");
+        let metadata = json!({
+            "KEF R3": {
+                "default_measurement": "asr",
+                "measurements": {"asr": {"pref_rating": {"pref_score": 5.5}}}
+            }
+        });
+        write(&source, "assets/metadata.json", &metadata.to_string());
+        write(
+            &source,
+            "speakers/KEF R3/ASR/asr/CEA2034.json",
+            &plot("asr"),
+        );
+        write(
+            &source,
+            "speakers/KEF R3/Vendor/vendor/CEA2034.json",
+            &plot("vendor"),
+        );
+        write(
+            &source,
+            "speakers/KEF R3/ASR/asr/Estimated In-Room Response.json",
+            "{",
+        );
+        // data_cached layout, API format, no metadata
+        let api = serde_json::to_string(&json!([plot("api")])).unwrap();
+        write(&source, "Genelec 8030C/CEA2034.json", &api);
+        write(&source, "package.json", "{}");
+
+        let report =
+            ingest_spinorama_dataset(&source, &cache, &measurements(), None, false).unwrap();
+        assert_eq!(report.speakers, 2);
+        assert_eq!(report.written, 3);
+        assert_eq!(report.invalid.len(), 1);
+
+        let kef = std::fs::read_to_string(cache.join("KEF R3/CEA2034.json")).unwrap();
+        assert!(kef.contains("\"asr\""));
+        assert!(cache.join("KEF R3/metadata.json").exists());
+        let genelec = std::fs::read_to_string(cache.join("Genelec 8030C/CEA2034.json")).unwrap();
+        assert!(normalize_plotly_json_from_str(&genelec).unwrap()["data"].is_array());
+
+        let index = load_speaker_index(&cache).unwrap();
+        let names: Vec<String> = index.iter().map(|e| e.speaker.clone()).collect();
+        assert_eq!(names, vec!["Genelec 8030C", "KEF R3"]);
+        assert_eq!(index[1].pref_score, Some(5.5));
+        assert_eq!(find_similar_speakers("KEF R3x", &names, 5), vec!["KEF R3"]);
+
+        // a second run leaves the cache alone
+        let again =
+            ingest_spinorama_dataset(&source, &cache, &measurements(), None, false).unwrap();
+        assert_eq!((again.written, again.skipped), (0, 3));
+
+        std::fs::remove_dir_all(&source).unwrap();
+        std::fs::remove_dir_all(&cache).unwrap();
+    }
+
+    #[test]
+    fn ingest_tarball_with_speaker_filter() {
+        let archive = temp_dir("dataset-tgz").with_extension("tar.gz");
+        let cache = temp_dir("dataset-tgz-cache");
+        {
+            let file = File::create(&archive).unwrap();
+            let gz = flate2::write::GzEncoder::new(file, flate2::Compression::fast());
+            let mut tar = tar::Builder::new(gz);
+            for (speaker, content) in [("Ascend Sierra 2", plot("a")), ("JBL 305P", plot("b"))] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                tar.append_data(
+                    &mut header,
+                    format!("spinorama/{}/CEA2034.json", speaker),
+                    content.as_bytes(),
+                )
+                .unwrap();
+            }
+            tar.into_inner().unwrap().finish().unwrap().flush().unwrap();
+        }
+
+        let report =
+            ingest_spinorama_dataset(&archive, &cache, &measurements(), Some("jbl"), false)
+                .unwrap();
+        assert_eq!((report.speakers, report.written), (1, 1));
+        assert!(cache.join("JBL 305P/CEA2034.json").exists());
+        assert!(!cache.join("Ascend Sierra 2").exists());
+
+        std::fs::remove_file(&archive).unwrap();
+        std::fs::remove_dir_all(&cache).unwrap();
+    }
+
+    #[test]
+    fn ingest_zip_with_api_metadata() {
+        let archive = temp_dir("dataset-zip").with_extension("zip");
+        let cache = temp_dir("dataset-zip-cache");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            let metadata = json!({
+                "default_measurement": "asr",
+                "measurements": {"asr": {"pref_rating": {"pref_score": 4.2}}}
+            });
+            for (name, content) in [
+                ("Revel F35/metadata.json", metadata.to_string()),
+                ("Revel F35/CEA2034.json", plot("on")),
+                ("Revel F35/Estimated In-Room Response.json", plot("pir")),
+            ] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(content.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let report =
+            ingest_spinorama_dataset(&archive, &cache, &measurements(), None, false).unwrap();
+        assert_eq!((report.speakers, report.written), (1, 3));
+        let index = load_speaker_index(&cache).unwrap();
+        assert_eq!(
+            index,
+            vec![SpeakerIndexEntry {
+                speaker: "Revel F35".to_string(),
+                dir: "Revel F35".to_string(),
+                measurements: measurements(),
+                pref_score: Some(4.2),
+            }]
+        );
+
+        std::fs::remove_file(&archive).unwrap();
+        std::fs::remove_dir_all(&cache).unwrap();
+    }
+
+    #[test]
+    fn ingest_rejects_unknown_source() {
+        let file = temp_dir("dataset-rar").with_extension("rar");
+        std::fs::write(&file, "").unwrap();
+        let err = ingest_spinorama_dataset(&file, &file, &measurements(), None, false)
+            .unwrap_err()
+            .to_string();
+        std::fs::remove_file(&file).unwrap();
+        assert!(err.contains("neither a directory"));
+    }
+}
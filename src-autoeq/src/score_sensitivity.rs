@@ -0,0 +1,360 @@
+// Sensitivity of the CEA2034 preference score to measurement and metric details
+
+use crate::Curve;
+use crate::cea2034 as score;
+use crate::read::{create_log_frequency_grid, interpolate_log_space};
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+/// Settings of the score sensitivity analysis
+#[derive(Debug, Clone)]
+pub struct SensitivityConfig {
+    /// Number of perturbed evaluations of the score
+    pub trials: usize,
+    /// Frequency resolutions (points per octave) drawn for each trial
+    pub points_per_octave: Vec<usize>,
+    /// Standard deviation of the noise added to every curve (dB)
+    pub noise_db: f64,
+    /// Spacing of the independent noise values (octaves); the noise is interpolated
+    /// in between, like the smooth error of a repeated measurement
+    pub noise_width_oct: f64,
+    /// Largest shift of the half octave bands used by NBD (octaves)
+    pub band_shift_oct: f64,
+    /// Largest shift of the 100 Hz and 16 kHz limits of SM (octaves)
+    pub sm_limit_oct: f64,
+    /// Random seed, None for a random run
+    pub seed: Option<u64>,
+}
+
+impl Default for SensitivityConfig {
+    fn default() -> Self {
+        Self {
+            trials: 200,
+            points_per_octave: vec![12, 24, 48],
+            noise_db: 0.3,
+            noise_width_oct: 1.0 / 3.0,
+            band_shift_oct: 0.125,
+            sm_limit_oct: 1.0 / 3.0,
+            seed: None,
+        }
+    }
+}
+
+/// Spread of a metric over the perturbed evaluations
+#[derive(Debug, Clone, Copy)]
+pub struct MetricInterval {
+    /// Value on the unperturbed curves
+    pub nominal: f64,
+    /// Mean over the trials
+    pub mean: f64,
+    /// 2.5th percentile over the trials
+    pub low: f64,
+    /// 97.5th percentile over the trials
+    pub high: f64,
+}
+
+impl MetricInterval {
+    fn new(nominal: f64, mut values: Vec<f64>) -> Self {
+        values.retain(|v| v.is_finite());
+        if values.is_empty() {
+            return Self {
+                nominal,
+                mean: f64::NAN,
+                low: f64::NAN,
+                high: f64::NAN,
+            };
+        }
+        values.sort_by(f64::total_cmp);
+        let quantile = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+        Self {
+            nominal,
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            low: quantile(0.025),
+            high: quantile(0.975),
+        }
+    }
+}
+
+/// Contribution of a half octave band to the preference score
+#[derive(Debug, Clone, Copy)]
+pub struct BandContribution {
+    /// Center frequency of the band (Hz)
+    pub center: f64,
+    /// Mean absolute deviation of the on-axis response in the band (dB)
+    pub nbd_on: f64,
+    /// Mean absolute deviation of the predicted in-room response in the band (dB)
+    pub nbd_pir: f64,
+    /// Score points lost in the band through NBD_ON and NBD_PIR
+    pub penalty: f64,
+}
+
+/// Result of the score sensitivity analysis
+#[derive(Debug, Clone)]
+pub struct ScoreSensitivity {
+    /// Narrow Band Deviation of the on-axis response
+    pub nbd_on: MetricInterval,
+    /// Narrow Band Deviation of the predicted in-room response
+    pub nbd_pir: MetricInterval,
+    /// Low Frequency Extension (log10 of the frequency)
+    pub lfx: MetricInterval,
+    /// Smoothness of the predicted in-room response
+    pub sm_pir: MetricInterval,
+    /// Preference score
+    pub pref_score: MetricInterval,
+    /// Band contributions on the unperturbed curves, largest penalty first
+    pub bands: Vec<BandContribution>,
+}
+
+/// Standard normal sample (Box-Muller)
+fn gaussian(rng: &mut dyn rand::RngCore) -> f64 {
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Split NBD_ON and NBD_PIR into the score points lost in each band
+fn band_contributions(
+    freq: &Array1<f64>,
+    on: &Array1<f64>,
+    pir: &Array1<f64>,
+) -> Vec<BandContribution> {
+    let bands = score::octave_band_intervals(2, 1290.0, freq);
+    let count = bands.len() as f64;
+    let mut out: Vec<BandContribution> = bands
+        .into_iter()
+        .map(|(center, interval)| {
+            let nbd_on = score::nbd(&[interval], on);
+            let nbd_pir = score::nbd(&[interval], pir);
+            BandContribution {
+                center,
+                nbd_on,
+                nbd_pir,
+                penalty: (score::NBD_ON_WEIGHT * nbd_on + score::NBD_PIR_WEIGHT * nbd_pir) / count,
+            }
+        })
+        .filter(|b| b.penalty.is_finite())
+        .collect();
+    out.sort_by(|a, b| b.penalty.total_cmp(&a.penalty));
+    out
+}
+
+/// Estimate how much the preference score depends on measurement and metric details
+///
+/// Each trial re-interpolates the curves on a log grid with a resolution drawn
+/// from `config.points_per_octave`, adds smooth gaussian noise to every curve, shifts
+/// the half octave bands of NBD and the frequency limits of SM, then computes
+/// the sub-metrics and the score. The nominal values are those of
+/// [`score::compute_cea2034_metrics`].
+///
+/// # Arguments
+/// * `freq` - Frequency grid of the curves
+/// * `cea2034_data` - CEA2034 curves (On Axis, Listening Window, Sound Power, Estimated In-Room Response)
+/// * `peq` - Optional PEQ response on `freq` added to every curve
+/// * `config` - Perturbation settings
+///
+/// # Returns
+/// * Confidence intervals of the sub-metrics and of the score, and the band contributions
+pub fn score_sensitivity(
+    freq: &Array1<f64>,
+    cea2034_data: &HashMap<String, Curve>,
+    peq: Option<&Array1<f64>>,
+    config: &SensitivityConfig,
+) -> Result<ScoreSensitivity, String> {
+    let peq = peq.cloned().unwrap_or_else(|| Array1::zeros(freq.len()));
+    let curve = |name: &str| -> Result<Curve, String> {
+        let c = cea2034_data
+            .get(name)
+            .ok_or_else(|| format!("Missing CEA2034 curve '{}'", name))?;
+        if c.spl.len() != freq.len() {
+            return Err(format!(
+                "CEA2034 curve '{}' is not on the frequency grid",
+                name
+            ));
+        }
+        Ok(Curve {
+            freq: freq.clone(),
+            spl: &c.spl + &peq,
+        })
+    };
+    let on = curve("On Axis")?;
+    let lw = curve("Listening Window")?;
+    let sp = curve("Sound Power")?;
+    let pir = curve("Estimated In-Room Response")?;
+
+    let nominal = score::score(
+        freq,
+        &score::octave_intervals(2, freq),
+        &on.spl,
+        &lw.spl,
+        &sp.spl,
+        &pir.spl,
+    );
+
+    let mut rng: Box<dyn rand::RngCore> = match config.seed {
+        Some(seed) => Box::new(StdRng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+    let f_min = freq[0].max(20.0);
+    let f_max = freq[freq.len() - 1].min(20000.0);
+    let octaves = (f_max / f_min).log2();
+    let noise_freq = create_log_frequency_grid(
+        ((octaves / config.noise_width_oct.max(1e-3)).ceil() as usize + 1).max(2),
+        f_min,
+        f_max,
+    );
+
+    let mut samples: [Vec<f64>; 5] = Default::default();
+    for _ in 0..config.trials {
+        let ppo = match config.points_per_octave.len() {
+            0 => 24,
+            n => config.points_per_octave[rng.random_range(0..n)],
+        };
+        let grid = create_log_frequency_grid(
+            ((ppo as f64 * octaves).round() as usize).max(2),
+            f_min,
+            f_max,
+        );
+        let mut perturb = |c: &Curve| {
+            let noise = Curve {
+                freq: noise_freq.clone(),
+                spl: noise_freq.mapv(|_| config.noise_db * gaussian(rng.as_mut())),
+            };
+            interpolate_log_space(&grid, c).spl + interpolate_log_space(&grid, &noise).spl
+        };
+        let (on, lw, sp, pir) = (perturb(&on), perturb(&lw), perturb(&sp), perturb(&pir));
+
+        let mut shift = |range: f64| 2f64.powf(range * (2.0 * rng.random::<f64>() - 1.0));
+        let intervals: Vec<(usize, usize)> =
+            score::octave_band_intervals(2, 1290.0 * shift(config.band_shift_oct), &grid)
+                .into_iter()
+                .map(|(_, interval)| interval)
+                .collect();
+        let (sm_low, sm_high) = (
+            100.0 * shift(config.sm_limit_oct),
+            16000.0 * shift(config.sm_limit_oct),
+        );
+
+        let nbd_on = score::nbd(&intervals, &on);
+        let nbd_pir = score::nbd(&intervals, &pir);
+        let lfx = score::lfx(&grid, &lw, &sp);
+        let sm_pir = score::sm_range(&grid, &pir, sm_low, sm_high);
+        for (s, v) in samples.iter_mut().zip([
+            nbd_on,
+            nbd_pir,
+            lfx,
+            sm_pir,
+            score::pref_score(nbd_on, nbd_pir, lfx, sm_pir),
+        ]) {
+            s.push(v);
+        }
+    }
+
+    let [s_nbd_on, s_nbd_pir, s_lfx, s_sm_pir, s_pref] = samples;
+    Ok(ScoreSensitivity {
+        nbd_on: MetricInterval::new(nominal.nbd_on, s_nbd_on),
+        nbd_pir: MetricInterval::new(nominal.nbd_pir, s_nbd_pir),
+        lfx: MetricInterval::new(nominal.lfx, s_lfx),
+        sm_pir: MetricInterval::new(nominal.sm_pir, s_sm_pir),
+        pref_score: MetricInterval::new(nominal.pref_score, s_pref),
+        bands: band_contributions(freq, &on.spl, &pir.spl),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spin(freq: &Array1<f64>, ripple: f64) -> HashMap<String, Curve> {
+        // flat on axis down to 50 Hz with a ripple around 2 kHz, tilted in-room response
+        let on = freq.mapv(|f: f64| {
+            85.0 + ripple * (20.0 * f.log2()).sin() * (-(f / 2000.0).log2().powi(2) * 4.0).exp()
+                - 24.0 * (50.0 / f).log2().max(0.0)
+        });
+        let pir = freq.mapv(|f: f64| 85.0 - 1.5 * (f / 1000.0).log2());
+        let curve = |spl: Array1<f64>| Curve {
+            freq: freq.clone(),
+            spl,
+        };
+        HashMap::from([
+            ("On Axis".to_string(), curve(on.clone())),
+            ("Listening Window".to_string(), curve(on.clone())),
+            ("Sound Power".to_string(), curve(on - 3.0)),
+            ("Estimated In-Room Response".to_string(), curve(pir)),
+        ])
+    }
+
+    #[test]
+    fn intervals_contain_nominal_score() {
+        let freq = create_log_frequency_grid(200, 20.0, 20000.0);
+        let config = SensitivityConfig {
+            trials: 100,
+            seed: Some(7),
+            ..SensitivityConfig::default()
+        };
+        let result = score_sensitivity(&freq, &spin(&freq, 2.0), None, &config).unwrap();
+
+        let nominal = score::score(
+            &freq,
+            &score::octave_intervals(2, &freq),
+            &spin(&freq, 2.0)["On Axis"].spl,
+            &spin(&freq, 2.0)["Listening Window"].spl,
+            &spin(&freq, 2.0)["Sound Power"].spl,
+            &spin(&freq, 2.0)["Estimated In-Room Response"].spl,
+        );
+        assert!((result.pref_score.nominal - nominal.pref_score).abs() < 1e-12);
+        for m in [
+            result.nbd_on,
+            result.nbd_pir,
+            result.lfx,
+            result.sm_pir,
+            result.pref_score,
+        ] {
+            assert!(m.low <= m.mean && m.mean <= m.high);
+            assert!(m.high - m.low < 1.0);
+        }
+        // measurement errors add deviation to an already smooth response
+        assert!(result.pref_score.mean < nominal.pref_score);
+        assert!(nominal.pref_score - result.pref_score.low < 1.0);
+    }
+
+    #[test]
+    fn ripple_band_contributes_most() {
+        let freq = create_log_frequency_grid(200, 20.0, 20000.0);
+        let config = SensitivityConfig {
+            trials: 10,
+            seed: Some(1),
+            ..SensitivityConfig::default()
+        };
+        let result = score_sensitivity(&freq, &spin(&freq, 3.0), None, &config).unwrap();
+
+        let worst = result.bands[0];
+        assert!(worst.center > 1400.0 && worst.center < 2900.0);
+        assert!(
+            result
+                .bands
+                .windows(2)
+                .all(|w| w[0].penalty >= w[1].penalty)
+        );
+        let total: f64 = result.bands.iter().map(|b| b.penalty).sum();
+        let expected = score::NBD_ON_WEIGHT * result.nbd_on.nominal
+            + score::NBD_PIR_WEIGHT * result.nbd_pir.nominal;
+        assert!((total - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn peq_is_added_to_every_curve() {
+        let freq = create_log_frequency_grid(200, 20.0, 20000.0);
+        let data = spin(&freq, 0.0);
+        let config = SensitivityConfig {
+            trials: 0,
+            ..SensitivityConfig::default()
+        };
+        let flat = score_sensitivity(&freq, &data, None, &config).unwrap();
+        let shifted =
+            score_sensitivity(&freq, &data, Some(&Array1::from_elem(200, 3.0)), &config).unwrap();
+        assert!((flat.pref_score.nominal - shifted.pref_score.nominal).abs() < 1e-9);
+        assert!(shifted.pref_score.mean.is_nan());
+    }
+}
@@ -0,0 +1,343 @@
+//! Built-in target curves and parametric target modifiers
+//!
+//! Copyright (C) 2025 Pierre Aubert pierre(at)spinorama(dot)org
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use clap::ValueEnum;
+use ndarray::Array1;
+use std::collections::HashMap;
+
+use crate::Curve;
+use crate::cea2034::compute_pir_from_lw_er_sp;
+use crate::iir::{Biquad, BiquadFilterType};
+use crate::loss::{HeadphoneType, regression_slope_per_octave_in_range};
+use crate::read;
+
+/// Q of the bass and treble shelves of the target modifiers
+const SHELF_Q: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+/// Frequency at which the tilt is 0 dB
+const TILT_PIVOT_HZ: f64 = 1000.0;
+
+/// Frequency range of the regression of the predicted in-room response
+const ROOM_SLOPE_FMIN: f64 = 100.0;
+const ROOM_SLOPE_FMAX: f64 = 10000.0;
+
+/// Target curves shipped with AutoEQ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BuiltinTarget {
+    /// Harman over-ear target (2013)
+    #[value(name = "harman-over-ear-2013")]
+    HarmanOverEar2013,
+    /// Harman over-ear target (2015)
+    #[value(name = "harman-over-ear-2015")]
+    HarmanOverEar2015,
+    /// Harman over-ear target (2018)
+    #[value(name = "harman-over-ear-2018")]
+    HarmanOverEar2018,
+    /// Harman in-ear target (2019)
+    #[value(name = "harman-in-ear-2019")]
+    HarmanInEar2019,
+    /// Diffuse-field response at the eardrum (ISO 11904-2, 1/3 octave)
+    DiffuseField,
+    /// Frontal free-field response at the eardrum (ISO 11904-2, 1/3 octave)
+    FreeField,
+}
+
+impl BuiltinTarget {
+    /// Parse a target name as accepted by `--target-builtin` (e.g. "harman-over-ear-2018")
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::from_str(name.trim(), true).ok()
+    }
+
+    /// CSV data of the target (format: frequency,spl)
+    fn csv(self) -> &'static str {
+        match self {
+            BuiltinTarget::HarmanOverEar2013 => {
+                include_str!("../../data_tests/targets/harman-over-ear-2013.csv")
+            }
+            BuiltinTarget::HarmanOverEar2015 => {
+                include_str!("../../data_tests/targets/harman-over-ear-2015.csv")
+            }
+            BuiltinTarget::HarmanOverEar2018 => {
+                include_str!("../../data_tests/targets/harman-over-ear-2018.csv")
+            }
+            BuiltinTarget::HarmanInEar2019 => {
+                include_str!("../../data_tests/targets/harman-in-ear-2019.csv")
+            }
+            BuiltinTarget::DiffuseField => {
+                include_str!("../../data_tests/targets/diffuse-field.csv")
+            }
+            BuiltinTarget::FreeField => include_str!("../../data_tests/targets/free-field.csv"),
+        }
+    }
+
+    /// Target curve on its own frequency grid
+    pub fn curve(self) -> Curve {
+        let (freq, spl) = read::parse_frequency_response(self.csv().as_bytes())
+            .expect("built-in target curves are valid CSV");
+        Curve { freq, spl }
+    }
+
+    /// Headphone type the target is designed for
+    pub fn headphone_type(self) -> HeadphoneType {
+        match self {
+            BuiltinTarget::HarmanInEar2019 => HeadphoneType::InEar,
+            _ => HeadphoneType::OverEar,
+        }
+    }
+}
+
+/// Parametric changes applied on top of a target curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetModifiers {
+    /// Tilt in dB per octave, pivoting at 1 kHz (negative values darken the target)
+    pub tilt_db_per_octave: f64,
+    /// Corner frequency of the bass shelf in Hz
+    pub bass_shelf_freq: f64,
+    /// Gain of the bass shelf in dB
+    pub bass_shelf_gain: f64,
+    /// Corner frequency of the treble shelf in Hz
+    pub treble_shelf_freq: f64,
+    /// Gain of the treble shelf in dB
+    pub treble_shelf_gain: f64,
+}
+
+impl Default for TargetModifiers {
+    fn default() -> Self {
+        Self {
+            tilt_db_per_octave: 0.0,
+            bass_shelf_freq: 105.0,
+            bass_shelf_gain: 0.0,
+            treble_shelf_freq: 2500.0,
+            treble_shelf_gain: 0.0,
+        }
+    }
+}
+
+impl TargetModifiers {
+    /// True if the modifiers leave the target unchanged
+    pub fn is_identity(&self) -> bool {
+        self.tilt_db_per_octave == 0.0
+            && self.bass_shelf_gain == 0.0
+            && self.treble_shelf_gain == 0.0
+    }
+
+    /// Response of the modifiers in dB
+    ///
+    /// # Arguments
+    /// * `freqs` - Frequencies in Hz
+    /// * `sample_rate` - Sample rate used to design the shelves
+    ///
+    /// # Returns
+    /// * Sum of the tilt and of both shelves at each frequency
+    pub fn response(&self, freqs: &Array1<f64>, sample_rate: f64) -> Array1<f64> {
+        let mut spl =
+            freqs.mapv(|f| self.tilt_db_per_octave * (f.max(1e-12) / TILT_PIVOT_HZ).log2());
+        if self.bass_shelf_gain != 0.0 {
+            let shelf = Biquad::new(
+                BiquadFilterType::Lowshelf,
+                self.bass_shelf_freq,
+                sample_rate,
+                SHELF_Q,
+                self.bass_shelf_gain,
+            );
+            spl += &shelf.np_log_result(freqs);
+        }
+        if self.treble_shelf_gain != 0.0 {
+            let shelf = Biquad::new(
+                BiquadFilterType::Highshelf,
+                self.treble_shelf_freq,
+                sample_rate,
+                SHELF_Q,
+                self.treble_shelf_gain,
+            );
+            spl += &shelf.np_log_result(freqs);
+        }
+        spl
+    }
+
+    /// Apply the modifiers to a target curve
+    ///
+    /// # Arguments
+    /// * `target` - Target curve
+    /// * `sample_rate` - Sample rate used to design the shelves
+    ///
+    /// # Returns
+    /// * Modified target curve on the same frequency grid
+    pub fn apply(&self, target: &Curve, sample_rate: f64) -> Curve {
+        if self.is_identity() {
+            return target.clone();
+        }
+        Curve {
+            freq: target.freq.clone(),
+            spl: &target.spl + &self.response(&target.freq, sample_rate),
+        }
+    }
+}
+
+/// Predicted in-room response of a speaker, from its CEA2034 curves
+///
+/// Uses LW, ER and SP when available (PIR = 0.12 LW + 0.44 ER + 0.44 SP in
+/// power), otherwise the "Estimated In-Room Response" curve as is.
+pub fn predicted_in_room_response(spin_data: &HashMap<String, Curve>) -> Option<Curve> {
+    match (
+        spin_data.get("Listening Window"),
+        spin_data.get("Early Reflections"),
+        spin_data.get("Sound Power"),
+    ) {
+        (Some(lw), Some(er), Some(sp))
+            if lw.spl.len() == er.spl.len() && lw.spl.len() == sp.spl.len() =>
+        {
+            Some(Curve {
+                freq: lw.freq.clone(),
+                spl: compute_pir_from_lw_er_sp(&lw.spl, &er.spl, &sp.spl),
+            })
+        }
+        _ => spin_data.get("Estimated In-Room Response").cloned(),
+    }
+}
+
+/// Room response target predicted from the directivity of a speaker
+///
+/// The target is the regression line of the predicted in-room response over
+/// 100 Hz to 10 kHz, held flat below 100 Hz, plus a low shelf modelling the
+/// room gain. A speaker with a wide directivity gets a flatter target than a
+/// speaker that beams.
+///
+/// # Arguments
+/// * `freqs` - Frequency grid of the target
+/// * `spin_data` - CEA2034 curves of the speaker
+/// * `room_gain_db` - Bass rise due to the room in dB
+/// * `room_gain_freq` - Corner frequency of the bass rise in Hz
+/// * `sample_rate` - Sample rate used to design the room gain shelf
+///
+/// # Returns
+/// * Normalized target curve, or None if the spin data has no usable in-room response
+pub fn room_target_from_spin(
+    freqs: &Array1<f64>,
+    spin_data: &HashMap<String, Curve>,
+    room_gain_db: f64,
+    room_gain_freq: f64,
+    sample_rate: f64,
+) -> Option<Curve> {
+    let pir = predicted_in_room_response(spin_data)?;
+    let slope = regression_slope_per_octave_in_range(
+        &pir.freq,
+        &pir.spl,
+        ROOM_SLOPE_FMIN,
+        ROOM_SLOPE_FMAX,
+    )?;
+    let line = Curve {
+        freq: freqs.clone(),
+        spl: freqs.mapv(|f| slope * (f.max(ROOM_SLOPE_FMIN) / TILT_PIVOT_HZ).log2()),
+    };
+    let room_gain = TargetModifiers {
+        bass_shelf_freq: room_gain_freq,
+        bass_shelf_gain: room_gain_db,
+        ..TargetModifiers::default()
+    };
+    Some(read::normalize_and_interpolate_response(
+        freqs,
+        &room_gain.apply(&line, sample_rate),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_targets_load() {
+        for target in BuiltinTarget::value_variants() {
+            let curve = target.curve();
+            assert!(curve.freq.len() > 20, "{:?}", target);
+            assert_eq!(curve.freq.len(), curve.spl.len());
+            assert!(curve.freq[0] <= 20.0 && *curve.freq.last().unwrap() >= 16000.0);
+        }
+        assert_eq!(
+            BuiltinTarget::from_name("Diffuse-Field"),
+            Some(BuiltinTarget::DiffuseField)
+        );
+        assert_eq!(BuiltinTarget::from_name("harman"), None);
+        assert_eq!(
+            BuiltinTarget::HarmanInEar2019.headphone_type(),
+            HeadphoneType::InEar
+        );
+    }
+
+    #[test]
+    fn modifiers_tilt_and_shelves() {
+        let freqs = Array1::from(vec![20.0, 500.0, 1000.0, 2000.0, 16000.0]);
+        let tilt = TargetModifiers {
+            tilt_db_per_octave: -1.0,
+            ..TargetModifiers::default()
+        };
+        let spl = tilt.response(&freqs, 48000.0);
+        assert!(spl[2].abs() < 1e-12);
+        assert!((spl[1] - 1.0).abs() < 1e-12);
+        assert!((spl[3] + 1.0).abs() < 1e-12);
+
+        let shelves = TargetModifiers {
+            bass_shelf_gain: 6.0,
+            treble_shelf_gain: -3.0,
+            ..TargetModifiers::default()
+        };
+        let spl = shelves.response(&freqs, 48000.0);
+        assert!((spl[0] - 6.0).abs() < 0.2, "bass {}", spl[0]);
+        assert!((spl[4] + 3.0).abs() < 0.2, "treble {}", spl[4]);
+        assert!(spl[2].abs() < 1.0);
+
+        assert!(TargetModifiers::default().is_identity());
+    }
+
+    fn spin(lw_slope: f64, sp_slope: f64) -> HashMap<String, Curve> {
+        let freq = Array1::logspace(10.0, 1.3, 4.3, 200);
+        let line = |slope: f64| Curve {
+            freq: freq.clone(),
+            spl: freq.mapv(|f| 85.0 + slope * (f / 1000.0).log2()),
+        };
+        let mut spin = HashMap::new();
+        spin.insert("Listening Window".to_string(), line(lw_slope));
+        spin.insert(
+            "Early Reflections".to_string(),
+            line(0.5 * (lw_slope + sp_slope)),
+        );
+        spin.insert("Sound Power".to_string(), line(sp_slope));
+        spin
+    }
+
+    #[test]
+    fn room_target_follows_directivity() {
+        let freqs = Array1::logspace(10.0, 1.3, 4.3, 100);
+        let slope = |curve: &Curve| {
+            regression_slope_per_octave_in_range(&curve.freq, &curve.spl, 100.0, 10000.0).unwrap()
+        };
+
+        // constant directivity widening to the bass: PIR slopes down
+        let wide = room_target_from_spin(&freqs, &spin(0.0, -0.5), 0.0, 120.0, 48000.0).unwrap();
+        let narrow = room_target_from_spin(&freqs, &spin(0.0, -1.5), 0.0, 120.0, 48000.0).unwrap();
+        assert!(slope(&wide) < 0.0);
+        assert!(slope(&narrow) < slope(&wide));
+
+        // room gain lifts the bass relative to the midrange
+        let gain = room_target_from_spin(&freqs, &spin(0.0, -1.0), 4.0, 120.0, 48000.0).unwrap();
+        let flat = room_target_from_spin(&freqs, &spin(0.0, -1.0), 0.0, 120.0, 48000.0).unwrap();
+        let lift = (gain.spl[0] - gain.spl[60]) - (flat.spl[0] - flat.spl[60]);
+        assert!((lift - 4.0).abs() < 0.3, "lift {}", lift);
+
+        assert!(room_target_from_spin(&freqs, &HashMap::new(), 0.0, 120.0, 48000.0).is_none());
+    }
+}
@@ -4,14 +4,17 @@
 //! building target curves, preparing objective data, and running optimization.
 
 use crate::{
-    Curve, cli::PeqModel, loss::DriversLossData, loss::HeadphoneLossData, loss::SpeakerLossData,
-    optim, optim::ObjectiveData, optim_de::optimize_filters_autoeq_with_callback, read,
+    Curve, cli::PeqModel, loss::DriversLossData, loss::HeadphoneLossData,
+    loss::MultiPositionLossData, loss::PerceptualLossData, loss::SUB_MAINS_ALIGNMENT_PARAMS,
+    loss::SpeakerLossData, loss::SubMainsLossData, optim, optim::ObjectiveData,
+    optim_de::optimize_filters_autoeq_with_callback, read,
 };
 use ndarray::Array1;
 use std::{collections::HashMap, error::Error};
 
-/// Load the input curve from either local CSV (when no API params) or from
-/// cached/API Plotly JSON for a given `speaker`/`version`/`measurement`.
+/// Load the input curve from either local CSV (when no API params), a directory
+/// of per-angle measurements (`--spin-dir`) or from cached/API Plotly JSON for
+/// a given `speaker`/`version`/`measurement`.
 ///
 /// Returns the main input `Curve` and optional CEA2034 spinorama curves when
 /// the measurement requires them.
@@ -56,6 +59,20 @@ pub async fn load_input_curve(
             }
             extracted_curve
         }
+    } else if let Some(dir) = &args.spin_dir {
+        // Per-angle measurements -> CEA2034 curves computed locally
+        let curves = read::load_spin_directory(dir)?;
+        let curve = curves
+            .get(&args.curve_name)
+            .ok_or_else(|| format!("Unknown curve '{}' for --spin-dir", args.curve_name))?
+            .clone();
+        spin_data = Some(curves);
+        curve
+    } else if !args.positions.is_empty() {
+        // Several listening positions -> spatial average on a common grid
+        let freqs = read::create_log_frequency_grid(200, 20.0, 20000.0);
+        let curves = load_position_curves(args, &freqs)?;
+        read::spatial_average(&curves, &args.position_weights, args.spatial_average)?
     } else {
         // No API params -> expect a CSV path
         let curve_path = args.curve.as_ref().ok_or(
@@ -67,14 +84,92 @@ pub async fn load_input_curve(
     Ok((input_curve, spin_data))
 }
 
+/// Load the curves given with `--positions`, normalized and interpolated on `freqs`
+pub fn load_position_curves(
+    args: &crate::cli::Args,
+    freqs: &Array1<f64>,
+) -> Result<Vec<Curve>, Box<dyn Error>> {
+    args.positions
+        .iter()
+        .map(|path| {
+            let curve = read::read_curve_from_csv(path)
+                .map_err(|e| format!("Failed to load position '{}': {}", path.display(), e))?;
+            Ok(read::normalize_and_interpolate_response(freqs, &curve))
+        })
+        .collect()
+}
+
+/// Build the multi-position loss data from the curves given with `--positions`.
+///
+/// Returns None when a single curve is optimized.
+pub fn setup_multi_position_data(
+    args: &crate::cli::Args,
+    target_curve: &Curve,
+) -> Result<Option<MultiPositionLossData>, Box<dyn Error>> {
+    if args.positions.is_empty() {
+        return Ok(None);
+    }
+    let curves = load_position_curves(args, &target_curve.freq)?;
+    let deviations = curves
+        .iter()
+        .map(|curve| &target_curve.spl - &curve.spl)
+        .collect();
+    Ok(Some(MultiPositionLossData::new(
+        deviations,
+        &args.position_weights,
+        args.position_loss,
+        args.position_spread_weight,
+    )))
+}
+
 /// Build a target curve (and optional smoothed version) from CLI args and the input curve.
 /// Returns (inverted_curve, smoothed_curve_opt).
+///
+/// When optimizing the estimated in-room response of a speaker and the CEA2034
+/// curves are available in `spin_data`, the target is the room response predicted
+/// from the directivity of the speaker (see [`crate::targets::room_target_from_spin`]).
 pub fn build_target_curve(
     args: &crate::cli::Args,
     freqs: &Array1<f64>,
     input_curve: &Curve,
+    spin_data: Option<&HashMap<String, Curve>>,
+) -> Curve {
+    let target_curve = build_base_target_curve(args, freqs, input_curve, spin_data);
+    args.target_modifiers()
+        .apply(&target_curve, args.sample_rate)
+}
+
+/// True if the curve to optimize is the estimated in-room response of a speaker
+fn optimizes_in_room_response(args: &crate::cli::Args) -> bool {
+    const PIR: &str = "Estimated In-Room Response";
+    args.curve_name == PIR
+        || matches!(args.measurement.as_deref(), Some(m) if m.eq_ignore_ascii_case(PIR))
+}
+
+/// Target curve before the --target-tilt / --target-bass-* / --target-treble-* modifiers
+fn build_base_target_curve(
+    args: &crate::cli::Args,
+    freqs: &Array1<f64>,
+    input_curve: &Curve,
+    spin_data: Option<&HashMap<String, Curve>>,
 ) -> Curve {
-    if let Some(ref target_path) = args.target {
+    let room_target = if optimizes_in_room_response(args) {
+        spin_data.and_then(|spin| {
+            crate::targets::room_target_from_spin(
+                freqs,
+                spin,
+                args.room_gain,
+                args.room_gain_freq,
+                args.sample_rate,
+            )
+        })
+    } else {
+        None
+    };
+
+    if let Some(builtin) = args.target_builtin {
+        read::normalize_and_interpolate_response(freqs, &builtin.curve())
+    } else if let Some(ref target_path) = args.target {
         crate::qa_println!(
             args,
             "[RUST DEBUG] Loading target curve from path: {}",
@@ -95,6 +190,8 @@ pub fn build_target_curve(
             panic!("Failed to load target curve: {}", e);
         });
         read::normalize_and_interpolate_response(freqs, &target_curve)
+    } else if let Some(room_target) = room_target {
+        room_target
     } else {
         match args.curve_name.as_str() {
             "Listening Window" => {
@@ -160,11 +257,11 @@ pub fn setup_objective_data(
     deviation_curve: &Curve,
     spin_data: &Option<HashMap<String, Curve>>,
 ) -> (ObjectiveData, bool) {
-    let use_cea = (matches!(args.measurement.as_deref(), Some(m) if m.eq_ignore_ascii_case("CEA2034"))
+    let from_api = (matches!(args.measurement.as_deref(), Some(m) if m.eq_ignore_ascii_case("CEA2034"))
         || matches!(args.measurement.as_deref(), Some(m) if m.eq_ignore_ascii_case("Estimated In-Room Response")))
         && args.speaker.is_some()
-        && args.version.is_some()
-        && spin_data.is_some();
+        && args.version.is_some();
+    let use_cea = (from_api || args.spin_dir.is_some()) && spin_data.is_some();
 
     let speaker_score_data_opt = if use_cea {
         Some(SpeakerLossData::new(spin_data.as_ref().unwrap()))
@@ -173,7 +270,10 @@ pub fn setup_objective_data(
     };
 
     let headphone_score_data_opt = if !use_cea {
-        Some(HeadphoneLossData::new(args.smooth, args.smooth_n))
+        Some(
+            HeadphoneLossData::new(args.smooth, args.smooth_n)
+                .with_headphone_type(args.effective_headphone_type()),
+        )
     } else {
         None
     };
@@ -187,9 +287,11 @@ pub fn setup_objective_data(
         spacing_weight: args.spacing_weight,
         max_db: args.max_db,
         min_db: args.min_db,
-        min_freq: args.min_freq,
+        min_freq: args.effective_min_freq(),
         max_freq: args.max_freq,
+        sub_crossover: args.sub_crossover,
         peq_model: args.effective_peq_model(),
+        biquad_design: args.biquad_design,
         loss_type: args.loss,
         speaker_score_data: speaker_score_data_opt,
         headphone_score_data: headphone_score_data_opt,
@@ -199,8 +301,19 @@ pub fn setup_objective_data(
         } else {
             None
         },
-        // Multi-driver data will be set separately
+        // Multi-driver and multi-position data will be set separately
         drivers_data: None,
+        multi_position_data: None,
+        sub_mains_data: None,
+        perceptual_data: (args.loss == crate::LossType::PerceptualFlat).then(|| {
+            PerceptualLossData::new(
+                &input_curve.freq,
+                args.perceptual_weighting,
+                args.peak_weight,
+                args.dip_weight,
+                args.dip_min_width,
+            )
+        }),
         // Penalties default to zero; configured per algorithm in optimize_filters
         penalty_w_ceiling: 0.0,
         penalty_w_spacing: 0.0,
@@ -235,12 +348,17 @@ pub fn setup_drivers_objective_data(
         min_db: args.min_db,
         min_freq: args.min_freq,
         max_freq: args.max_freq,
+        sub_crossover: args.sub_crossover,
         peq_model: args.effective_peq_model(),
+        biquad_design: args.biquad_design,
         loss_type: crate::LossType::DriversFlat,
         speaker_score_data: None,
         headphone_score_data: None,
         input_curve: None,
         drivers_data: Some(drivers_data),
+        multi_position_data: None,
+        sub_mains_data: None,
+        perceptual_data: None,
         penalty_w_ceiling: 0.0,
         penalty_w_spacing: 0.0,
         penalty_w_mingain: 0.0,
@@ -258,15 +376,18 @@ pub fn setup_drivers_objective_data(
 /// * Tuple of (lower_bounds, upper_bounds)
 ///
 /// # Parameter Vector Layout
-/// For N drivers: [gain1, gain2, ..., gainN, xover_freq1, xover_freq2, ..., xover_freq(N-1)]
+/// For N drivers: [gain1, ..., gainN, xover_freq1, ..., xover_freq(N-1), delay1, ..., delayN,
+/// polarity1, ..., polarityN]
 /// - Gains are in dB, bounded by [-max_db, max_db]
 /// - Crossover frequencies are in Hz (log10 space), bounded by driver frequency ranges
+/// - Delays are in ms, bounded by [0, driver_max_delay_ms]
+/// - Polarities are in [0, 1], inverted from 0.5
 pub fn setup_drivers_bounds(
     args: &crate::cli::Args,
     drivers_data: &DriversLossData,
 ) -> (Vec<f64>, Vec<f64>) {
     let n_drivers = drivers_data.drivers.len();
-    let n_params = n_drivers + (n_drivers - 1); // N gains + (N-1) crossovers
+    let n_params = crate::loss::DriversParams::num_params(n_drivers);
 
     let mut lower_bounds = Vec::with_capacity(n_params);
     let mut upper_bounds = Vec::with_capacity(n_params);
@@ -301,6 +422,18 @@ pub fn setup_drivers_bounds(
         upper_bounds.push(xover_max);
     }
 
+    // Bounds for delays: [0, driver_max_delay_ms]
+    for _ in 0..n_drivers {
+        lower_bounds.push(0.0);
+        upper_bounds.push(args.driver_max_delay_ms);
+    }
+
+    // Bounds for polarities
+    for _ in 0..n_drivers {
+        lower_bounds.push(0.0);
+        upper_bounds.push(1.0);
+    }
+
     (lower_bounds, upper_bounds)
 }
 
@@ -312,7 +445,7 @@ pub fn setup_drivers_bounds(
 /// * `n_drivers` - Number of drivers
 ///
 /// # Returns
-/// * Initial guess vector: [gains, crossover_freqs_log10]
+/// * Initial guess vector: [gains, crossover_freqs_log10, delays, polarities]
 pub fn drivers_initial_guess(
     lower_bounds: &[f64],
     upper_bounds: &[f64],
@@ -326,11 +459,159 @@ pub fn drivers_initial_guess(
     }
 
     // Initial crossover frequencies: use geometric mean of bounds (in log space)
-    for i in n_drivers..lower_bounds.len() {
+    for i in n_drivers..(2 * n_drivers - 1) {
         let xover_log10 = (lower_bounds[i] + upper_bounds[i]) / 2.0;
         x.push(xover_log10);
     }
 
+    // No delay and normal polarity
+    x.extend(std::iter::repeat_n(0.0, n_drivers));
+    x.extend(std::iter::repeat_n(0.25, n_drivers));
+
+    x
+}
+
+/// Range of the sub + mains crossover frequency in Hz
+const SUB_MAINS_CROSSOVER_RANGE: (f64, f64) = (40.0, 200.0);
+/// Range of the subwoofer gain relative to the mains in dB
+const SUB_MAINS_GAIN_RANGE: (f64, f64) = (-12.0, 12.0);
+
+/// Set up objective data for subwoofer + mains integration
+///
+/// # Arguments
+/// * `args` - CLI arguments
+/// * `sub_mains_data` - Mains and subwoofer measurements
+///
+/// # Returns
+/// * ObjectiveData configured for the sub-mains-flat loss
+pub fn setup_sub_mains_objective_data(
+    args: &crate::cli::Args,
+    sub_mains_data: SubMainsLossData,
+) -> ObjectiveData {
+    ObjectiveData {
+        freqs: sub_mains_data.freq_grid.clone(),
+        target: Array1::zeros(sub_mains_data.freq_grid.len()),
+        deviation: Array1::zeros(sub_mains_data.freq_grid.len()),
+        srate: args.sample_rate,
+        min_spacing_oct: 0.0,
+        spacing_weight: 0.0,
+        max_db: args.max_db,
+        min_db: args.min_db,
+        min_freq: args.min_freq,
+        max_freq: args.max_freq,
+        sub_crossover: args.sub_crossover,
+        peq_model: PeqModel::Pk,
+        biquad_design: args.biquad_design,
+        loss_type: crate::LossType::SubMainsFlat,
+        speaker_score_data: None,
+        headphone_score_data: None,
+        input_curve: None,
+        drivers_data: None,
+        multi_position_data: None,
+        sub_mains_data: Some(sub_mains_data),
+        perceptual_data: None,
+        penalty_w_ceiling: 0.0,
+        penalty_w_spacing: 0.0,
+        penalty_w_mingain: 0.0,
+        integrality: None,
+    }
+}
+
+/// Arguments describing the mains and subwoofer PEQs as plain peak PEQs
+///
+/// The mains PEQ covers `[min_freq, max_freq]`; the sub PEQ stops at twice the
+/// highest crossover frequency.
+fn sub_mains_peq_args(
+    args: &crate::cli::Args,
+    data: &SubMainsLossData,
+) -> (crate::cli::Args, crate::cli::Args) {
+    let mut mains_args = args.clone();
+    mains_args.peq_model = PeqModel::Pk;
+    mains_args.num_filters = data.num_mains_filters;
+
+    let mut sub_args = mains_args.clone();
+    sub_args.num_filters = data.num_sub_filters;
+    sub_args.max_freq = args
+        .max_freq
+        .min(2.0 * SUB_MAINS_CROSSOVER_RANGE.1)
+        .max(args.min_freq * 2.0);
+    (mains_args, sub_args)
+}
+
+/// Build optimization parameter bounds for subwoofer + mains integration
+///
+/// # Arguments
+/// * `args` - CLI arguments
+/// * `data` - Mains and subwoofer measurements
+///
+/// # Returns
+/// * Tuple of (lower_bounds, upper_bounds)
+///
+/// # Parameter Vector Layout
+/// `[log10(crossover), sub gain, sub delay, polarity, mains PEQ, sub PEQ]`
+/// - Crossover between 40 and 200 Hz, sub gain within ±12 dB
+/// - Delay within ±`--sub-max-delay-ms`, polarity in [0, 1] (inverted from 0.5)
+/// - Both PEQs use the peak filter bounds of `setup_bounds`
+pub fn setup_sub_mains_bounds(
+    args: &crate::cli::Args,
+    data: &SubMainsLossData,
+) -> (Vec<f64>, Vec<f64>) {
+    let mut lower_bounds = vec![
+        SUB_MAINS_CROSSOVER_RANGE.0.log10(),
+        SUB_MAINS_GAIN_RANGE.0,
+        -args.sub_max_delay_ms,
+        0.0,
+    ];
+    let mut upper_bounds = vec![
+        SUB_MAINS_CROSSOVER_RANGE.1.log10(),
+        SUB_MAINS_GAIN_RANGE.1,
+        args.sub_max_delay_ms,
+        1.0,
+    ];
+
+    let (mains_args, sub_args) = sub_mains_peq_args(args, data);
+    for peq_args in [&mains_args, &sub_args] {
+        if peq_args.num_filters > 0 {
+            let (lb, ub) = setup_bounds(peq_args);
+            lower_bounds.extend(lb);
+            upper_bounds.extend(ub);
+        }
+    }
+
+    (lower_bounds, upper_bounds)
+}
+
+/// Generate initial guess for subwoofer + mains integration
+///
+/// # Arguments
+/// * `args` - CLI arguments
+/// * `data` - Mains and subwoofer measurements
+/// * `lower_bounds` - Lower bounds for parameters
+/// * `upper_bounds` - Upper bounds for parameters
+///
+/// # Returns
+/// * Initial guess vector: centered crossover, no gain or delay, normal
+///   polarity, followed by the usual PEQ initial guesses
+pub fn sub_mains_initial_guess(
+    args: &crate::cli::Args,
+    data: &SubMainsLossData,
+    lower_bounds: &[f64],
+    upper_bounds: &[f64],
+) -> Vec<f64> {
+    let mut x = vec![(lower_bounds[0] + upper_bounds[0]) / 2.0, 0.0, 0.0, 0.25];
+
+    let (mains_args, sub_args) = sub_mains_peq_args(args, data);
+    let split = SUB_MAINS_ALIGNMENT_PARAMS + 3 * data.num_mains_filters;
+    x.extend(initial_guess(
+        &mains_args,
+        &lower_bounds[SUB_MAINS_ALIGNMENT_PARAMS..split],
+        &upper_bounds[SUB_MAINS_ALIGNMENT_PARAMS..split],
+    ));
+    x.extend(initial_guess(
+        &sub_args,
+        &lower_bounds[split..],
+        &upper_bounds[split..],
+    ));
     x
 }
 
@@ -347,15 +628,16 @@ pub fn setup_bounds(args: &crate::cli::Args) -> (Vec<f64>, Vec<f64>) {
     let spacing = 1.0; // Overlap factor - allows adjacent filters to overlap
     let gain_lower = -6.0 * args.max_db;
     let q_lower = args.min_q.max(0.1);
-    let range = (args.max_freq.log10() - args.min_freq.log10()) / (args.num_filters as f64);
+    let min_freq = args.effective_min_freq();
+    let range = (args.max_freq.log10() - min_freq.log10()) / (args.num_filters as f64);
 
     for i in 0..args.num_filters {
         // Center frequency for this filter in log space
-        let f_center = args.min_freq.log10() + (i as f64) * range;
+        let f_center = min_freq.log10() + (i as f64) * range;
 
         // Calculate bounds with overlap
         // Each filter can range from (center - spacing*range) to (center + spacing*range)
-        let f_low = (f_center - spacing * range).max(args.min_freq.log10());
+        let f_low = (f_center - spacing * range).max(min_freq.log10());
         let f_high = (f_center + spacing * range).min(args.max_freq.log10());
 
         // Ensure progressive increase: each filter's lower bound should be >= previous filter's lower bound
@@ -389,7 +671,11 @@ pub fn setup_bounds(args: &crate::cli::Args) -> (Vec<f64>, Vec<f64>) {
             | PeqModel::HpPk
             | PeqModel::HpPkLp
             | PeqModel::LsPk
-            | PeqModel::LsPkHs => {
+            | PeqModel::LsPkHs
+            | PeqModel::Hp1Pk
+            | PeqModel::Hp1PkLp1
+            | PeqModel::Ls1PkHs1
+            | PeqModel::LtPk => {
                 // Fixed filter types: [freq, Q, gain]
                 lower_bounds.extend_from_slice(&[f_low_adjusted, q_lower, gain_lower]);
                 upper_bounds.extend_from_slice(&[f_high_adjusted, args.max_q, args.max_db]);
@@ -418,8 +704,8 @@ pub fn setup_bounds(args: &crate::cli::Args) -> (Vec<f64>, Vec<f64>) {
     match model {
         PeqModel::HpPk | PeqModel::HpPkLp => {
             // First filter is highpass - fixed 3-param layout
-            lower_bounds[0] = 20.0_f64.max(args.min_freq).log10();
-            upper_bounds[0] = 120.0_f64.min(args.min_freq + 20.0).log10();
+            lower_bounds[0] = 20.0_f64.max(min_freq).log10();
+            upper_bounds[0] = 120.0_f64.min(min_freq + 20.0).log10();
             lower_bounds[1] = 1.0;
             upper_bounds[1] = 1.5; // could be tuned as a function of max_db
             lower_bounds[2] = 0.0;
@@ -427,13 +713,40 @@ pub fn setup_bounds(args: &crate::cli::Args) -> (Vec<f64>, Vec<f64>) {
         }
         PeqModel::LsPk | PeqModel::LsPkHs => {
             // First filter is low shelves - fixed 3-param layout
-            lower_bounds[0] = 20.0_f64.max(args.min_freq).log10();
-            upper_bounds[0] = 120.0_f64.min(args.min_freq + 20.0).log10();
+            lower_bounds[0] = 20.0_f64.max(min_freq).log10();
+            upper_bounds[0] = 120.0_f64.min(min_freq + 20.0).log10();
             lower_bounds[1] = args.min_q;
             upper_bounds[1] = args.max_q;
             lower_bounds[2] = -args.max_db;
             upper_bounds[2] = args.max_db;
         }
+        PeqModel::Hp1Pk | PeqModel::Hp1PkLp1 => {
+            // First filter is a first-order highpass: Q is ignored, no gain
+            lower_bounds[0] = 20.0_f64.max(min_freq).log10();
+            upper_bounds[0] = 120.0_f64.min(min_freq + 20.0).log10();
+            lower_bounds[1] = 1.0;
+            upper_bounds[1] = 1.0;
+            lower_bounds[2] = 0.0;
+            upper_bounds[2] = 0.0;
+        }
+        PeqModel::Ls1PkHs1 => {
+            // First filter is a first-order low shelf: Q is ignored
+            lower_bounds[0] = 20.0_f64.max(min_freq).log10();
+            upper_bounds[0] = 120.0_f64.min(min_freq + 20.0).log10();
+            lower_bounds[1] = 1.0;
+            upper_bounds[1] = 1.0;
+            lower_bounds[2] = -args.max_db;
+            upper_bounds[2] = args.max_db;
+        }
+        PeqModel::LtPk => {
+            // First filter is a Linkwitz transform: [f0, Q0, bass boost]
+            lower_bounds[0] = 20.0_f64.max(min_freq).log10();
+            upper_bounds[0] = 150.0_f64.max(min_freq + 20.0).log10();
+            lower_bounds[1] = 0.5;
+            upper_bounds[1] = 2.0;
+            lower_bounds[2] = 0.0;
+            upper_bounds[2] = args.max_db;
+        }
         _ => {}
     }
 
@@ -451,6 +764,28 @@ pub fn setup_bounds(args: &crate::cli::Args) -> (Vec<f64>, Vec<f64>) {
             }
         }
 
+        if matches!(model, PeqModel::Hp1PkLp1) {
+            // Last filter is a first-order lowpass - fixed 3-param layout
+            let last_idx = (args.num_filters - 1) * ppf;
+            lower_bounds[last_idx] = (args.max_freq - 2000.0).max(5000.0).log10();
+            upper_bounds[last_idx] = args.max_freq.log10();
+            lower_bounds[last_idx + 1] = 1.0;
+            upper_bounds[last_idx + 1] = 1.0;
+            lower_bounds[last_idx + 2] = 0.0;
+            upper_bounds[last_idx + 2] = 0.0;
+        }
+
+        if matches!(model, PeqModel::Ls1PkHs1) {
+            // Last filter is a first-order high shelf - fixed 3-param layout
+            let last_idx = (args.num_filters - 1) * ppf;
+            lower_bounds[last_idx] = (args.max_freq - 2000.0).max(5000.0).log10();
+            upper_bounds[last_idx] = args.max_freq.log10();
+            lower_bounds[last_idx + 1] = 1.0;
+            upper_bounds[last_idx + 1] = 1.0;
+            lower_bounds[last_idx + 2] = -args.max_db;
+            upper_bounds[last_idx + 2] = args.max_db;
+        }
+
         if matches!(model, PeqModel::LsPkHs) {
             // Last filter is lowpass - fixed 3-param layout
             let last_idx = (args.num_filters - 1) * ppf;
@@ -495,6 +830,16 @@ pub fn setup_bounds(args: &crate::cli::Args) -> (Vec<f64>, Vec<f64>) {
                 PeqModel::LsPkHs if i == 0 => "LS",
                 PeqModel::LsPkHs if i == args.num_filters - 1 => "HS",
                 PeqModel::LsPkHs => "PK",
+                PeqModel::Hp1Pk if i == 0 => "HP1",
+                PeqModel::Hp1Pk => "PK",
+                PeqModel::Hp1PkLp1 if i == 0 => "HP1",
+                PeqModel::Hp1PkLp1 if i == args.num_filters - 1 => "LP1",
+                PeqModel::Hp1PkLp1 => "PK",
+                PeqModel::Ls1PkHs1 if i == 0 => "LS1",
+                PeqModel::Ls1PkHs1 if i == args.num_filters - 1 => "HS1",
+                PeqModel::Ls1PkHs1 => "PK",
+                PeqModel::LtPk if i == 0 => "LT",
+                PeqModel::LtPk => "PK",
                 PeqModel::FreePkFree if i == 0 || i == args.num_filters - 1 => "??",
                 PeqModel::FreePkFree => "PK",
                 PeqModel::Free => "??",
@@ -536,7 +881,11 @@ pub fn initial_guess(
             | PeqModel::HpPk
             | PeqModel::HpPkLp
             | PeqModel::LsPk
-            | PeqModel::LsPkHs => {
+            | PeqModel::LsPkHs
+            | PeqModel::Hp1Pk
+            | PeqModel::Hp1PkLp1
+            | PeqModel::Ls1PkHs1
+            | PeqModel::LtPk => {
                 // Fixed filter types: [freq, Q, gain]
                 let freq = lower_bounds[offset].min(args.max_freq.log10());
                 let q = (upper_bounds[offset + 1] * lower_bounds[offset + 1]).sqrt();
@@ -641,14 +990,14 @@ mod tests {
         // No smoothing
         args.smooth = false;
         let freqs = Array1::from(vec![100.0, 1000.0, 10000.0]);
-        let _target_curve = super::build_target_curve(&args, &freqs, &curve);
+        let _target_curve = super::build_target_curve(&args, &freqs, &curve, None);
         let smoothed_none: Option<Curve> = None;
         assert!(smoothed_none.is_none());
 
         // With smoothing
         args.smooth = true;
         let freqs = Array1::from(vec![100.0, 1000.0, 10000.0]);
-        let target_curve = super::build_target_curve(&args, &freqs, &curve);
+        let target_curve = super::build_target_curve(&args, &freqs, &curve, None);
         let inv_smooth = target_curve.clone();
         let smoothed_some = Some(target_curve);
         assert!(smoothed_some.is_some());
@@ -656,6 +1005,61 @@ mod tests {
         assert_eq!(s.spl.len(), inv_smooth.spl.len());
     }
 
+    #[test]
+    fn build_target_curve_builtin_with_modifiers() {
+        let mut args =
+            Args::parse_from(["autoeq-test", "--target-builtin", "harman-over-ear-2018"]);
+        let curve = zero_curve(vec![100.0, 1000.0, 10000.0]);
+        let freqs = Array1::from(vec![20.0, 1000.0, 8000.0]);
+        let base = super::build_target_curve(&args, &freqs, &curve, None);
+        assert_eq!(base.spl.len(), 3);
+
+        args.target_tilt = -1.0;
+        let tilted = super::build_target_curve(&args, &freqs, &curve, None);
+        let diff = &tilted.spl - &base.spl;
+        assert!((diff[1]).abs() < 1e-9);
+        assert!((diff[2] + 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn build_target_curve_in_room_uses_spin_data() {
+        let mut args = Args::parse_from(["autoeq-test"]);
+        args.measurement = Some("Estimated In-Room Response".to_string());
+        let freq = Array1::logspace(10.0, 1.3, 4.3, 100);
+        let curve = Curve {
+            freq: freq.clone(),
+            spl: Array1::zeros(freq.len()),
+        };
+        let mut spin: HashMap<String, Curve> = HashMap::new();
+        for (name, slope) in [
+            ("Listening Window", 0.0),
+            ("Early Reflections", -0.5),
+            ("Sound Power", -1.0),
+        ] {
+            spin.insert(
+                name.to_string(),
+                Curve {
+                    freq: freq.clone(),
+                    spl: freq.mapv(|f| slope * (f / 1000.0).log2()),
+                },
+            );
+        }
+
+        let target = super::build_target_curve(&args, &freq, &curve, Some(&spin));
+        let slope = crate::loss::curve_slope_per_octave_in_range(&target, 100.0, 10000.0).unwrap();
+        assert!(slope < -0.2 && slope > -1.0, "slope {}", slope);
+
+        // without spin data the input curve heuristic is kept
+        let fallback = super::build_target_curve(&args, &freq, &curve, None);
+        assert!(
+            fallback
+                .spl
+                .iter()
+                .zip(target.spl.iter())
+                .any(|(a, b)| (a - b).abs() > 1e-3)
+        );
+    }
+
     #[test]
     fn setup_objective_data_sets_use_cea_when_expected() {
         let mut args = Args::parse_from(["autoeq-test"]);
@@ -705,4 +1109,35 @@ mod tests {
         assert!(!use_cea3);
         assert!(obj3.speaker_score_data.is_none());
     }
+
+    #[test]
+    fn sub_mains_skips_peq_penalties() {
+        use crate::loss::{CrossoverType, DriverMeasurement};
+
+        let freq = Array1::logspace(10.0, 1.0, 20000.0_f64.log10(), 300);
+        let flat = DriverMeasurement::new(
+            freq.clone(),
+            Array1::zeros(freq.len()),
+            Some(Array1::zeros(freq.len())),
+        );
+        let data = SubMainsLossData::new(&flat, &flat, CrossoverType::LinkwitzRiley4.into(), 1, 1)
+            .unwrap();
+        let args = Args::parse_from(["autoeq-test"]);
+        let mut objective_data = setup_sub_mains_objective_data(&args, data);
+        objective_data.max_db = 3.0;
+        objective_data.min_db = 1.0;
+        objective_data.penalty_w_ceiling = 1e4;
+        objective_data.penalty_w_spacing = 1e3;
+        objective_data.penalty_w_mingain = 1e3;
+
+        // read as PEQ triplets, the crossover/gain/delay head is an 80 Hz filter
+        // with a 3.9 dB gain, above --max-db, and the zero gain peaks are below --min-db
+        let mut x = vec![80.0_f64.log10(), 0.0, 3.9, 0.0];
+        for _ in 0..2 {
+            x.extend_from_slice(&[1000.0_f64.log10(), 1.0, 0.0]);
+        }
+        let base = crate::optim::compute_base_fitness(&x, &objective_data);
+        let penalized = crate::optim::compute_fitness_penalties(&x, None, &mut objective_data);
+        assert_eq!(base, penalized);
+    }
 }
@@ -16,7 +16,7 @@
 //! along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::cli::PeqModel;
-use crate::iir::{Biquad, Peq};
+use crate::iir::{Biquad, BiquadDesign, Peq};
 use crate::param_utils::{self, FilterParams};
 use ndarray::Array1;
 
@@ -30,6 +30,20 @@ use ndarray::Array1;
 /// # Returns
 /// A Peq structure containing the filters
 pub fn x2peq(x: &[f64], srate: f64, peq_model: PeqModel) -> Peq {
+    x2peq_with_design(x, srate, peq_model, BiquadDesign::Rbj)
+}
+
+/// Convert parameter vector to Peq structure with a given coefficient design
+///
+/// # Arguments
+/// * `x` - Parameter vector with filter parameters (layout depends on PeqModel)
+/// * `srate` - Sample rate in Hz
+/// * `peq_model` - PEQ model that defines the filter structure
+/// * `design` - Coefficient design of every filter (see `--biquad-design`)
+///
+/// # Returns
+/// A Peq structure containing the filters
+pub fn x2peq_with_design(x: &[f64], srate: f64, peq_model: PeqModel, design: BiquadDesign) -> Peq {
     let num_filters = param_utils::num_filters(x, peq_model);
     let mut peq = Vec::with_capacity(num_filters);
 
@@ -42,7 +56,7 @@ pub fn x2peq(x: &[f64], srate: f64, peq_model: PeqModel) -> Peq {
         let ftype =
             param_utils::determine_filter_type(i, num_filters, peq_model, params.filter_type);
 
-        let filter = Biquad::new(ftype, freq, srate, q, gain);
+        let filter = Biquad::new_with_design(ftype, freq, srate, q, gain, design);
         peq.push((1.0, filter));
     }
 
@@ -79,7 +93,11 @@ pub fn peq2x(peq: &Peq, peq_model: PeqModel) -> Vec<f64> {
             | PeqModel::HpPk
             | PeqModel::HpPkLp
             | PeqModel::LsPk
-            | PeqModel::LsPkHs => {
+            | PeqModel::LsPkHs
+            | PeqModel::Hp1Pk
+            | PeqModel::Hp1PkLp1
+            | PeqModel::Ls1PkHs1
+            | PeqModel::LtPk => {
                 x.push(params.freq);
                 x.push(params.q);
                 x.push(params.gain);
@@ -110,7 +128,28 @@ pub fn peq2x(peq: &Peq, peq_model: PeqModel) -> Vec<f64> {
 /// # Returns
 /// Frequency response in dB SPL at the specified frequency points
 pub fn x2spl(freqs: &Array1<f64>, x: &[f64], srate: f64, peq_model: PeqModel) -> Array1<f64> {
-    let peq = x2peq(x, srate, peq_model);
+    x2spl_with_design(freqs, x, srate, peq_model, BiquadDesign::Rbj)
+}
+
+/// Convert parameter vector to parametric EQ frequency response with a given coefficient design
+///
+/// # Arguments
+/// * `freqs` - Frequency points for evaluation (Hz)
+/// * `x` - Parameter vector with filter parameters (layout depends on PeqModel)
+/// * `srate` - Sample rate in Hz
+/// * `peq_model` - PEQ model that defines the filter structure
+/// * `design` - Coefficient design of every filter (see `--biquad-design`)
+///
+/// # Returns
+/// Frequency response in dB SPL at the specified frequency points
+pub fn x2spl_with_design(
+    freqs: &Array1<f64>,
+    x: &[f64],
+    srate: f64,
+    peq_model: PeqModel,
+    design: BiquadDesign,
+) -> Array1<f64> {
+    let peq = x2peq_with_design(x, srate, peq_model, design);
     crate::iir::compute_peq_response(freqs, &peq, srate)
 }
 
@@ -195,6 +195,16 @@ fn test_q_bounds_consistency() {
                     upper[q_upper_idx], 1.5,
                     "HP/LP filter Q upper bound should be 1.5"
                 );
+            } else if (model == PeqModel::Hp1Pk && i == 0)
+                || (matches!(model, PeqModel::Hp1PkLp1 | PeqModel::Ls1PkHs1) && (i == 0 || i == 2))
+            {
+                // First-order filters ignore Q, which is pinned
+                assert_eq!(lower[q_lower_idx], 1.0);
+                assert_eq!(upper[q_upper_idx], 1.0);
+            } else if model == PeqModel::LtPk && i == 0 {
+                // Linkwitz transform Q0 covers usual sealed boxes
+                assert_eq!(lower[q_lower_idx], 0.5);
+                assert_eq!(upper[q_upper_idx], 2.0);
             } else {
                 // Normal peak filters
                 assert!(
@@ -216,8 +226,10 @@ fn test_q_bounds_consistency() {
 fn test_gain_bounds_for_special_filters() {
     // Test that highpass and lowpass filters have zero gain
     let test_cases = vec![
-        (PeqModel::HpPk, 3, vec![0]),      // First filter is HP
-        (PeqModel::HpPkLp, 3, vec![0, 2]), // First is HP, last is LP
+        (PeqModel::HpPk, 3, vec![0]),        // First filter is HP
+        (PeqModel::HpPkLp, 3, vec![0, 2]),   // First is HP, last is LP
+        (PeqModel::Hp1Pk, 3, vec![0]),       // First is HP1
+        (PeqModel::Hp1PkLp1, 3, vec![0, 2]), // First is HP1, last is LP1
     ];
 
     for (model, num_filters, zero_gain_indices) in test_cases {
@@ -293,3 +305,15 @@ fn test_uses_highpass_first() {
     args.peq_model = PeqModel::Free;
     assert!(!args.uses_highpass_first());
 }
+
+#[test]
+fn test_linkwitz_transform_model_bounds() {
+    // First filter is a Linkwitz transform: low f0, boost only
+    let args = create_test_args(PeqModel::LtPk, 3);
+    let (lower, upper) = setup_bounds(&args);
+
+    assert!(10f64.powf(upper[0]) <= 150.0 + 1e-9);
+    assert_eq!(lower[2], 0.0, "LT boost should not be negative");
+    assert_eq!(upper[2], args.max_db);
+    assert!(lower[5] < 0.0, "Second filter is a peak filter");
+}
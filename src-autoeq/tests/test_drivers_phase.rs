@@ -0,0 +1,109 @@
+use autoeq::loss::{
+    CrossoverType, DriverMeasurement, DriversLossData, DriversParams, drivers_flat_loss,
+};
+use autoeq::read::load_driver_measurement;
+use num_complex::Complex64;
+use std::path::PathBuf;
+
+const SRATE: f64 = 48000.0;
+
+fn load_wavecor(example: &str, names: &[&str]) -> DriversLossData {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../data_tests/drivers")
+        .join(example);
+    let drivers = names
+        .iter()
+        .map(|name| {
+            let (freq, spl, phase) = load_driver_measurement(&dir.join(name)).unwrap();
+            assert!(phase.is_some(), "{} has no phase", name);
+            DriverMeasurement::new(freq, spl, phase)
+        })
+        .collect();
+    DriversLossData::new(drivers, CrossoverType::LinkwitzRiley4)
+}
+
+fn loss(data: &DriversLossData, params: &DriversParams) -> f64 {
+    drivers_flat_loss(data, params, SRATE, 100.0, 10000.0)
+}
+
+/// Best loss over delays of driver `idx` in [0, 1] ms and both polarities
+fn best_alignment(data: &DriversLossData, params: &DriversParams, idx: usize) -> (f64, f64, bool) {
+    let mut best = (f64::INFINITY, 0.0, false);
+    for inverted in [false, true] {
+        for step in 0..=100 {
+            let mut p = params.clone();
+            p.delays_ms[idx] = step as f64 * 0.01;
+            p.inverted[idx] = inverted;
+            let l = loss(data, &p);
+            if l < best.0 {
+                best = (l, p.delays_ms[idx], inverted);
+            }
+        }
+    }
+    best
+}
+
+#[test]
+fn test_two_way_wavecor_phase_matters() {
+    let data = load_wavecor("ex-2-wavecor", &["WF120BD04.txt", "TW022WA06.txt"]);
+    let params = DriversParams::new(vec![0.0, 0.0], vec![2500.0]);
+    let normal = loss(&data, &params);
+
+    let mut inverted = params.clone();
+    inverted.inverted[1] = true;
+    let inverted = loss(&data, &inverted);
+    // summing magnitudes would give the same loss for both polarities
+    assert!(
+        (normal - inverted).abs() > 0.1,
+        "normal {} inverted {}",
+        normal,
+        inverted
+    );
+
+    let (best, delay, _) = best_alignment(&data, &params, 1);
+    assert!(best < normal.min(inverted), "best {} at {} ms", best, delay);
+}
+
+#[test]
+fn test_three_way_wavecor_alignment() {
+    let data = load_wavecor(
+        "ex-3-wavecor",
+        &["WF120BD03.txt", "SW070WA01.txt", "TW030WA04.txt"],
+    );
+    let params = DriversParams::new(vec![0.0, 0.0, 0.0], vec![500.0, 3000.0]);
+    let start = loss(&data, &params);
+    let (best, delay, inverted) = best_alignment(&data, &params, 2);
+    assert!(best < start - 1e-3, "best {} start {}", best, start);
+
+    // an early, inverted tweeter must be found back as a longer delay and a flip
+    let shift_ms = 0.4;
+    let mut shifted = data.clone();
+    shifted.responses[2] = &data.responses[2]
+        * &data.freq_grid.mapv(|f| {
+            Complex64::from_polar(-1.0, 2.0 * std::f64::consts::PI * f * shift_ms / 1000.0)
+        });
+    let shifted_start = loss(&shifted, &params);
+    let (shifted_best, shifted_delay, shifted_inverted) = best_alignment(&shifted, &params, 2);
+    assert!(
+        shifted_best < shifted_start - 1e-3,
+        "best {} start {}",
+        shifted_best,
+        shifted_start
+    );
+    assert!(
+        (shifted_delay - (delay + shift_ms)).abs() < 1e-6,
+        "delay {} expected {}",
+        shifted_delay,
+        delay + shift_ms
+    );
+    assert_eq!(shifted_inverted, !inverted);
+    assert!((shifted_best - best).abs() < 1e-6);
+
+    // x vector round trip
+    let mut x = vec![0.0, 0.0, 0.0, 500.0_f64.log10(), 3000.0_f64.log10()];
+    x.extend([0.0, 0.0, delay]);
+    x.extend([0.0, 0.0, if inverted { 1.0 } else { 0.0 }]);
+    let decoded = DriversParams::from_x(3, &x);
+    assert!((decoded.crossover_freqs[1] - 3000.0).abs() < 1e-6);
+    assert!((loss(&data, &decoded) - best).abs() < 1e-9);
+}
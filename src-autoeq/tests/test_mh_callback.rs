@@ -28,11 +28,16 @@ fn create_test_objective_data() -> autoeq::optim::ObjectiveData {
         min_db: 1.0,
         min_freq: 60.0,
         max_freq: 16000.0,
+        sub_crossover: 80.0,
         peq_model: PeqModel::Pk,
+        biquad_design: autoeq::iir::BiquadDesign::Rbj,
         loss_type: LossType::SpeakerFlat,
         speaker_score_data: None,
         headphone_score_data: None,
         drivers_data: None,
+        multi_position_data: None,
+        sub_mains_data: None,
+        perceptual_data: None,
         penalty_w_ceiling: 0.0,
         penalty_w_spacing: 0.0,
         penalty_w_mingain: 0.0,
@@ -248,8 +248,22 @@ pub fn cea2034(spl: &Array2<f64>, idx: &[Vec<usize>], weights: &Array1<f64>) ->
 /// # Panics
 /// * If count is less than 2
 pub fn octave(count: usize) -> Vec<(f64, f64, f64)> {
+    octave_with_reference(count, 1290.0)
+}
+
+/// Generate octave band frequencies around a given reference center frequency
+///
+/// # Arguments
+/// * `count` - Number of bands per octave
+/// * `reference` - Center frequency of the reference band (1290 Hz in [`octave`])
+///
+/// # Returns
+/// * Vector of tuples representing (low, center, high) frequencies for each band
+///
+/// # Panics
+/// * If count is less than 2
+pub fn octave_with_reference(count: usize, reference: f64) -> Vec<(f64, f64, f64)> {
     assert!(count >= 2, "count (N) must be >= 2");
-    let reference = 1290.0_f64;
     let p = 2.0_f64.powf(1.0 / count as f64);
     let p_band = 2.0_f64.powf(1.0 / (2.0 * count as f64));
     let o_iter: i32 = (count as i32 * 10 + 1) / 2;
@@ -279,7 +293,27 @@ pub fn octave(count: usize) -> Vec<(f64, f64, f64)> {
 /// # Returns
 /// * Vector of tuples representing (start_index, end_index) for each band
 pub fn octave_intervals(count: usize, freq: &Array1<f64>) -> Vec<(usize, usize)> {
-    let bands = octave(count);
+    octave_band_intervals(count, 1290.0, freq)
+        .into_iter()
+        .map(|(_, interval)| interval)
+        .collect()
+}
+
+/// Compute octave band intervals around a given reference center frequency
+///
+/// # Arguments
+/// * `count` - Number of bands per octave
+/// * `reference` - Center frequency of the reference band (1290 Hz in [`octave_intervals`])
+/// * `freq` - Array of frequencies
+///
+/// # Returns
+/// * Vector of (center_frequency, (start_index, end_index)) for each band
+pub fn octave_band_intervals(
+    count: usize,
+    reference: f64,
+    freq: &Array1<f64>,
+) -> Vec<(f64, (usize, usize))> {
+    let bands = octave_with_reference(count, reference);
 
     // Python logic: band_min_freq = max(100, min_freq)
     let min_freq = freq[0];
@@ -294,7 +328,7 @@ pub fn octave_intervals(count: usize, freq: &Array1<f64>) -> Vec<(usize, usize)>
         // Python uses inclusive bounds on both ends
         let imin = freq.iter().position(|&f| f >= low).unwrap_or(freq.len());
         let imax = freq.iter().position(|&f| f > high).unwrap_or(freq.len());
-        out.push((imin, imax));
+        out.push((center, (imin, imax)));
     }
     out
 }
@@ -380,11 +414,22 @@ pub fn lfx(freq: &Array1<f64>, lw: &Array1<f64>, sp: &Array1<f64>) -> f64 {
 /// # Returns
 /// * SM value as f64 (R-squared value)
 pub fn sm(freq: &Array1<f64>, spl: &Array1<f64>) -> f64 {
-    let f_min = freq.iter().position(|&f| f > 100.0).unwrap_or(freq.len());
-    let f_max = freq
-        .iter()
-        .position(|&f| f >= 16000.0)
-        .unwrap_or(freq.len());
+    sm_range(freq, spl, 100.0, 16000.0)
+}
+
+/// Compute the Smoothness Metric (SM) between two frequencies
+///
+/// # Arguments
+/// * `freq` - Frequency array
+/// * `spl` - SPL measurements
+/// * `low` - Frequencies above `low` are included (100 Hz in [`sm`])
+/// * `high` - Frequencies below `high` are included (16 kHz in [`sm`])
+///
+/// # Returns
+/// * SM value as f64 (R-squared value)
+pub fn sm_range(freq: &Array1<f64>, spl: &Array1<f64>, low: f64, high: f64) -> f64 {
+    let f_min = freq.iter().position(|&f| f > low).unwrap_or(freq.len());
+    let f_max = freq.iter().position(|&f| f >= high).unwrap_or(freq.len());
     if f_min >= f_max {
         return f64::NAN;
     }
@@ -393,6 +438,31 @@ pub fn sm(freq: &Array1<f64>, spl: &Array1<f64>) -> f64 {
     r_squared(&x, &y)
 }
 
+/// Low Frequency Extension (Hz) of a speaker paired with an ideal subwoofer
+///
+/// spinorama.org reports a "with sub" preference score with LFX pinned at this value.
+pub const LFX_WITH_SUB_HZ: f64 = 14.5;
+
+/// Score points lost per unit of on-axis Narrow Band Deviation in [`pref_score`]
+pub const NBD_ON_WEIGHT: f64 = 2.49;
+
+/// Score points lost per unit of in-room Narrow Band Deviation in [`pref_score`]
+pub const NBD_PIR_WEIGHT: f64 = 2.99;
+
+/// Compute the preference score from its sub-metrics
+///
+/// # Arguments
+/// * `nbd_on` - Narrow Band Deviation of the on-axis response
+/// * `nbd_pir` - Narrow Band Deviation of the predicted in-room response
+/// * `lfx` - Low Frequency Extension (log10 of the frequency)
+/// * `sm_pir` - Smoothness Metric of the predicted in-room response
+///
+/// # Returns
+/// * Preference score
+pub fn pref_score(nbd_on: f64, nbd_pir: f64, lfx: f64, sm_pir: f64) -> f64 {
+    12.69 - NBD_ON_WEIGHT * nbd_on - NBD_PIR_WEIGHT * nbd_pir - 4.31 * lfx + 2.32 * sm_pir
+}
+
 /// Metrics computed for the CEA2034 preference score
 #[derive(Debug, Clone)]
 pub struct ScoreMetrics {
@@ -406,6 +476,21 @@ pub struct ScoreMetrics {
     pub sm_pir: f64,
     /// Overall preference score
     pub pref_score: f64,
+    /// Preference score with an ideal subwoofer (LFX at [`LFX_WITH_SUB_HZ`])
+    pub pref_score_wsub: f64,
+}
+
+impl ScoreMetrics {
+    /// Preference score with the Low Frequency Extension replaced by `lfx_hz`
+    ///
+    /// # Arguments
+    /// * `lfx_hz` - Low Frequency Extension in Hz, e.g. the -6 dB point of a subwoofer
+    ///
+    /// # Returns
+    /// * Preference score with the other sub-metrics unchanged
+    pub fn pref_score_with_lfx(&self, lfx_hz: f64) -> f64 {
+        pref_score(self.nbd_on, self.nbd_pir, lfx_hz.log10(), self.sm_pir)
+    }
 }
 
 /// Compute all CEA2034 metrics and preference score
@@ -432,16 +517,50 @@ pub fn score(
     let nbd_pir = nbd(intervals, pir);
     let sm_pir = sm(freq, pir);
     let lfx_val = lfx(freq, lw, sp);
-    let pref = 12.69 - 2.49 * nbd_on - 2.99 * nbd_pir - 4.31 * lfx_val + 2.32 * sm_pir;
     ScoreMetrics {
         nbd_on,
         nbd_pir,
         lfx: lfx_val,
         sm_pir,
-        pref_score: pref,
+        pref_score: pref_score(nbd_on, nbd_pir, lfx_val, sm_pir),
+        pref_score_wsub: pref_score(nbd_on, nbd_pir, LFX_WITH_SUB_HZ.log10(), sm_pir),
     }
 }
 
+/// Compute the preference score of a speaker crossed over to an ideal subwoofer
+///
+/// The NBD bands starting below `crossover` and the in-room response below it are
+/// left to the subwoofer and not scored; LFX is pinned at [`LFX_WITH_SUB_HZ`].
+///
+/// # Arguments
+/// * `freq` - Frequency array
+/// * `intervals` - Octave band intervals
+/// * `on` - On-axis SPL measurements
+/// * `pir` - Predicted in-room SPL measurements
+/// * `crossover` - Crossover frequency to the subwoofer in Hz
+///
+/// # Returns
+/// * Preference score above the crossover
+pub fn pref_score_with_sub(
+    freq: &Array1<f64>,
+    intervals: &[(usize, usize)],
+    on: &Array1<f64>,
+    pir: &Array1<f64>,
+    crossover: f64,
+) -> f64 {
+    let above: Vec<(usize, usize)> = intervals
+        .iter()
+        .copied()
+        .filter(|&(imin, _)| imin < freq.len() && freq[imin] >= crossover)
+        .collect();
+    pref_score(
+        nbd(&above, on),
+        nbd(&above, pir),
+        LFX_WITH_SUB_HZ.log10(),
+        sm_range(freq, pir, crossover, 16000.0),
+    )
+}
+
 /// Compute CEA2034 metrics and preference score for a PEQ filter
 ///
 /// # Arguments
@@ -606,6 +725,58 @@ mod tests {
         let val = lfx(&freq, &lw, &sp);
         assert!((val - 300.0_f64.log10()).abs() < 1e-12);
     }
+
+    #[test]
+    fn score_with_sub_pins_lfx() {
+        let freq = Array1::logspace(10.0, 20f64.log10(), 20000f64.log10(), 200);
+        // flat speaker rolled off below 60 Hz
+        let spl = freq.mapv(|f: f64| 85.0 + (f / 60.0).log2().min(0.0) * 24.0);
+        let pir = &spl - &freq.mapv(|f: f64| (f / 1000.0).log2());
+        let m = score(&freq, &octave_intervals(2, &freq), &spl, &spl, &spl, &pir);
+        assert!(m.lfx > 50f64.log10() && m.lfx < 60f64.log10());
+        assert!(m.pref_score_wsub > m.pref_score);
+        assert!((m.pref_score_with_lfx(10f64.powf(m.lfx)) - m.pref_score).abs() < 1e-12);
+        assert!((m.pref_score_with_lfx(LFX_WITH_SUB_HZ) - m.pref_score_wsub).abs() < 1e-12);
+    }
+
+    #[test]
+    fn score_with_sub_ignores_bass_below_crossover() {
+        let freq = Array1::logspace(10.0, 20f64.log10(), 20000f64.log10(), 200);
+        let intervals = octave_intervals(2, &freq);
+        let flat = freq.mapv(|f: f64| 85.0 - (f / 1000.0).log2());
+        // a ragged response below 150 Hz, the same tilt above
+        let ragged = &flat
+            + &freq.mapv(|f: f64| {
+                if f < 150.0 {
+                    6.0 * (f / 7.0).sin()
+                } else {
+                    0.0
+                }
+            });
+        let clean = pref_score_with_sub(&freq, &intervals, &flat, &flat, 200.0);
+        let bass = pref_score_with_sub(&freq, &intervals, &ragged, &ragged, 200.0);
+        assert!((clean - bass).abs() < 1e-12);
+        let full = pref_score_with_sub(&freq, &intervals, &ragged, &ragged, 20.0);
+        assert!(full < bass);
+    }
+
+    #[test]
+    fn shifted_bands_and_sm_range() {
+        let freq = Array1::logspace(10.0, 20f64.log10(), 20000f64.log10(), 200);
+        let bands = octave_band_intervals(2, 1290.0, &freq);
+        let intervals: Vec<(usize, usize)> = bands.iter().map(|(_, i)| *i).collect();
+        assert_eq!(intervals, octave_intervals(2, &freq));
+        assert!(bands.iter().all(|(c, _)| *c >= 100.0 && *c <= 12000.0));
+
+        let shifted = octave_band_intervals(2, 1290.0 * 2f64.powf(0.25), &freq);
+        assert!(shifted[0].0 > bands[0].0);
+
+        // tilted above 50 Hz, rolled off below
+        let spl =
+            freq.mapv(|f: f64| 80.0 - (f / 1000.0).log2() + (f / 50.0).log2().min(0.0) * 12.0);
+        assert!((sm(&freq, &spl) - sm_range(&freq, &spl, 100.0, 16000.0)).abs() < 1e-12);
+        assert!(sm_range(&freq, &spl, 30.0, 16000.0) < sm(&freq, &spl));
+    }
 }
 
 /// Compute Predicted In-Room (PIR) response from LW, ER, and SP measurements
@@ -633,6 +804,229 @@ pub fn compute_pir_from_lw_er_sp(
     pressure2spl(&pir_p)
 }
 
+/// Angles in degrees of the rows of `spl_h` and `spl_v`: -170, -160, ..., 0, ..., 170, 180
+///
+/// On axis is row 17 of `spl_h`, as expected by [`score_peq`].
+pub const CEA2034_ANGLES: [i32; 36] = [
+    -170, -160, -150, -140, -130, -120, -110, -100, -90, -80, -70, -60, -50, -40, -30, -20, -10, 0,
+    10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120, 130, 140, 150, 160, 170, 180,
+];
+
+/// Sound power weight of a measurement by its angle to the front axis (0, 10, ..., 90 degrees)
+///
+/// Each point of the horizontal and vertical orbits stands for the area of
+/// the sphere around it, as defined in CEA2034.
+const SOUND_POWER_WEIGHTS: [f64; 10] = [
+    0.000604486,
+    0.004730189,
+    0.008955027,
+    0.012387354,
+    0.014989611,
+    0.016868154,
+    0.018165962,
+    0.019006744,
+    0.019479255,
+    0.019632539,
+];
+
+/// Row of an angle in the stacked `[spl_h; spl_v]` matrix
+fn row_of(vertical: bool, angle: i32) -> usize {
+    let angle = if angle == -180 { 180 } else { angle };
+    let row = ((angle + 170) / 10) as usize;
+    if vertical {
+        row + CEA2034_ANGLES.len()
+    } else {
+        row
+    }
+}
+
+/// Measurement groups of [`cea2034`] for `[spl_h; spl_v]` stacked as in [`score_peq`]
+///
+/// # Returns
+/// * Indices for Listening Window, Early Reflections, Floor Bounce, Ceiling Bounce,
+///   Front Wall Bounce, Side Wall Bounce, Rear Wall Bounce and Sound Power
+///
+/// # Details
+/// Sound Power uses the 70 distinct points: the vertical 0 and 180 degrees
+/// measurements are the horizontal ones again.
+pub fn cea2034_indices() -> Vec<Vec<usize>> {
+    let h = |angles: &[i32]| -> Vec<usize> { angles.iter().map(|a| row_of(false, *a)).collect() };
+    let v = |angles: &[i32]| -> Vec<usize> { angles.iter().map(|a| row_of(true, *a)).collect() };
+
+    let lw = [h(&[0, 10, -10, 20, -20, 30, -30]), v(&[10, -10])].concat();
+    let floor = v(&[-20, -30, -40]);
+    let ceiling = v(&[40, 50, 60]);
+    let front = h(&[0, 10, -10, 20, -20, 30, -30]);
+    let side = h(&[40, -40, 50, -50, 60, -60, 70, -70, 80, -80]);
+    let rear = h(&[90, -90, 180]);
+    let er = [
+        floor.clone(),
+        ceiling.clone(),
+        front.clone(),
+        side.clone(),
+        rear.clone(),
+    ]
+    .concat();
+    let sp = (0..2 * CEA2034_ANGLES.len())
+        .filter(|row| *row != row_of(true, 0) && *row != row_of(true, 180))
+        .collect();
+
+    vec![lw, er, floor, ceiling, front, side, rear, sp]
+}
+
+/// Sound power weights of the rows of `[spl_h; spl_v]`
+pub fn cea2034_weights() -> Array1<f64> {
+    CEA2034_ANGLES
+        .iter()
+        .chain(CEA2034_ANGLES.iter())
+        .map(|angle| {
+            let from_front = angle.unsigned_abs().min(180);
+            let from_axis = from_front.min(180 - from_front);
+            SOUND_POWER_WEIGHTS[(from_axis / 10) as usize]
+        })
+        .collect()
+}
+
+/// Compute the CEA2034 curves from horizontal and vertical measurements
+///
+/// # Arguments
+/// * `freq` - Frequency array shared by all measurements
+/// * `spl_h` - Horizontal SPL, one row per angle of [`CEA2034_ANGLES`]
+/// * `spl_v` - Vertical SPL, one row per angle of [`CEA2034_ANGLES`]
+///
+/// # Returns
+/// * On Axis, Listening Window, Early Reflections, Sound Power, Early Reflections DI,
+///   Sound Power DI and Estimated In-Room Response, keyed like the spinorama.org data
+pub fn spin_from_spl(
+    freq: &Array1<f64>,
+    spl_h: &Array2<f64>,
+    spl_v: &Array2<f64>,
+) -> HashMap<String, Curve> {
+    let spl = concatenate(Axis(0), &[spl_h.view(), spl_v.view()]).expect("concatenate failed");
+    let spin = cea2034(&spl, &cea2034_indices(), &cea2034_weights());
+
+    let on = spl_h.row(row_of(false, 0)).to_owned();
+    let lw = spin.row(0).to_owned();
+    let er = spin.row(1).to_owned();
+    let sp = spin.row(spin.shape()[0] - 2).to_owned();
+    let pir = spin.row(spin.shape()[0] - 1).to_owned();
+    let erdi = &lw - &er;
+    let spdi = &lw - &sp;
+
+    [
+        ("On Axis", on),
+        ("Listening Window", lw),
+        ("Early Reflections", er),
+        ("Sound Power", sp),
+        ("Early Reflections DI", erdi),
+        ("Sound Power DI", spdi),
+        ("Estimated In-Room Response", pir),
+    ]
+    .into_iter()
+    .map(|(name, spl)| {
+        (
+            name.to_string(),
+            Curve {
+                freq: freq.clone(),
+                spl,
+            },
+        )
+    })
+    .collect()
+}
+
+/// Normalize a horizontal or vertical SPL matrix to its on-axis response
+///
+/// # Arguments
+/// * `spl` - SPL, one row per angle of [`CEA2034_ANGLES`]
+///
+/// # Returns
+/// * SPL of each angle relative to 0 degree, as used for contour plots
+pub fn normalized_contour(spl: &Array2<f64>) -> Array2<f64> {
+    let on = spl.row(row_of(false, 0)).insert_axis(Axis(0)).to_owned();
+    spl - &on
+}
+
+/// Angle where a normalized response first drops below `-threshold_db`, walking away from 0
+fn drop_angle(contour: &Array2<f64>, column: usize, threshold_db: f64, direction: i32) -> f64 {
+    let mut prev = 0.0;
+    for step in 1..=18 {
+        let angle = direction * step * 10;
+        let level = contour[[row_of(false, angle), column]];
+        if level < -threshold_db {
+            let fraction = (prev + threshold_db) / (prev - level);
+            return 10.0 * (step - 1) as f64 + 10.0 * fraction;
+        }
+        prev = level;
+    }
+    180.0
+}
+
+/// Beamwidth versus frequency of a horizontal or vertical SPL matrix
+///
+/// # Arguments
+/// * `spl` - SPL, one row per angle of [`CEA2034_ANGLES`]
+/// * `threshold_db` - Drop relative to on axis that bounds the beam (6 dB usually)
+///
+/// # Returns
+/// * Total angle in degrees (0 to 360) around the axis where the response stays
+///   within `threshold_db` of on axis, interpolated between measured angles
+pub fn beamwidth(spl: &Array2<f64>, threshold_db: f64) -> Array1<f64> {
+    let contour = normalized_contour(spl);
+    (0..spl.shape()[1])
+        .map(|column| {
+            drop_angle(&contour, column, threshold_db, 1)
+                + drop_angle(&contour, column, threshold_db, -1)
+        })
+        .collect()
+}
+
+/// Directivity data of a speaker computed from its horizontal and vertical measurements
+#[derive(Debug, Clone)]
+pub struct DirectivityMetrics {
+    /// Directivity index: On Axis minus Sound Power
+    pub di: Array1<f64>,
+    /// Early Reflections DI: Listening Window minus Early Reflections
+    pub erdi: Array1<f64>,
+    /// Sound Power DI: Listening Window minus Sound Power
+    pub spdi: Array1<f64>,
+    /// Horizontal -6 dB beamwidth in degrees
+    pub beamwidth_h: Array1<f64>,
+    /// Vertical -6 dB beamwidth in degrees
+    pub beamwidth_v: Array1<f64>,
+    /// Horizontal SPL relative to on axis, one row per angle of [`CEA2034_ANGLES`]
+    pub contour_h: Array2<f64>,
+    /// Vertical SPL relative to on axis, one row per angle of [`CEA2034_ANGLES`]
+    pub contour_v: Array2<f64>,
+}
+
+/// Compute the directivity metrics from horizontal and vertical measurements
+///
+/// # Arguments
+/// * `spl_h` - Horizontal SPL, one row per angle of [`CEA2034_ANGLES`]
+/// * `spl_v` - Vertical SPL, one row per angle of [`CEA2034_ANGLES`]
+///
+/// # Returns
+/// * DIs, -6 dB beamwidths and normalized contours, one value per frequency
+pub fn directivity_metrics(spl_h: &Array2<f64>, spl_v: &Array2<f64>) -> DirectivityMetrics {
+    let spl = concatenate(Axis(0), &[spl_h.view(), spl_v.view()]).expect("concatenate failed");
+    let spin = cea2034(&spl, &cea2034_indices(), &cea2034_weights());
+    let on = spl_h.row(row_of(false, 0));
+    let lw = spin.row(0);
+    let er = spin.row(1);
+    let sp = spin.row(spin.shape()[0] - 2);
+
+    DirectivityMetrics {
+        di: &on - &sp,
+        erdi: &lw - &er,
+        spdi: &lw - &sp,
+        beamwidth_h: beamwidth(spl_h, 6.0),
+        beamwidth_v: beamwidth(spl_v, 6.0),
+        contour_h: normalized_contour(spl_h),
+        contour_v: normalized_contour(spl_v),
+    }
+}
+
 /// Compute CEA2034 metrics for speaker performance evaluation
 ///
 /// # Arguments
@@ -899,3 +1293,87 @@ mod pir_helpers_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod spl_layout_tests {
+    use super::*;
+
+    #[test]
+    fn indices_follow_cea2034_groups() {
+        let idx = cea2034_indices();
+        assert_eq!(idx.len(), 8);
+        // LW: 7 horizontal + 2 vertical, ER: the 5 bounces, SP: 70 distinct points
+        assert_eq!(idx[0].len(), 9);
+        assert_eq!(idx[1].len(), 3 + 3 + 7 + 10 + 3);
+        assert_eq!(idx[7].len(), 70);
+        assert!(idx[0].contains(&17));
+        assert!(idx[6].contains(&35));
+
+        let w = cea2034_weights();
+        assert_eq!(w.len(), 72);
+        assert_eq!(w[17], w[35]);
+        assert_eq!(w[8], SOUND_POWER_WEIGHTS[9]);
+    }
+
+    #[test]
+    fn omnidirectional_speaker_has_no_directivity() {
+        let freq = Array1::from(vec![100.0, 1000.0, 10000.0]);
+        let spl = Array2::from_elem((36, 3), 85.0);
+        let spin = spin_from_spl(&freq, &spl, &spl);
+
+        assert_eq!(spin.len(), 7);
+        for name in [
+            "On Axis",
+            "Listening Window",
+            "Sound Power",
+            "Estimated In-Room Response",
+        ] {
+            assert!(
+                spin[name].spl.iter().all(|v| (v - 85.0).abs() < 1e-9),
+                "{}",
+                name
+            );
+        }
+        assert!(spin["Sound Power DI"].spl.iter().all(|v| v.abs() < 1e-9));
+    }
+
+    #[test]
+    fn directivity_lowers_sound_power() {
+        let freq = Array1::from(vec![1000.0]);
+        // 1 dB less every 10 degrees off axis, in both planes
+        let spl = Array2::from_shape_fn((36, 1), |(i, _)| {
+            85.0 - CEA2034_ANGLES[i].unsigned_abs() as f64 / 10.0
+        });
+        let spin = spin_from_spl(&freq, &spl, &spl);
+
+        let lw = spin["Listening Window"].spl[0];
+        let sp = spin["Sound Power"].spl[0];
+        assert!(spin["On Axis"].spl[0] > lw && lw > sp);
+        assert!(spin["Sound Power DI"].spl[0] > 0.0);
+    }
+
+    #[test]
+    fn beamwidth_interpolates_minus_6db() {
+        // 1 dB less every 10 degrees off axis: -6 dB at +-60 degrees
+        let spl = Array2::from_shape_fn((36, 2), |(i, j)| {
+            85.0 - CEA2034_ANGLES[i].unsigned_abs() as f64 / 10.0 * (j + 1) as f64
+        });
+        let bw = beamwidth(&spl, 6.0);
+        assert!((bw[0] - 120.0).abs() < 1e-9);
+        assert!((bw[1] - 60.0).abs() < 1e-9);
+
+        let flat = Array2::from_elem((36, 1), 85.0);
+        assert_eq!(beamwidth(&flat, 6.0)[0], 360.0);
+    }
+
+    #[test]
+    fn directivity_metrics_of_directive_speaker() {
+        let spl = Array2::from_shape_fn((36, 1), |(i, _)| {
+            85.0 - CEA2034_ANGLES[i].unsigned_abs() as f64 / 10.0
+        });
+        let m = directivity_metrics(&spl, &spl);
+        assert!(m.di[0] > m.spdi[0] && m.spdi[0] > m.erdi[0] && m.erdi[0] > 0.0);
+        assert_eq!(m.contour_h[[17, 0]], 0.0);
+        assert_eq!(m.contour_v[[35, 0]], -18.0);
+    }
+}
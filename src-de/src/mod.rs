@@ -34,10 +34,15 @@ pub mod differential_evolution;
 pub mod function_registry;
 pub mod impl_helpers;
 pub mod metadata;
+pub mod multi_objective;
 pub mod parallel_eval;
 pub mod recorder;
 pub mod run_recorded;
 pub use differential_evolution::differential_evolution;
+pub use multi_objective::{
+    MultiObjectiveConfig, MultiObjectiveReport, ParetoMember,
+    multi_objective_differential_evolution,
+};
 pub use parallel_eval::ParallelConfig;
 pub use recorder::{OptimizationRecord, OptimizationRecorder};
 pub use run_recorded::run_recorded_differential_evolution;
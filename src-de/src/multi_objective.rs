@@ -0,0 +1,308 @@
+//! Multi-objective differential evolution
+//!
+//! DE/rand/1/bin variation with NSGA-II environmental selection: parents and
+//! trials are merged, sorted into non-dominated fronts and truncated with the
+//! crowding distance. The result is an approximation of the Pareto set.
+
+use ndarray::Array1;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+
+use crate::crossover_binomial::binomial_crossover;
+use crate::distinct_indices::distinct_indices;
+use crate::init_random::init_random;
+
+/// Configuration for the multi-objective optimizer
+#[derive(Debug, Clone)]
+pub struct MultiObjectiveConfig {
+    /// Number of generations
+    pub maxiter: usize,
+    /// Population size (total number of individuals, at least 4)
+    pub popsize: usize,
+    /// Differential weight F
+    pub mutation: f64,
+    /// Crossover probability CR in [0,1]
+    pub recombination: f64,
+    /// Optional seed for reproducible runs
+    pub seed: Option<u64>,
+    /// Individuals replacing the first random members of the initial population
+    pub init_population: Vec<Array1<f64>>,
+    /// Print the size of the first front at each generation
+    pub disp: bool,
+}
+
+impl Default for MultiObjectiveConfig {
+    fn default() -> Self {
+        Self {
+            maxiter: 200,
+            popsize: 100,
+            mutation: 0.5,
+            recombination: 0.9,
+            seed: None,
+            init_population: Vec::new(),
+            disp: false,
+        }
+    }
+}
+
+/// One non-dominated solution
+#[derive(Debug, Clone)]
+pub struct ParetoMember {
+    /// Parameter vector
+    pub x: Array1<f64>,
+    /// Objective values (all minimized)
+    pub f: Vec<f64>,
+}
+
+/// Result of a multi-objective run
+#[derive(Debug, Clone)]
+pub struct MultiObjectiveReport {
+    /// Non-dominated solutions of the final population, sorted by the first objective
+    pub front: Vec<ParetoMember>,
+    /// Number of generations
+    pub nit: usize,
+    /// Number of function evaluations
+    pub nfev: usize,
+}
+
+/// True if `a` Pareto-dominates `b` (no worse everywhere, better somewhere)
+pub fn dominates(a: &[f64], b: &[f64]) -> bool {
+    let mut better = false;
+    for (x, y) in a.iter().zip(b) {
+        if x > y {
+            return false;
+        }
+        if x < y {
+            better = true;
+        }
+    }
+    better
+}
+
+/// Fast non-dominated sort
+///
+/// Returns the indices of `objectives` grouped by front, the first front being
+/// the non-dominated set.
+pub fn non_dominated_sort(objectives: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let n = objectives.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut domination_count = vec![0usize; n];
+    let mut fronts: Vec<Vec<usize>> = vec![Vec::new()];
+
+    for p in 0..n {
+        for q in 0..n {
+            if dominates(&objectives[p], &objectives[q]) {
+                dominated_by[p].push(q);
+            } else if dominates(&objectives[q], &objectives[p]) {
+                domination_count[p] += 1;
+            }
+        }
+        if domination_count[p] == 0 {
+            fronts[0].push(p);
+        }
+    }
+
+    let mut i = 0;
+    while !fronts[i].is_empty() {
+        let mut next = Vec::new();
+        for &p in &fronts[i] {
+            for &q in &dominated_by[p] {
+                domination_count[q] -= 1;
+                if domination_count[q] == 0 {
+                    next.push(q);
+                }
+            }
+        }
+        i += 1;
+        fronts.push(next);
+    }
+    fronts.pop();
+    fronts
+}
+
+/// Crowding distance of each member of `front` (same order as `front`)
+///
+/// Boundary points get an infinite distance so that the extremes of the front
+/// are always kept.
+pub fn crowding_distance(objectives: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+    let n = front.len();
+    let mut distance = vec![0.0; n];
+    if n <= 2 {
+        return vec![f64::INFINITY; n];
+    }
+    let n_obj = objectives[front[0]].len();
+    let columns: Vec<Vec<f64>> = (0..n_obj)
+        .map(|m| front.iter().map(|&k| objectives[k][m]).collect())
+        .collect();
+    for values in &columns {
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        let lo = values[order[0]];
+        let hi = values[order[n - 1]];
+        distance[order[0]] = f64::INFINITY;
+        distance[order[n - 1]] = f64::INFINITY;
+        if hi - lo <= 0.0 {
+            continue;
+        }
+        for k in 1..n - 1 {
+            distance[order[k]] += (values[order[k + 1]] - values[order[k - 1]]) / (hi - lo);
+        }
+    }
+    distance
+}
+
+/// Minimize several objectives at once and return the Pareto set
+///
+/// # Arguments
+/// * `func` - Maps x to its objective values, all minimized
+/// * `bounds` - (lower, upper) pair for each parameter
+/// * `config` - Optimizer configuration
+///
+/// # Returns
+/// * The non-dominated members of the final population
+pub fn multi_objective_differential_evolution<F>(
+    func: &F,
+    bounds: &[(f64, f64)],
+    config: MultiObjectiveConfig,
+) -> MultiObjectiveReport
+where
+    F: Fn(&Array1<f64>) -> Vec<f64> + Sync,
+{
+    let n = bounds.len();
+    let npop = config.popsize.max(4);
+    let lower = Array1::from_iter(bounds.iter().map(|b| b.0));
+    let upper = Array1::from_iter(bounds.iter().map(|b| b.1));
+    let mut rng: StdRng = match config.seed {
+        Some(s) => StdRng::seed_from_u64(s),
+        None => StdRng::from_rng(&mut rand::rng()),
+    };
+
+    let init = init_random(n, npop, &lower, &upper, &vec![true; n], &mut rng);
+    let mut pop: Vec<Array1<f64>> = init.rows().into_iter().map(|r| r.to_owned()).collect();
+    for (member, x0) in pop.iter_mut().zip(&config.init_population) {
+        *member = x0
+            .iter()
+            .zip(bounds)
+            .map(|(v, b)| v.clamp(b.0, b.1))
+            .collect();
+    }
+    let mut objectives: Vec<Vec<f64>> = pop.par_iter().map(func).collect();
+    let mut nfev = npop;
+
+    for iter in 1..=config.maxiter {
+        let trials: Vec<Array1<f64>> = (0..npop)
+            .map(|i| {
+                let r = distinct_indices(i, 3, npop, &mut rng);
+                let mutant = &pop[r[0]] + &((&pop[r[1]] - &pop[r[2]]) * config.mutation);
+                let mutant =
+                    Array1::from_iter(mutant.iter().zip(bounds).map(|(v, b)| v.clamp(b.0, b.1)));
+                binomial_crossover(&pop[i], &mutant, config.recombination, &mut rng)
+            })
+            .collect();
+        let trial_objectives: Vec<Vec<f64>> = trials.par_iter().map(func).collect();
+        nfev += npop;
+
+        pop.extend(trials);
+        objectives.extend(trial_objectives);
+
+        // Keep the best fronts, truncating the last one by crowding distance
+        let mut keep: Vec<usize> = Vec::with_capacity(npop);
+        for front in non_dominated_sort(&objectives) {
+            if keep.len() + front.len() <= npop {
+                keep.extend(front);
+            } else {
+                let distance = crowding_distance(&objectives, &front);
+                let mut order: Vec<usize> = (0..front.len()).collect();
+                order.sort_by(|&a, &b| distance[b].total_cmp(&distance[a]));
+                keep.extend(order.iter().take(npop - keep.len()).map(|&k| front[k]));
+            }
+            if keep.len() == npop {
+                break;
+            }
+        }
+        pop = keep.iter().map(|&k| pop[k].clone()).collect();
+        objectives = keep.iter().map(|&k| objectives[k].clone()).collect();
+
+        if config.disp {
+            eprintln!(
+                "multi-objective DE iter {:4}  first front size={}",
+                iter,
+                non_dominated_sort(&objectives)[0].len()
+            );
+        }
+    }
+
+    let mut front: Vec<ParetoMember> = non_dominated_sort(&objectives)[0]
+        .iter()
+        .map(|&k| ParetoMember {
+            x: pop[k].clone(),
+            f: objectives[k].clone(),
+        })
+        .collect();
+    front.sort_by(|a, b| a.f[0].total_cmp(&b.f[0]));
+    front.dedup_by(|a, b| a.f == b.f);
+
+    MultiObjectiveReport {
+        front,
+        nit: config.maxiter,
+        nfev,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dominates() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 3.0]));
+        assert!(!dominates(&[1.0, 3.0], &[1.0, 3.0]));
+        assert!(!dominates(&[0.0, 4.0], &[1.0, 3.0]));
+    }
+
+    #[test]
+    fn test_non_dominated_sort_and_crowding() {
+        let objectives = vec![
+            vec![1.0, 4.0],
+            vec![2.0, 2.0],
+            vec![4.0, 1.0],
+            vec![3.0, 3.0],
+            vec![5.0, 5.0],
+        ];
+        let fronts = non_dominated_sort(&objectives);
+        assert_eq!(fronts, vec![vec![0, 1, 2], vec![3], vec![4]]);
+
+        let distance = crowding_distance(&objectives, &fronts[0]);
+        assert!(distance[0].is_infinite() && distance[2].is_infinite());
+        assert!((distance[1] - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_zdt1_front() {
+        // ZDT1: the Pareto front is f2 = 1 - sqrt(f1), reached with x[1..] = 0
+        let zdt1 = |x: &Array1<f64>| {
+            let g = 1.0 + 9.0 * x.iter().skip(1).sum::<f64>() / (x.len() - 1) as f64;
+            vec![x[0], g * (1.0 - (x[0] / g).sqrt())]
+        };
+        let config = MultiObjectiveConfig {
+            maxiter: 150,
+            popsize: 40,
+            seed: Some(7),
+            ..MultiObjectiveConfig::default()
+        };
+        let report = multi_objective_differential_evolution(&zdt1, &[(0.0, 1.0); 5], config);
+
+        assert!(
+            report.front.len() >= 10,
+            "front size {}",
+            report.front.len()
+        );
+        for member in &report.front {
+            let ideal = 1.0 - member.f[0].sqrt();
+            assert!(member.f[1] - ideal < 0.1, "{:?}", member.f);
+        }
+        let spread = report.front.last().unwrap().f[0] - report.front[0].f[0];
+        assert!(spread > 0.8, "spread {}", spread);
+    }
+}
@@ -0,0 +1,114 @@
+//! Benchmark of biquad cascade processing
+//!
+//! Compares the per sample `Biquad::process` loop used before block processing
+//! with the scalar and vectorized paths of `BiquadCascade`.
+//!
+//! Usage: `benchmark_cascade [channels] [filters] [sample_rate] [seconds]`
+//! (defaults: 8 channels, 12 filters, 192000 Hz, 10 s of audio)
+
+use autoeq_iir::{Biquad, BiquadCascade, BiquadFilterType};
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BLOCK_FRAMES: usize = 1024;
+
+fn arg<T: std::str::FromStr>(args: &[String], idx: usize, default: T) -> T {
+    args.get(idx)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+fn make_filters(count: usize, srate: f64) -> Vec<Biquad> {
+    (0..count)
+        .map(|i| {
+            let freq = 20.0 * 1000.0_f64.powf((i as f64 + 0.5) / count as f64);
+            let gain = if i % 2 == 0 { 3.0 } else { -4.0 };
+            Biquad::new(BiquadFilterType::Peak, freq, srate, 1.5, gain)
+        })
+        .collect()
+}
+
+fn report(name: &str, elapsed: Duration, audio_seconds: f64, reference: Option<Duration>) {
+    let realtime = audio_seconds / elapsed.as_secs_f64();
+    match reference {
+        Some(r) => println!(
+            "{:<22} {:>9.3} s  {:>8.1}x realtime  {:>5.2}x speedup",
+            name,
+            elapsed.as_secs_f64(),
+            realtime,
+            r.as_secs_f64() / elapsed.as_secs_f64()
+        ),
+        None => println!(
+            "{:<22} {:>9.3} s  {:>8.1}x realtime",
+            name,
+            elapsed.as_secs_f64(),
+            realtime
+        ),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let nch: usize = arg(&args, 1, 8);
+    let nfilters: usize = arg(&args, 2, 12);
+    let srate: f64 = arg(&args, 3, 192_000.0);
+    let seconds: f64 = arg(&args, 4, 10.0);
+
+    let blocks = ((seconds * srate) as usize).div_ceil(BLOCK_FRAMES);
+    let audio_seconds = (blocks * BLOCK_FRAMES) as f64 / srate;
+    println!(
+        "{} channels, {} filters, {} Hz, {:.1} s of audio in blocks of {} frames",
+        nch, nfilters, srate, audio_seconds, BLOCK_FRAMES
+    );
+
+    let filters = make_filters(nfilters, srate);
+    let input: Vec<f32> = (0..BLOCK_FRAMES * nch)
+        .map(|i| ((i as f32) * 0.001).sin() * 0.5)
+        .collect();
+    let mut output = vec![0.0_f32; input.len()];
+
+    // per sample loop over channels and filters
+    let mut chains: Vec<Vec<Biquad>> = (0..nch).map(|_| filters.clone()).collect();
+    let start = Instant::now();
+    for _ in 0..blocks {
+        for f in 0..BLOCK_FRAMES {
+            for (ch, chain) in chains.iter_mut().enumerate() {
+                let mut x = input[f * nch + ch] as f64;
+                for bq in chain.iter_mut() {
+                    x = bq.process(x);
+                }
+                output[f * nch + ch] = x as f32;
+            }
+        }
+        black_box(&mut output);
+    }
+    let per_sample = start.elapsed();
+    report("Biquad::process", per_sample, audio_seconds, None);
+
+    let mut cascade = BiquadCascade::new(&filters, nch);
+    let start = Instant::now();
+    for _ in 0..blocks {
+        cascade.process_interleaved_scalar(black_box(&input), &mut output);
+        black_box(&mut output);
+    }
+    report(
+        "cascade (scalar)",
+        start.elapsed(),
+        audio_seconds,
+        Some(per_sample),
+    );
+
+    cascade.reset();
+    let start = Instant::now();
+    for _ in 0..blocks {
+        cascade.process_interleaved(black_box(&input), &mut output);
+        black_box(&mut output);
+    }
+    report(
+        "cascade (block, SIMD)",
+        start.elapsed(),
+        audio_seconds,
+        Some(per_sample),
+    );
+}
@@ -0,0 +1,581 @@
+// ----------------------------------------------------------------------
+// Hardware DSP Coefficient Export (miniDSP, SigmaStudio, Hypex)
+// ----------------------------------------------------------------------
+//
+// Hardware DSPs take raw biquad coefficients instead of (type, freq, Q, gain).
+// The PEQ is converted to the device sample rate, optionally quantized to the
+// fixed-point format of the DSP core and the resulting response is compared
+// with `compute_peq_response` so that the drift can be checked before upload.
+
+use super::{Biquad, Peq, compute_peq_response, peq_resample};
+use ndarray::Array1;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Coefficient file layout expected by the DSP vendor tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DspTarget {
+    /// miniDSP "advanced biquad" text: `biquadN, b0=.., b1=.., b2=.., a1=.., a2=..`
+    /// with a1 and a2 negated
+    MiniDsp,
+    /// SigmaStudio parameter list: one coefficient per line with its
+    /// fixed-point hex word, order b0 b1 b2 a1 a2 with a1 and a2 negated
+    SigmaStudio,
+    /// Hypex: one biquad per line as `b0, b1, b2, a1, a2` (a0 = 1, not negated)
+    Hypex,
+}
+
+/// Numeric format of the coefficients in the DSP core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoeffQuantization {
+    /// 64-bit float, no quantization
+    Float,
+    /// 28-bit signed 5.23 fixed point (ADAU1701 / SigmaDSP), range [-16, 16)
+    Fixed5_23,
+    /// 32-bit signed Q1.30 fixed point, range [-2, 2)
+    Q1_30,
+}
+
+impl CoeffQuantization {
+    /// Number of fractional bits (0 for floating point)
+    pub fn fractional_bits(&self) -> u32 {
+        match self {
+            CoeffQuantization::Float => 0,
+            CoeffQuantization::Fixed5_23 => 23,
+            CoeffQuantization::Q1_30 => 30,
+        }
+    }
+
+    /// Largest representable magnitude (exclusive upper bound)
+    pub fn range(&self) -> f64 {
+        match self {
+            CoeffQuantization::Float => f64::INFINITY,
+            CoeffQuantization::Fixed5_23 => 16.0,
+            CoeffQuantization::Q1_30 => 2.0,
+        }
+    }
+
+    /// Quantize a coefficient to the nearest representable value
+    ///
+    /// # Arguments
+    /// * `value` - Coefficient to quantize
+    ///
+    /// # Returns
+    /// * Tuple of (integer word, quantized value, saturated flag). The integer
+    ///   word is 0 for `Float`.
+    pub fn quantize(&self, value: f64) -> (i64, f64, bool) {
+        if *self == CoeffQuantization::Float {
+            return (0, value, false);
+        }
+        let scale = (1_i64 << self.fractional_bits()) as f64;
+        let max_word = (self.range() * scale) as i64 - 1;
+        let min_word = -(self.range() * scale) as i64;
+        let word = (value * scale).round() as i64;
+        let clamped = word.clamp(min_word, max_word);
+        (clamped, clamped as f64 / scale, clamped != word)
+    }
+
+    /// Format an integer word as two's complement hex on the width of the format
+    fn hex(&self, word: i64) -> String {
+        match self {
+            CoeffQuantization::Float => String::new(),
+            CoeffQuantization::Fixed5_23 => format!("0x{:07X}", (word as u64) & 0x0FFF_FFFF),
+            CoeffQuantization::Q1_30 => format!("0x{:08X}", (word as u64) & 0xFFFF_FFFF),
+        }
+    }
+}
+
+/// Normalized biquad coefficients (a0 = 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiquadCoeffs {
+    /// Feed-forward coefficient b0
+    pub b0: f64,
+    /// Feed-forward coefficient b1
+    pub b1: f64,
+    /// Feed-forward coefficient b2
+    pub b2: f64,
+    /// Feedback coefficient a1
+    pub a1: f64,
+    /// Feedback coefficient a2
+    pub a2: f64,
+}
+
+impl BiquadCoeffs {
+    /// All coefficients set to zero
+    pub const ZERO: BiquadCoeffs = BiquadCoeffs {
+        b0: 0.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+
+    /// Pass-through section used to pad a bank
+    pub const UNITY: BiquadCoeffs = BiquadCoeffs {
+        b0: 1.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+
+    /// Get the coefficients of a biquad
+    pub fn from_biquad(biquad: &Biquad) -> Self {
+        let (a1, a2, b0, b1, b2) = biquad.constants();
+        BiquadCoeffs { b0, b1, b2, a1, a2 }
+    }
+
+    /// Complex response H(e^jw) at frequency `f` for sample rate `srate`
+    pub fn response(&self, f: f64, srate: f64) -> Complex64 {
+        let z1 = Complex64::from_polar(1.0, -2.0 * PI * f / srate);
+        let z2 = z1 * z1;
+        (self.b0 + self.b1 * z1 + self.b2 * z2) / (1.0 + self.a1 * z1 + self.a2 * z2)
+    }
+}
+
+/// Configuration of a hardware DSP export
+#[derive(Debug, Clone)]
+pub struct DspExportConfig {
+    /// Vendor file layout
+    pub target: DspTarget,
+    /// Sample rate of the DSP in Hz (e.g. 96000 for a miniDSP 2x4 HD)
+    pub srate: f64,
+    /// Coefficient format of the DSP core
+    pub quantization: CoeffQuantization,
+    /// Number of biquads per channel; unused slots are padded with
+    /// pass-through sections. None keeps exactly one section per filter.
+    pub bank_size: Option<usize>,
+}
+
+/// One channel of exported coefficients with its quantization report
+#[derive(Debug, Clone)]
+pub struct DspBank {
+    /// Channel name (e.g. "L", "R", "Out3")
+    pub name: String,
+    /// Quantized coefficients in processing order, padding included
+    pub coeffs: Vec<BiquadCoeffs>,
+    /// Coefficient file content for the selected target
+    pub text: String,
+    /// Maximum absolute deviation from `compute_peq_response` in dB
+    pub max_drift_db: f64,
+    /// RMS deviation from `compute_peq_response` in dB
+    pub rms_drift_db: f64,
+    /// Number of coefficients clipped to the range of the fixed-point format
+    pub saturated: usize,
+    /// Number of filters with a weight other than 1, exported once
+    pub weighted: usize,
+}
+
+impl fmt::Display for DspBank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Channel {}: {} biquads, max drift {:.4} dB, rms drift {:.4} dB, {} saturated",
+            self.name,
+            self.coeffs.len(),
+            self.max_drift_db,
+            self.rms_drift_db,
+            self.saturated
+        )?;
+        if self.weighted > 0 {
+            write!(f, ", {} weights ignored", self.weighted)?;
+        }
+        Ok(())
+    }
+}
+
+/// Frequency grid used to measure quantization drift
+///
+/// Log spaced from 20 Hz to 20 kHz, capped below the Nyquist frequency of the
+/// lowest sample rate involved.
+fn drift_freqs(peq: &Peq, srate: f64) -> Array1<f64> {
+    let min_srate = peq.iter().map(|(_, bq)| bq.srate).fold(srate, f64::min);
+    let f_max = 20000.0_f64.min(0.45 * min_srate);
+    Array1::logspace(10.0, 20.0_f64.log10(), f_max.log10(), 200)
+}
+
+/// Format one bank in the layout of the selected target
+fn format_bank(
+    name: &str,
+    words: &[[i64; 5]],
+    coeffs: &[BiquadCoeffs],
+    config: &DspExportConfig,
+) -> String {
+    let mut lines = Vec::new();
+    match config.target {
+        DspTarget::MiniDsp => {
+            for (i, c) in coeffs.iter().enumerate() {
+                lines.push(format!("biquad{},", i + 1));
+                lines.push(format!("b0={:.16},", c.b0));
+                lines.push(format!("b1={:.16},", c.b1));
+                lines.push(format!("b2={:.16},", c.b2));
+                lines.push(format!("a1={:.16},", 0.0 - c.a1));
+                lines.push(format!("a2={:.16},", 0.0 - c.a2));
+            }
+            // the importer does not accept a trailing comma; `0.0 - x` avoids
+            // printing -0.0 for the padding sections
+            if let Some(last) = lines.last_mut() {
+                last.pop();
+            }
+        }
+        DspTarget::SigmaStudio => {
+            lines.push(format!(
+                "# Channel {} @ {} Hz, {:?}",
+                name, config.srate, config.quantization
+            ));
+            for (i, (c, w)) in coeffs.iter().zip(words.iter()).enumerate() {
+                let values = [c.b0, c.b1, c.b2, 0.0 - c.a1, 0.0 - c.a2];
+                for (k, label) in ["B0", "B1", "B2", "A1", "A2"].iter().enumerate() {
+                    // hex words are stored for the negated feedback coefficients too
+                    let word = if k >= 3 { -w[k] } else { w[k] };
+                    let hex = config.quantization.hex(word);
+                    if hex.is_empty() {
+                        lines.push(format!("Biquad{} {} {:+.12}", i + 1, label, values[k]));
+                    } else {
+                        lines.push(format!(
+                            "Biquad{} {} {} {:+.12}",
+                            i + 1,
+                            label,
+                            hex,
+                            values[k]
+                        ));
+                    }
+                }
+            }
+        }
+        DspTarget::Hypex => {
+            lines.push(format!(
+                "# Channel {} @ {} Hz: b0, b1, b2, a1, a2",
+                name, config.srate
+            ));
+            for c in coeffs {
+                lines.push(format!(
+                    "{:.16}, {:.16}, {:.16}, {:.16}, {:.16}",
+                    c.b0, c.b1, c.b2, c.a1, c.a2
+                ));
+            }
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Export one channel of a PEQ as a hardware DSP biquad bank
+///
+/// # Arguments
+/// * `name` - Channel name used in the report and in the file header
+/// * `peq` - PEQ vector containing weighted biquad filters
+/// * `config` - Target layout, sample rate, quantization and bank size
+///
+/// # Returns
+/// * Bank with quantized coefficients, file text and drift report, or an
+///   error if the PEQ does not fit in the bank
+///
+/// # Notes
+/// Filters designed at another rate than `config.srate` are converted with
+/// `peq_resample`, then the coefficients are quantized. The drift is the difference between the
+/// response of the quantized cascade and `compute_peq_response` of the input
+/// PEQ. Filters with a weight other than 1 are exported once and counted in
+/// `weighted`.
+pub fn peq_export_dsp_bank(
+    name: &str,
+    peq: &Peq,
+    config: &DspExportConfig,
+) -> Result<DspBank, String> {
+    if let Some(size) = config.bank_size
+        && peq.len() > size
+    {
+        return Err(format!(
+            "channel {} has {} filters but the DSP bank only holds {}",
+            name,
+            peq.len(),
+            size
+        ));
+    }
+    let weighted = peq
+        .iter()
+        .filter(|(w, _)| (*w - 1.0).abs() > f64::EPSILON)
+        .count();
+
+    let mut coeffs = Vec::new();
+    let mut words = Vec::new();
+    let mut saturated = 0;
+    let resampled = if peq.iter().all(|(_, bq)| bq.srate == config.srate) {
+        peq.clone()
+    } else {
+        peq_resample(peq, config.srate)?.peq
+    };
+    for (_, biquad) in &resampled {
+        let c = BiquadCoeffs::from_biquad(biquad);
+        let mut w = [0_i64; 5];
+        let mut q = [0.0; 5];
+        for (k, v) in [c.b0, c.b1, c.b2, c.a1, c.a2].iter().enumerate() {
+            let (word, value, sat) = config.quantization.quantize(*v);
+            w[k] = word;
+            q[k] = value;
+            if sat {
+                saturated += 1;
+            }
+        }
+        coeffs.push(BiquadCoeffs {
+            b0: q[0],
+            b1: q[1],
+            b2: q[2],
+            a1: q[3],
+            a2: q[4],
+        });
+        words.push(w);
+    }
+    if let Some(size) = config.bank_size {
+        let unity = config.quantization.quantize(1.0).0;
+        while coeffs.len() < size {
+            coeffs.push(BiquadCoeffs::UNITY);
+            words.push([unity, 0, 0, 0, 0]);
+        }
+    }
+    let freqs = drift_freqs(peq, config.srate);
+    let reference = compute_peq_response(&freqs, peq, config.srate);
+    let quantized = freqs.mapv(|f| {
+        let h: Complex64 = coeffs.iter().map(|c| c.response(f, config.srate)).product();
+        20.0 * h.norm().max(1.0e-10).log10()
+    });
+    let diff = &quantized - &reference;
+    let max_drift_db = diff.iter().fold(0.0_f64, |acc, d| acc.max(d.abs()));
+    let rms_drift_db = (diff.mapv(|d| d * d).sum() / diff.len() as f64).sqrt();
+
+    let text = format_bank(name, &words, &coeffs, config);
+
+    Ok(DspBank {
+        name: name.to_string(),
+        coeffs,
+        text,
+        max_drift_db,
+        rms_drift_db,
+        saturated,
+        weighted,
+    })
+}
+
+/// Export several channels of PEQ as hardware DSP biquad banks
+///
+/// # Arguments
+/// * `channels` - List of (channel name, PEQ) pairs
+/// * `config` - Target layout, sample rate, quantization and bank size
+///
+/// # Returns
+/// * One bank per channel, in the same order, or the first error
+pub fn peq_export_dsp(
+    channels: &[(&str, &Peq)],
+    config: &DspExportConfig,
+) -> Result<Vec<DspBank>, String> {
+    channels
+        .iter()
+        .map(|(name, peq)| peq_export_dsp_bank(name, peq, config))
+        .collect()
+}
+
+#[cfg(test)]
+mod dsp_tests {
+    use super::*;
+    use crate::BiquadFilterType;
+
+    fn config(target: DspTarget, quantization: CoeffQuantization) -> DspExportConfig {
+        DspExportConfig {
+            target,
+            srate: 96000.0,
+            quantization,
+            bank_size: None,
+        }
+    }
+
+    fn sample_peq() -> Peq {
+        vec![
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Lowshelf, 120.0, 96000.0, 0.7, 3.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 1000.0, 96000.0, 2.0, -4.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highshelf, 8000.0, 96000.0, 0.7, -2.0),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_quantize_fixed_point() {
+        let q = CoeffQuantization::Fixed5_23;
+        let (word, value, sat) = q.quantize(1.0);
+        assert_eq!(word, 1 << 23);
+        assert_eq!(value, 1.0);
+        assert!(!sat);
+        let (_, value, _) = q.quantize(0.1);
+        assert!((value - 0.1).abs() <= 0.5 / (1 << 23) as f64);
+        let (_, value, sat) = q.quantize(20.0);
+        assert!(sat);
+        assert!(value < 16.0);
+
+        let q = CoeffQuantization::Q1_30;
+        let (_, value, sat) = q.quantize(-2.0);
+        assert_eq!(value, -2.0);
+        assert!(!sat);
+        let (_, _, sat) = q.quantize(2.0);
+        assert!(sat);
+        assert_eq!(q.hex(-1), "0xFFFFFFFF");
+        assert_eq!(CoeffQuantization::Fixed5_23.hex(-1), "0xFFFFFFF");
+        assert_eq!(CoeffQuantization::Fixed5_23.hex(1 << 23), "0x0800000");
+    }
+
+    #[test]
+    fn test_float_export_matches_reference() {
+        let peq = sample_peq();
+        let bank = peq_export_dsp_bank(
+            "L",
+            &peq,
+            &config(DspTarget::Hypex, CoeffQuantization::Float),
+        )
+        .unwrap();
+        assert_eq!(bank.coeffs.len(), 3);
+        assert!(bank.max_drift_db < 1e-6, "{}", bank);
+        assert_eq!(bank.saturated, 0);
+        assert_eq!(bank.weighted, 0);
+
+        let mut weighted = peq.clone();
+        weighted[1].0 = 0.5;
+        let bank = peq_export_dsp_bank(
+            "L",
+            &weighted,
+            &config(DspTarget::Hypex, CoeffQuantization::Float),
+        )
+        .unwrap();
+        assert_eq!(bank.weighted, 1);
+        assert!(bank.to_string().ends_with(", 1 weights ignored"));
+    }
+
+    #[test]
+    fn test_redesign_at_device_rate() {
+        // A 48 kHz PEQ exported to a 96 kHz DSP is refitted at the device rate
+        let peq: Peq = sample_peq()
+            .into_iter()
+            .map(|(w, bq)| {
+                (
+                    w,
+                    Biquad::new(bq.filter_type, bq.freq, 48000.0, bq.q, bq.db_gain),
+                )
+            })
+            .collect();
+        let bank = peq_export_dsp_bank(
+            "L",
+            &peq,
+            &config(DspTarget::Hypex, CoeffQuantization::Float),
+        )
+        .unwrap();
+        assert!(bank.coeffs[0] != BiquadCoeffs::from_biquad(&peq[0].1));
+        assert!(bank.max_drift_db < 0.1, "{}", bank);
+    }
+
+    #[test]
+    fn test_quantization_drift_ordering() {
+        // A narrow low frequency filter at a high sample rate is the worst case
+        let peq = vec![(
+            1.0,
+            Biquad::new(BiquadFilterType::Peak, 25.0, 96000.0, 8.0, -6.0),
+        )];
+        let b523 = peq_export_dsp_bank(
+            "L",
+            &peq,
+            &config(DspTarget::SigmaStudio, CoeffQuantization::Fixed5_23),
+        )
+        .unwrap();
+        let bq130 = peq_export_dsp_bank(
+            "L",
+            &peq,
+            &config(DspTarget::SigmaStudio, CoeffQuantization::Q1_30),
+        )
+        .unwrap();
+        assert!(bq130.max_drift_db < b523.max_drift_db);
+        assert!(bq130.max_drift_db < 0.01, "{}", bq130);
+    }
+
+    #[test]
+    fn test_typical_peq_drift_is_small() {
+        let bank = peq_export_dsp_bank(
+            "R",
+            &sample_peq(),
+            &config(DspTarget::SigmaStudio, CoeffQuantization::Fixed5_23),
+        )
+        .unwrap();
+        assert!(bank.max_drift_db < 0.1, "{}", bank);
+        assert!(bank.rms_drift_db <= bank.max_drift_db);
+    }
+
+    #[test]
+    fn test_minidsp_format_and_padding() {
+        let mut cfg = config(DspTarget::MiniDsp, CoeffQuantization::Float);
+        cfg.bank_size = Some(10);
+        let bank = peq_export_dsp_bank("Out1", &sample_peq(), &cfg).unwrap();
+        assert_eq!(bank.coeffs.len(), 10);
+        assert_eq!(bank.coeffs[9], BiquadCoeffs::UNITY);
+        assert!(bank.text.starts_with("biquad1,\nb0="));
+        assert!(bank.text.contains("biquad10,"));
+        assert!(bank.text.trim_end().ends_with("a2=0.0000000000000000"));
+        // miniDSP negates the feedback coefficients
+        let c = bank.coeffs[1];
+        assert!(bank.text.contains(&format!("a1={:.16},", 0.0 - c.a1)));
+
+        cfg.bank_size = Some(2);
+        assert!(peq_export_dsp_bank("Out1", &sample_peq(), &cfg).is_err());
+    }
+
+    #[test]
+    fn test_sigmastudio_format() {
+        let bank = peq_export_dsp_bank(
+            "L",
+            &sample_peq(),
+            &config(DspTarget::SigmaStudio, CoeffQuantization::Fixed5_23),
+        )
+        .unwrap();
+        assert!(bank.text.starts_with("# Channel L @ 96000 Hz"));
+        assert_eq!(
+            bank.text
+                .lines()
+                .filter(|l| l.starts_with("Biquad"))
+                .count(),
+            15
+        );
+        // The hex word decodes back to the printed value
+        let line = bank
+            .text
+            .lines()
+            .find(|l| l.starts_with("Biquad2 A1"))
+            .unwrap();
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let word = i64::from_str_radix(parts[2].trim_start_matches("0x"), 16).unwrap();
+        let word = if word & (1 << 27) != 0 {
+            word - (1 << 28)
+        } else {
+            word
+        };
+        let value: f64 = parts[3].parse().unwrap();
+        assert!((word as f64 / (1 << 23) as f64 - value).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_export_multiple_channels() {
+        let left = sample_peq();
+        let right: Peq = sample_peq().into_iter().take(1).collect();
+        let banks = peq_export_dsp(
+            &[("L", &left), ("R", &right)],
+            &config(DspTarget::Hypex, CoeffQuantization::Q1_30),
+        )
+        .unwrap();
+        assert_eq!(banks.len(), 2);
+        assert_eq!(banks[0].name, "L");
+        assert_eq!(banks[1].coeffs.len(), 1);
+        assert_eq!(banks[1].text.lines().count(), 2);
+    }
+}
@@ -0,0 +1,616 @@
+// ----------------------------------------------------------------------
+// Linux / Android Format Functions (CamillaDSP, EasyEffects, PipeWire, Wavelet)
+// ----------------------------------------------------------------------
+
+use super::{Biquad, BiquadDesign, BiquadFilterType, Peq, peq_preamp_gain, peq_spl};
+use ndarray::Array1;
+
+/// Number of bands in an AutoEQ / Wavelet GraphicEQ string
+const GRAPHICEQ_BANDS: usize = 127;
+
+/// Convert BiquadFilterType to CamillaDSP biquad type name
+///
+/// # Arguments
+/// * `filter_type` - The biquad filter type
+///
+/// # Returns
+/// * CamillaDSP `Biquad` filter type
+fn biquad_to_camilladsp_type(filter_type: BiquadFilterType) -> &'static str {
+    match filter_type {
+        BiquadFilterType::Lowpass => "Lowpass",
+        BiquadFilterType::Highpass | BiquadFilterType::HighpassVariableQ => "Highpass",
+        BiquadFilterType::Bandpass => "Bandpass",
+        BiquadFilterType::Peak => "Peaking",
+        BiquadFilterType::Notch => "Notch",
+        BiquadFilterType::Lowshelf => "Lowshelf",
+        BiquadFilterType::Highshelf => "Highshelf",
+        BiquadFilterType::LowpassFirstOrder => "LowpassFO",
+        BiquadFilterType::HighpassFirstOrder => "HighpassFO",
+        BiquadFilterType::LowshelfFirstOrder => "LowshelfFO",
+        BiquadFilterType::HighshelfFirstOrder => "HighshelfFO",
+        BiquadFilterType::AllpassFirstOrder => "AllpassFO",
+        BiquadFilterType::Allpass => "Allpass",
+        BiquadFilterType::LinkwitzTransform => "LinkwitzTransform",
+    }
+}
+
+/// Format PEQ as a CamillaDSP configuration fragment (YAML)
+///
+/// # Arguments
+/// * `comment` - Comment string to include at the top (lines starting with `#`)
+/// * `peq` - PEQ vector containing weighted biquad filters
+///
+/// # Returns
+/// * String with `filters` and `pipeline` sections for CamillaDSP v2+
+///
+/// # Notes
+/// A `Gain` filter named `preamp` carries `peq_preamp_gain` and is placed first
+/// in the pipeline. The pipeline step is applied to channels 0 and 1; merge the
+/// two sections into an existing configuration that defines `devices`.
+/// Filters with the Vicanek design are written as `Free` biquads with their
+/// coefficients, since CamillaDSP designs its filter types with RBJ.
+pub fn peq_format_camilladsp(comment: &str, peq: &Peq) -> String {
+    let mut lines = Vec::new();
+    if !comment.is_empty() {
+        lines.push(comment.to_string());
+    }
+    lines.push("filters:".to_string());
+    lines.push("  preamp:".to_string());
+    lines.push("    type: Gain".to_string());
+    lines.push("    parameters:".to_string());
+    lines.push(format!("      gain: {:.2}", peq_preamp_gain(peq)));
+    lines.push("      scale: dB".to_string());
+
+    let mut names = vec!["preamp".to_string()];
+    for (i, (_, biquad)) in peq.iter().enumerate() {
+        let name = format!("peq_{:02}", i + 1);
+        lines.push(format!("  {}:", name));
+        lines.push("    type: Biquad".to_string());
+        lines.push("    parameters:".to_string());
+        if biquad.design == BiquadDesign::Vicanek {
+            let (a1, a2, b0, b1, b2) = biquad.constants();
+            lines.push("      type: Free".to_string());
+            lines.push(format!("      a1: {}", a1));
+            lines.push(format!("      a2: {}", a2));
+            lines.push(format!("      b0: {}", b0));
+            lines.push(format!("      b1: {}", b1));
+            lines.push(format!("      b2: {}", b2));
+            names.push(name);
+            continue;
+        }
+        lines.push(format!(
+            "      type: {}",
+            biquad_to_camilladsp_type(biquad.filter_type)
+        ));
+        if biquad.filter_type == BiquadFilterType::LinkwitzTransform {
+            lines.push(format!("      freq_act: {:.2}", biquad.freq));
+            lines.push(format!("      q_act: {:.3}", biquad.q));
+            lines.push(format!("      freq_target: {:.2}", biquad.target_freq));
+            lines.push(format!("      q_target: {:.3}", biquad.target_q));
+            names.push(name);
+            continue;
+        }
+        lines.push(format!("      freq: {:.2}", biquad.freq));
+        if !biquad.filter_type.is_first_order() {
+            lines.push(format!("      q: {:.3}", biquad.q));
+        }
+        match biquad.filter_type {
+            BiquadFilterType::Peak
+            | BiquadFilterType::Lowshelf
+            | BiquadFilterType::Highshelf
+            | BiquadFilterType::LowshelfFirstOrder
+            | BiquadFilterType::HighshelfFirstOrder => {
+                lines.push(format!("      gain: {:.2}", biquad.db_gain));
+            }
+            _ => {}
+        }
+        names.push(name);
+    }
+
+    lines.push("pipeline:".to_string());
+    lines.push("  - type: Filter".to_string());
+    lines.push("    channels: [0, 1]".to_string());
+    lines.push("    names:".to_string());
+    for name in &names {
+        lines.push(format!("      - {}", name));
+    }
+    lines.push(String::new());
+
+    lines.join("\n")
+}
+
+/// Convert BiquadFilterType to EasyEffects (LSP) equalizer band type
+///
+/// Returns None for first-order filters and Linkwitz transforms which have no
+/// equivalent band type.
+fn biquad_to_easyeffects_type(filter_type: BiquadFilterType) -> Option<&'static str> {
+    match filter_type {
+        BiquadFilterType::Lowpass => Some("Lo-pass"),
+        BiquadFilterType::Highpass | BiquadFilterType::HighpassVariableQ => Some("Hi-pass"),
+        BiquadFilterType::Bandpass => Some("Bandpass"),
+        BiquadFilterType::Peak => Some("Bell"),
+        BiquadFilterType::Notch => Some("Notch"),
+        BiquadFilterType::Lowshelf => Some("Lo-shelf"),
+        BiquadFilterType::Highshelf => Some("Hi-shelf"),
+        BiquadFilterType::Allpass => Some("Allpass"),
+        BiquadFilterType::LowpassFirstOrder
+        | BiquadFilterType::HighpassFirstOrder
+        | BiquadFilterType::LowshelfFirstOrder
+        | BiquadFilterType::HighshelfFirstOrder
+        | BiquadFilterType::AllpassFirstOrder
+        | BiquadFilterType::LinkwitzTransform => None,
+    }
+}
+
+/// Format one EasyEffects channel (`left` or `right`) as JSON lines
+fn easyeffects_channel(peq: &Peq, channel: &str, lines: &mut Vec<String>) {
+    lines.push(format!("            \"{}\": {{", channel));
+    for (i, (_, biquad)) in peq.iter().enumerate() {
+        lines.push(format!("                \"band{}\": {{", i));
+        lines.push(format!(
+            "                    \"frequency\": {:.2},",
+            biquad.freq
+        ));
+        lines.push(format!(
+            "                    \"gain\": {:.2},",
+            biquad.db_gain
+        ));
+        lines.push("                    \"mode\": \"RLC (BT)\",".to_string());
+        lines.push("                    \"mute\": false,".to_string());
+        lines.push(format!("                    \"q\": {:.3},", biquad.q));
+        lines.push("                    \"slope\": \"x1\",".to_string());
+        lines.push("                    \"solo\": false,".to_string());
+        lines.push(format!(
+            "                    \"type\": \"{}\"",
+            biquad_to_easyeffects_type(biquad.filter_type).unwrap_or("Off")
+        ));
+        let sep = if i + 1 < peq.len() { "," } else { "" };
+        lines.push(format!("                }}{}", sep));
+    }
+    lines.push("            },".to_string());
+}
+
+/// EasyEffects preset and the filters that could not be written into it
+#[derive(Debug, Clone)]
+pub struct EasyEffectsPreset {
+    /// Preset content (JSON)
+    pub json: String,
+    /// Number of first-order and Linkwitz transform filters, which have no LSP band type
+    pub dropped_unsupported: usize,
+    /// Number of filters beyond the 32 bands of the equalizer
+    pub dropped_over_limit: usize,
+}
+
+/// Format PEQ as an EasyEffects output preset (JSON)
+///
+/// # Arguments
+/// * `peq` - PEQ vector containing weighted biquad filters
+///
+/// # Returns
+/// * The preset with the number of dropped filters, or an error if a filter
+///   uses the Vicanek design
+///
+/// # Notes
+/// Uses the IIR equalizer in "RLC (BT)" mode (bilinear transform), which
+/// matches the RBJ biquads computed here. Both channels get the same bands and
+/// the preamp of the written bands goes into `input-gain`. EasyEffects supports at most 32 bands.
+/// First-order filters and Linkwitz transforms have no LSP band type and are
+/// dropped. EasyEffects only stores the filter parameters, so Vicanek filters
+/// cannot be reproduced and are rejected.
+pub fn peq_format_easyeffects(peq: &Peq) -> Result<EasyEffectsPreset, String> {
+    if peq
+        .iter()
+        .any(|(_, biquad)| biquad.design == BiquadDesign::Vicanek)
+    {
+        return Err(
+            "EasyEffects designs its bands with RBJ coefficients and cannot hold Vicanek filters"
+                .to_string(),
+        );
+    }
+    let supported: Peq = peq
+        .iter()
+        .filter(|(_, biquad)| biquad_to_easyeffects_type(biquad.filter_type).is_some())
+        .cloned()
+        .collect();
+    let bands: Peq = supported.iter().take(32).cloned().collect();
+
+    let mut lines = vec![
+        "{".to_string(),
+        "    \"output\": {".to_string(),
+        "        \"blocklist\": [],".to_string(),
+        "        \"equalizer#0\": {".to_string(),
+        "            \"balance\": 0.0,".to_string(),
+        "            \"bypass\": false,".to_string(),
+        format!(
+            "            \"input-gain\": {:.2},",
+            peq_preamp_gain(&bands)
+        ),
+    ];
+    easyeffects_channel(&bands, "left", &mut lines);
+    lines.push("            \"mode\": \"IIR\",".to_string());
+    lines.push(format!("            \"num-bands\": {},", bands.len()));
+    lines.push("            \"output-gain\": 0.0,".to_string());
+    lines.push("            \"pitch-left\": 0.0,".to_string());
+    lines.push("            \"pitch-right\": 0.0,".to_string());
+    easyeffects_channel(&bands, "right", &mut lines);
+    lines.push("            \"split-channels\": false".to_string());
+    lines.push("        },".to_string());
+    lines.push("        \"plugins_order\": [".to_string());
+    lines.push("            \"equalizer#0\"".to_string());
+    lines.push("        ]".to_string());
+    lines.push("    }".to_string());
+    lines.push("}".to_string());
+    lines.push(String::new());
+
+    Ok(EasyEffectsPreset {
+        json: lines.join("\n"),
+        dropped_unsupported: peq.len() - supported.len(),
+        dropped_over_limit: supported.len() - bands.len(),
+    })
+}
+
+/// Convert BiquadFilterType to PipeWire filter-chain builtin label
+///
+/// Returns None for filters without a builtin, which are written as `bq_raw`.
+fn biquad_to_pipewire_label(filter_type: BiquadFilterType) -> Option<&'static str> {
+    match filter_type {
+        BiquadFilterType::Lowpass => Some("bq_lowpass"),
+        BiquadFilterType::Highpass | BiquadFilterType::HighpassVariableQ => Some("bq_highpass"),
+        BiquadFilterType::Bandpass => Some("bq_bandpass"),
+        BiquadFilterType::Peak => Some("bq_peaking"),
+        BiquadFilterType::Notch => Some("bq_notch"),
+        BiquadFilterType::Lowshelf => Some("bq_lowshelf"),
+        BiquadFilterType::Highshelf => Some("bq_highshelf"),
+        BiquadFilterType::Allpass => Some("bq_allpass"),
+        BiquadFilterType::LowpassFirstOrder
+        | BiquadFilterType::HighpassFirstOrder
+        | BiquadFilterType::LowshelfFirstOrder
+        | BiquadFilterType::HighshelfFirstOrder
+        | BiquadFilterType::AllpassFirstOrder
+        | BiquadFilterType::LinkwitzTransform => None,
+    }
+}
+
+/// Format one PipeWire filter-chain builtin node
+fn pipewire_node(name: &str, biquad: &Biquad) -> String {
+    let label = biquad_to_pipewire_label(biquad.filter_type)
+        .filter(|_| biquad.design != BiquadDesign::Vicanek);
+    let Some(label) = label else {
+        let (a1, a2, b0, b1, b2) = biquad.constants();
+        return format!(
+            "                    {{ type = builtin name = {} label = bq_raw config = {{ coefficients = [ {{ rate = {} b0 = {} b1 = {} b2 = {} a0 = 1.0 a1 = {} a2 = {} }} ] }} }}",
+            name, biquad.srate as u32, b0, b1, b2, a1, a2
+        );
+    };
+    format!(
+        "                    {{ type = builtin name = {} label = {} control = {{ \"Freq\" = {:.2} \"Q\" = {:.3} \"Gain\" = {:.2} }} }}",
+        name, label, biquad.freq, biquad.q, biquad.db_gain
+    )
+}
+
+/// Format PEQ as a PipeWire filter-chain configuration
+///
+/// # Arguments
+/// * `name` - Description of the virtual sink created by the filter-chain
+/// * `peq` - PEQ vector containing weighted biquad filters
+///
+/// # Returns
+/// * String to drop into `~/.config/pipewire/pipewire.conf.d/`
+///
+/// # Notes
+/// The preamp is a `bq_highshelf` at 0 Hz, i.e. a flat gain, placed first in
+/// the chain. Filters without a builtin (first-order, Linkwitz transform) and
+/// filters with the Vicanek design are written as `bq_raw` with the
+/// coefficients for the biquad sample rate. Nodes are linked in order and the graph is duplicated for both
+/// channels by PipeWire (`audio.channels = 2`).
+pub fn peq_format_pipewire(name: &str, peq: &Peq) -> String {
+    let mut nodes = vec![format!(
+        "                    {{ type = builtin name = preamp label = bq_highshelf control = {{ \"Freq\" = 0 \"Q\" = 1.0 \"Gain\" = {:.2} }} }}",
+        peq_preamp_gain(peq)
+    )];
+    let mut names = vec!["preamp".to_string()];
+    for (i, (_, biquad)) in peq.iter().enumerate() {
+        let node_name = format!("eq_band_{}", i + 1);
+        nodes.push(pipewire_node(&node_name, biquad));
+        names.push(node_name);
+    }
+    let links: Vec<String> = names
+        .windows(2)
+        .map(|w| {
+            format!(
+                "                    {{ output = \"{}:Out\" input = \"{}:In\" }}",
+                w[0], w[1]
+            )
+        })
+        .collect();
+
+    format!(
+        r#"# PipeWire filter-chain generated by AutoEQ
+context.modules = [
+    {{ name = libpipewire-module-filter-chain
+        args = {{
+            node.description = "{name}"
+            media.name       = "{name}"
+            filter.graph = {{
+                nodes = [
+{nodes}
+                ]
+                links = [
+{links}
+                ]
+            }}
+            audio.channels = 2
+            audio.position = [ FL FR ]
+            capture.props = {{
+                node.name   = "effect_input.autoeq"
+                media.class = Audio/Sink
+            }}
+            playback.props = {{
+                node.name    = "effect_output.autoeq"
+                node.passive = true
+            }}
+        }}
+    }}
+]
+"#,
+        name = name,
+        nodes = nodes.join("\n"),
+        links = links.join("\n")
+    )
+}
+
+/// Frequencies of the 127 point GraphicEQ grid (20 Hz to 20 kHz, log spaced)
+///
+/// # Returns
+/// * Array of frequencies rounded to the nearest Hz
+pub fn graphiceq_freqs() -> Array1<f64> {
+    Array1::logspace(10.0, 20.0_f64.log10(), 20000.0_f64.log10(), GRAPHICEQ_BANDS).mapv(f64::round)
+}
+
+/// Format PEQ as a GraphicEQ string (Wavelet, EqualizerAPO GraphicEQ)
+///
+/// # Arguments
+/// * `peq` - PEQ vector containing weighted biquad filters
+///
+/// # Returns
+/// * Single line `GraphicEQ: f1 g1; f2 g2; ...` on the 127 point grid
+///
+/// # Notes
+/// The PEQ response is sampled on `graphiceq_freqs` and shifted by
+/// `peq_preamp_gain` so that the curve never goes above 0 dB.
+pub fn peq_format_graphiceq(peq: &Peq) -> String {
+    let freqs = graphiceq_freqs();
+    let spl = peq_spl(&freqs, peq) + peq_preamp_gain(peq);
+    let bands: Vec<String> = freqs
+        .iter()
+        .zip(spl.iter())
+        .map(|(f, g)| format!("{} {:.1}", *f as i32, g))
+        .collect();
+    format!("GraphicEQ: {}\n", bands.join("; "))
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    fn sample_peq() -> Peq {
+        vec![
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highpass, 30.0, 48000.0, 0.7, 0.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Lowshelf, 105.0, 48000.0, 0.8, 2.5),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 1000.0, 48000.0, 1.5, -3.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highshelf, 9000.0, 48000.0, 0.7, -1.5),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_peq_format_camilladsp() {
+        let yaml = peq_format_camilladsp("# test", &sample_peq());
+        assert!(yaml.starts_with("# test\nfilters:\n"));
+        assert!(yaml.contains("  peq_01:\n    type: Biquad\n"));
+        assert!(yaml.contains("      type: Highpass\n      freq: 30.00\n      q: 0.700\n"));
+        assert!(yaml.contains("      type: Peaking\n      freq: 1000.00\n"));
+        assert!(yaml.contains("      gain: -3.00\n"));
+        assert!(yaml.contains("pipeline:\n  - type: Filter\n"));
+        // preamp first, then every band in order
+        let names = yaml.split("names:\n").nth(1).unwrap();
+        let names: Vec<&str> = names
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["- preamp", "- peq_01", "- peq_02", "- peq_03", "- peq_04"]
+        );
+    }
+
+    #[test]
+    fn test_peq_format_easyeffects() {
+        let preset = peq_format_easyeffects(&sample_peq()).unwrap();
+        assert_eq!(preset.dropped_unsupported, 0);
+        assert_eq!(preset.dropped_over_limit, 0);
+        let json = preset.json;
+        assert!(json.contains("\"num-bands\": 4,"));
+        assert!(json.contains("\"type\": \"Hi-pass\""));
+        assert!(json.contains("\"type\": \"Lo-shelf\""));
+        assert!(json.contains("\"type\": \"Bell\""));
+        assert!(json.contains("\"type\": \"Hi-shelf\""));
+        assert!(json.contains("\"band3\": {"));
+        assert!(!json.contains("\"band4\": {"));
+        let preamp = format!("\"input-gain\": {:.2},", peq_preamp_gain(&sample_peq()));
+        assert!(json.contains(&preamp));
+        // brackets and braces must balance
+        let open = json.matches('{').count();
+        let close = json.matches('}').count();
+        assert_eq!(open, close);
+        // no trailing comma before a closing brace
+        assert!(!json.contains(",\n                }"));
+        assert!(!json.contains(",\n            }"));
+    }
+
+    #[test]
+    fn test_first_order_and_linkwitz_transform() {
+        let peq: Peq = vec![
+            (
+                1.0,
+                Biquad::new_linkwitz_transform(45.0, 0.9, 25.0, 0.6, 48000.0),
+            ),
+            (
+                1.0,
+                Biquad::new(
+                    BiquadFilterType::LowshelfFirstOrder,
+                    200.0,
+                    48000.0,
+                    0.0,
+                    2.0,
+                ),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Allpass, 800.0, 48000.0, 0.7, 0.0),
+            ),
+        ];
+
+        let yaml = peq_format_camilladsp("", &peq);
+        assert!(yaml.contains(
+            "      type: LinkwitzTransform\n      freq_act: 45.00\n      q_act: 0.900\n      freq_target: 25.00\n      q_target: 0.600\n"
+        ));
+        assert!(yaml.contains("      type: LowshelfFO\n      freq: 200.00\n      gain: 2.00\n"));
+        assert!(yaml.contains("      type: Allpass\n      freq: 800.00\n      q: 0.700\n"));
+
+        let preset = peq_format_easyeffects(&peq).unwrap();
+        assert_eq!(preset.dropped_unsupported, 2);
+        let json = preset.json;
+        assert!(json.contains("\"num-bands\": 1,"));
+        assert!(json.contains("\"type\": \"Allpass\""));
+        // the dropped boosts must not lower the preamp
+        let preamp = format!(
+            "\"input-gain\": {:.2},",
+            peq_preamp_gain(&peq[2..].to_vec())
+        );
+        assert!(json.contains(&preamp));
+        assert_ne!(peq_preamp_gain(&peq), peq_preamp_gain(&peq[2..].to_vec()));
+
+        let conf = peq_format_pipewire("AutoEQ test", &peq);
+        assert!(conf.contains(
+            "name = eq_band_1 label = bq_raw config = { coefficients = [ { rate = 48000"
+        ));
+        assert!(conf.contains("name = eq_band_3 label = bq_allpass"));
+    }
+
+    #[test]
+    fn test_peq_format_easyeffects_max_bands() {
+        let peq: Peq = (0..40)
+            .map(|i| {
+                (
+                    1.0,
+                    Biquad::new(
+                        BiquadFilterType::Peak,
+                        100.0 + 100.0 * i as f64,
+                        48000.0,
+                        1.0,
+                        1.0,
+                    ),
+                )
+            })
+            .collect();
+        let preset = peq_format_easyeffects(&peq).unwrap();
+        assert_eq!(preset.dropped_over_limit, 8);
+        let json = preset.json;
+        assert!(json.contains("\"num-bands\": 32,"));
+        // the preamp only accounts for the bands that were written
+        let bands: Peq = peq.iter().take(32).cloned().collect();
+        let preamp = format!("\"input-gain\": {:.2},", peq_preamp_gain(&bands));
+        assert!(json.contains(&preamp));
+    }
+
+    #[test]
+    fn test_vicanek_design_writes_coefficients() {
+        let peq: Peq = vec![(
+            1.0,
+            Biquad::new_with_design(
+                BiquadFilterType::Peak,
+                12000.0,
+                48000.0,
+                2.0,
+                -4.0,
+                BiquadDesign::Vicanek,
+            ),
+        )];
+        let (a1, a2, b0, b1, b2) = peq[0].1.constants();
+
+        let yaml = peq_format_camilladsp("", &peq);
+        assert!(yaml.contains(&format!(
+            "      type: Free\n      a1: {}\n      a2: {}\n      b0: {}\n      b1: {}\n      b2: {}\n",
+            a1, a2, b0, b1, b2
+        )));
+        assert!(!yaml.contains("Peaking"));
+
+        let conf = peq_format_pipewire("AutoEQ test", &peq);
+        assert!(conf.contains("name = eq_band_1 label = bq_raw"));
+        assert!(conf.contains(&format!("b0 = {} b1 = {} b2 = {}", b0, b1, b2)));
+
+        assert!(peq_format_easyeffects(&peq).is_err());
+    }
+
+    #[test]
+    fn test_peq_format_pipewire() {
+        let conf = peq_format_pipewire("AutoEQ test", &sample_peq());
+        assert!(conf.contains("libpipewire-module-filter-chain"));
+        assert!(conf.contains("node.description = \"AutoEQ test\""));
+        assert!(conf.contains("name = eq_band_1 label = bq_highpass"));
+        assert!(conf.contains("name = eq_band_3 label = bq_peaking"));
+        assert!(conf.contains("\"Freq\" = 1000.00 \"Q\" = 1.500 \"Gain\" = -3.00"));
+        assert!(conf.contains("{ output = \"preamp:Out\" input = \"eq_band_1:In\" }"));
+        assert!(conf.contains("{ output = \"eq_band_3:Out\" input = \"eq_band_4:In\" }"));
+        assert!(!conf.contains("eq_band_5"));
+    }
+
+    #[test]
+    fn test_graphiceq_freqs() {
+        let freqs = graphiceq_freqs();
+        assert_eq!(freqs.len(), GRAPHICEQ_BANDS);
+        assert_eq!(freqs[0], 20.0);
+        assert_eq!(freqs[GRAPHICEQ_BANDS - 1], 20000.0);
+        assert!(freqs.windows(2).into_iter().all(|w| w[1] > w[0]));
+    }
+
+    #[test]
+    fn test_peq_format_graphiceq() {
+        let peq = vec![(
+            1.0,
+            Biquad::new(BiquadFilterType::Peak, 1000.0, 48000.0, 1.0, 6.0),
+        )];
+        let text = peq_format_graphiceq(&peq);
+        assert!(text.starts_with("GraphicEQ: 20 "));
+        assert!(text.ends_with("\n"));
+        let bands: Vec<(f64, f64)> = text
+            .trim()
+            .trim_start_matches("GraphicEQ: ")
+            .split("; ")
+            .map(|b| {
+                let mut it = b.split(' ');
+                let f = it.next().unwrap().parse().unwrap();
+                let g = it.next().unwrap().parse().unwrap();
+                (f, g)
+            })
+            .collect();
+        assert_eq!(bands.len(), GRAPHICEQ_BANDS);
+        // Normalized so the peak sits at (about) 0 dB and the tails at -6 dB
+        let max = bands.iter().map(|b| b.1).fold(f64::NEG_INFINITY, f64::max);
+        assert!(max <= 0.05 && max > -0.5, "max {}", max);
+        assert!((bands[0].1 + 6.0).abs() < 0.2);
+    }
+}
@@ -0,0 +1,328 @@
+// ----------------------------------------------------------------------
+// Matched Magnitude Biquad Design (Vicanek)
+// ----------------------------------------------------------------------
+//
+// The RBJ cookbook filters use the bilinear transform, which maps the whole
+// analog frequency axis onto [0, fs/2] and "cramps" peaks and shelves close to
+// Nyquist. Following M. Vicanek, "Matched Second Order Digital Filters"
+// (2016), the poles are placed by impulse invariance and the numerator is
+// solved so that the digital magnitude equals the analog one at DC, at the
+// filter frequency and at Nyquist (or, for peaks, with a flat top at the
+// filter frequency).
+
+use super::{Biquad, BiquadFilterType};
+use ndarray::Array1;
+use std::f64::consts::{PI, SQRT_2};
+
+/// Coefficient design method of a biquad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum BiquadDesign {
+    /// RBJ Audio EQ Cookbook (bilinear transform)
+    #[default]
+    Rbj,
+    /// Vicanek matched magnitude design, close to the analog prototype up to Nyquist
+    Vicanek,
+}
+
+/// Squared magnitude of s^2 + s*w/q + w^2 at s = j*2*pi*f, normalized by w^4
+fn resonance_mag2(f: f64, f0: f64, q: f64) -> f64 {
+    let x = f / f0;
+    (1.0 - x * x).powi(2) + (x / q).powi(2)
+}
+
+/// Squared magnitude of the analog prototype of a biquad at frequency `f`
+///
+/// # Arguments
+/// * `bq` - Biquad whose type, frequency, Q and gain define the prototype
+/// * `f` - Frequency in Hz
+///
+/// # Returns
+/// * |H(j*2*pi*f)|^2 of the analog filter that the RBJ design approximates
+fn analog_mag2(bq: &Biquad, f: f64) -> f64 {
+    let a = 10.0_f64.powf(bq.db_gain / 40.0);
+    let x = f / bq.freq;
+    let q = bq.q;
+    match bq.filter_type {
+        BiquadFilterType::Lowpass => 1.0 / resonance_mag2(f, bq.freq, q),
+        BiquadFilterType::Highpass | BiquadFilterType::HighpassVariableQ => {
+            x.powi(4) / resonance_mag2(f, bq.freq, q)
+        }
+        BiquadFilterType::Bandpass => (x / q).powi(2) / resonance_mag2(f, bq.freq, q),
+        BiquadFilterType::Notch => (1.0 - x * x).powi(2) / resonance_mag2(f, bq.freq, q),
+        BiquadFilterType::Peak => {
+            ((1.0 - x * x).powi(2) + (x * a / q).powi(2))
+                / ((1.0 - x * x).powi(2) + (x / (a * q)).powi(2))
+        }
+        // RBJ shelves in this crate use a fixed slope S = 1, i.e. Q = 1/sqrt(2)
+        BiquadFilterType::Lowshelf => {
+            a * a * ((a - x * x).powi(2) + 2.0 * a * x * x)
+                / ((1.0 - a * x * x).powi(2) + 2.0 * a * x * x)
+        }
+        BiquadFilterType::Highshelf => {
+            a * a * ((1.0 - a * x * x).powi(2) + 2.0 * a * x * x)
+                / ((a - x * x).powi(2) + 2.0 * a * x * x)
+        }
+        BiquadFilterType::LowpassFirstOrder => 1.0 / (1.0 + x * x),
+        BiquadFilterType::HighpassFirstOrder => x * x / (1.0 + x * x),
+        BiquadFilterType::LowshelfFirstOrder => (x * x + a * a) / (x * x + 1.0 / (a * a)),
+        BiquadFilterType::HighshelfFirstOrder => a * a * (a * a * x * x + 1.0) / (x * x + a * a),
+        BiquadFilterType::AllpassFirstOrder | BiquadFilterType::Allpass => 1.0,
+        BiquadFilterType::LinkwitzTransform => {
+            let qp = if bq.target_q > 0.0 { bq.target_q } else { q };
+            (bq.freq / bq.target_freq).powi(4) * resonance_mag2(f, bq.freq, q)
+                / resonance_mag2(f, bq.target_freq, qp)
+        }
+    }
+}
+
+/// Analog pole frequency and Q of a second-order prototype
+fn analog_poles(bq: &Biquad) -> (f64, f64) {
+    let a = 10.0_f64.powf(bq.db_gain / 40.0);
+    match bq.filter_type {
+        BiquadFilterType::Peak => (bq.freq, bq.q * a),
+        BiquadFilterType::Lowshelf => (bq.freq / a.sqrt(), 1.0 / SQRT_2),
+        BiquadFilterType::Highshelf => (bq.freq * a.sqrt(), 1.0 / SQRT_2),
+        BiquadFilterType::LinkwitzTransform => (
+            bq.target_freq,
+            if bq.target_q > 0.0 { bq.target_q } else { bq.q },
+        ),
+        _ => (bq.freq, bq.q),
+    }
+}
+
+/// Second-order denominator (a1, a2) matching an analog pole pair by impulse invariance
+fn matched_poles(freq: f64, q: f64, srate: f64) -> (f64, f64) {
+    let w0 = 2.0 * PI * freq / srate;
+    let zeta = 1.0 / (2.0 * q);
+    let a1 = if zeta <= 1.0 {
+        -2.0 * (-zeta * w0).exp() * ((1.0 - zeta * zeta).sqrt() * w0).cos()
+    } else {
+        -2.0 * (-zeta * w0).exp() * ((zeta * zeta - 1.0).sqrt() * w0).cosh()
+    };
+    (a1, (-2.0 * zeta * w0).exp())
+}
+
+/// Compute matched magnitude coefficients for a biquad
+///
+/// # Arguments
+/// * `bq` - Biquad describing the filter (type, freq, Q, gain, srate)
+///
+/// # Returns
+/// * Normalized coefficients (b0, b1, b2, a1, a2), or None when the fitted
+///   magnitude cannot be realized, in which case the caller keeps the RBJ design
+pub(crate) fn vicanek_coeffs(bq: &Biquad) -> Option<(f64, f64, f64, f64, f64)> {
+    let srate = bq.srate;
+    let nyquist = srate / 2.0;
+
+    if bq.filter_type.is_first_order() {
+        // Pole by impulse invariance, zero fitted at DC and Nyquist
+        let pole_freq = match bq.filter_type {
+            BiquadFilterType::LowshelfFirstOrder => bq.freq / 10.0_f64.powf(bq.db_gain / 40.0),
+            BiquadFilterType::HighshelfFirstOrder => bq.freq * 10.0_f64.powf(bq.db_gain / 40.0),
+            _ => bq.freq,
+        };
+        let a1 = -(-2.0 * PI * pole_freq / srate).exp();
+        if bq.filter_type == BiquadFilterType::AllpassFirstOrder {
+            return Some((a1, 1.0, 0.0, a1, 0.0));
+        }
+        let b_dc = analog_mag2(bq, 0.0).sqrt() * (1.0 + a1);
+        let b_ny = analog_mag2(bq, nyquist).sqrt() * (1.0 - a1);
+        return Some(((b_dc + b_ny) / 2.0, (b_dc - b_ny) / 2.0, 0.0, a1, 0.0));
+    }
+
+    let (pole_freq, pole_q) = analog_poles(bq);
+    let (a1, a2) = matched_poles(pole_freq, pole_q, srate);
+    match bq.filter_type {
+        BiquadFilterType::Allpass => {
+            // Mirror the poles to keep a true all-pass
+            return Some((a2, a1, 1.0, a1, a2));
+        }
+        BiquadFilterType::Notch => {
+            // Zeros stay on the unit circle at freq, gain matched at DC
+            let cs = (2.0 * PI * bq.freq / srate).cos();
+            let g = (1.0 + a1 + a2) / (2.0 - 2.0 * cs);
+            return Some((g, -2.0 * g * cs, g, a1, a2));
+        }
+        _ => {}
+    }
+
+    // |H|^2 = (B0 p0 + B1 p1 + B2 p2) / (A0 p0 + A1 p1 + A2 p2) with
+    // p1 = sin^2(w/2), p0 = 1 - p1 and p2 = 4 p0 p1
+    let big_a0 = (1.0 + a1 + a2).powi(2);
+    let big_a1 = (1.0 - a1 + a2).powi(2);
+    let big_a2 = -4.0 * a2;
+    let fm = bq.freq.min(0.45 * srate);
+    let p1 = (PI * fm / srate).sin().powi(2);
+    let p0 = 1.0 - p1;
+    let p2 = 4.0 * p0 * p1;
+
+    let big_b0 = analog_mag2(bq, 0.0) * big_a0;
+    let r1 = analog_mag2(bq, fm) * (big_a0 * p0 + big_a1 * p1 + big_a2 * p2);
+    let peaking = matches!(
+        bq.filter_type,
+        BiquadFilterType::Peak | BiquadFilterType::Bandpass
+    );
+    let (big_b1, big_b2) = if peaking && fm == bq.freq {
+        // Peaks have a flat top at freq: match the slope there instead of Nyquist
+        let g2 = analog_mag2(bq, fm);
+        let r2 = (-big_a0 + big_a1 + 4.0 * (p0 - p1) * big_a2) * g2;
+        let big_b2 = (r1 - r2 * p1 - big_b0) / (4.0 * p1 * p1);
+        (r2 + big_b0 + 4.0 * (p1 - p0) * big_b2, big_b2)
+    } else {
+        let big_b1 = analog_mag2(bq, nyquist) * big_a1;
+        (big_b1, (r1 - big_b0 * p0 - big_b1 * p1) / p2)
+    };
+
+    // Back to coefficients: sqrt(B0) = b0 + b1 + b2, sqrt(B1) = b0 - b1 + b2
+    // and B2 = -4 b0 b2
+    let (sb0, sb1) = (big_b0.sqrt(), big_b1.sqrt());
+    let w = (sb0 + sb1) / 2.0;
+    let disc = w * w + big_b2;
+    if !disc.is_finite() || disc < 0.0 {
+        return None;
+    }
+    let b0 = (w + disc.sqrt()) / 2.0;
+    let b1 = (sb0 - sb1) / 2.0;
+    let b2 = w - b0;
+    Some((b0, b1, b2, a1, a2))
+}
+
+/// Analog prototype response of a biquad in dB
+///
+/// # Arguments
+/// * `bq` - Biquad filter
+/// * `freqs` - Frequency points in Hz
+///
+/// # Returns
+/// * Magnitude of the analog filter the digital designs approximate, in dB
+pub fn biquad_analog_response(bq: &Biquad, freqs: &Array1<f64>) -> Array1<f64> {
+    freqs.mapv(|f| 10.0 * analog_mag2(bq, f).max(1.0e-20).log10())
+}
+
+#[cfg(test)]
+mod matched_tests {
+    use super::*;
+    use crate::{Peq, compute_peq_response, compute_peq_response_with_design};
+
+    fn audio_band() -> Array1<f64> {
+        Array1::logspace(10.0, 20.0_f64.log10(), 20_000.0_f64.log10(), 200)
+    }
+
+    fn max_error(bq: &Biquad, freqs: &Array1<f64>) -> f64 {
+        let analog = biquad_analog_response(bq, freqs);
+        (&bq.np_log_result(freqs) - &analog)
+            .mapv(f64::abs)
+            .fold(0.0, |m: f64, v| m.max(*v))
+    }
+
+    #[test]
+    fn test_high_frequency_filters_follow_analog() {
+        let freqs = audio_band();
+        let cases = [
+            (BiquadFilterType::Peak, 16_000.0, 2.0, 6.0),
+            (BiquadFilterType::Peak, 12_000.0, 1.0, -8.0),
+            (BiquadFilterType::Highshelf, 10_000.0, 0.7, 6.0),
+            (BiquadFilterType::Lowpass, 15_000.0, 0.9, 0.0),
+            (BiquadFilterType::Bandpass, 14_000.0, 3.0, 0.0),
+        ];
+        for (filter_type, freq, q, gain) in cases {
+            let rbj = Biquad::new(filter_type, freq, 48_000.0, q, gain);
+            let matched = Biquad::new_with_design(
+                filter_type,
+                freq,
+                48_000.0,
+                q,
+                gain,
+                BiquadDesign::Vicanek,
+            );
+            let (e_rbj, e_matched) = (max_error(&rbj, &freqs), max_error(&matched, &freqs));
+            assert!(
+                e_matched < 1.0,
+                "{:?}: matched error {}",
+                filter_type,
+                e_matched
+            );
+            assert!(
+                e_matched < e_rbj,
+                "{:?}: {} vs {}",
+                filter_type,
+                e_matched,
+                e_rbj
+            );
+        }
+    }
+
+    #[test]
+    fn test_low_frequency_designs_agree() {
+        let freqs = audio_band();
+        for filter_type in [
+            BiquadFilterType::Peak,
+            BiquadFilterType::Lowshelf,
+            BiquadFilterType::Highpass,
+            BiquadFilterType::LowshelfFirstOrder,
+        ] {
+            let rbj = Biquad::new(filter_type, 200.0, 48_000.0, 1.5, 4.0);
+            let matched = rbj.redesign(48_000.0, BiquadDesign::Vicanek);
+            assert!(max_error(&rbj, &freqs) < 0.5, "{:?}", filter_type);
+            assert!(max_error(&matched, &freqs) < 0.5, "{:?}", filter_type);
+        }
+    }
+
+    #[test]
+    fn test_allpass_and_notch_keep_their_zeros() {
+        let freqs = audio_band();
+        for filter_type in [
+            BiquadFilterType::Allpass,
+            BiquadFilterType::AllpassFirstOrder,
+        ] {
+            let ap = Biquad::new_with_design(
+                filter_type,
+                9_000.0,
+                48_000.0,
+                0.8,
+                0.0,
+                BiquadDesign::Vicanek,
+            );
+            for v in ap.np_log_result(&freqs).iter() {
+                assert!(v.abs() < 1e-6, "{:?}: {}", filter_type, v);
+            }
+        }
+        let notch = Biquad::new_with_design(
+            BiquadFilterType::Notch,
+            5_000.0,
+            48_000.0,
+            0.0,
+            0.0,
+            BiquadDesign::Vicanek,
+        );
+        assert!(notch.log_result(5_000.0) < -100.0);
+        assert!(notch.log_result(20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_peq_response_with_design() {
+        let peq: Peq = vec![
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 1_000.0, 48_000.0, 1.0, 3.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highshelf, 12_000.0, 48_000.0, 0.7, -4.0),
+            ),
+        ];
+        let freqs = audio_band();
+        let rbj = compute_peq_response_with_design(&freqs, &peq, BiquadDesign::Rbj);
+        let reference = compute_peq_response(&freqs, &peq, 48_000.0);
+        for (a, b) in rbj.iter().zip(reference.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        let matched = compute_peq_response_with_design(&freqs, &peq, BiquadDesign::Vicanek);
+        let analog =
+            biquad_analog_response(&peq[0].1, &freqs) + biquad_analog_response(&peq[1].1, &freqs);
+        let err = (&matched - &analog)
+            .mapv(f64::abs)
+            .fold(0.0, |m: f64, v| m.max(*v));
+        assert!(err < 0.5, "matched peq error {}", err);
+    }
+}
@@ -6,6 +6,32 @@ use ndarray::Array1;
 use std::f64::consts::PI;
 use std::fmt;
 
+mod cascade;
+mod dsp;
+mod export;
+mod matched;
+mod parse;
+mod phase;
+mod resample;
+pub use cascade::{BiquadCascade, CASCADE_LANES};
+pub use dsp::{
+    BiquadCoeffs, CoeffQuantization, DspBank, DspExportConfig, DspTarget, peq_export_dsp,
+    peq_export_dsp_bank,
+};
+pub use export::{
+    EasyEffectsPreset, graphiceq_freqs, peq_format_camilladsp, peq_format_easyeffects,
+    peq_format_graphiceq, peq_format_pipewire,
+};
+pub use matched::{BiquadDesign, biquad_analog_response};
+pub use parse::{
+    peq_parse_apo, peq_parse_apo_stereo, peq_parse_aupreset, peq_parse_rme_channel,
+    peq_parse_rme_room,
+};
+pub use phase::{
+    compute_peq_complex_response, compute_peq_group_delay, compute_peq_phase, unwrap_phase,
+};
+pub use resample::{PeqResample, peq_resample};
+
 // Helper functions translated from the Python script.
 /// Converts bandwidth in octaves to a Q factor.
 pub fn bw2q(bw: f64) -> f64 {
@@ -51,6 +77,20 @@ pub enum BiquadFilterType {
     Lowshelf,
     /// High-shelf filter
     Highshelf,
+    /// First-order low-pass filter (6 dB/octave)
+    LowpassFirstOrder,
+    /// First-order high-pass filter (6 dB/octave)
+    HighpassFirstOrder,
+    /// First-order low-shelf filter (half the gain at the center frequency)
+    LowshelfFirstOrder,
+    /// First-order high-shelf filter (half the gain at the center frequency)
+    HighshelfFirstOrder,
+    /// First-order all-pass filter (90 degrees of phase shift at freq)
+    AllpassFirstOrder,
+    /// Second-order all-pass filter (180 degrees of phase shift at freq)
+    Allpass,
+    /// Linkwitz transform: moves a resonance at freq/q to target_freq/target_q
+    LinkwitzTransform,
 }
 
 impl BiquadFilterType {
@@ -65,9 +105,39 @@ impl BiquadFilterType {
             BiquadFilterType::Notch => "NO",
             BiquadFilterType::Lowshelf => "LS",
             BiquadFilterType::Highshelf => "HS",
+            BiquadFilterType::LowpassFirstOrder => "LP1",
+            BiquadFilterType::HighpassFirstOrder => "HP1",
+            BiquadFilterType::LowshelfFirstOrder => "LS1",
+            BiquadFilterType::HighshelfFirstOrder => "HS1",
+            BiquadFilterType::AllpassFirstOrder => "AP1",
+            BiquadFilterType::Allpass => "AP",
+            BiquadFilterType::LinkwitzTransform => "LT",
         }
     }
 
+    /// Returns the filter type of a short name (e.g., "LP"), the inverse of `short_name`.
+    pub fn from_short_name(name: &str) -> Option<Self> {
+        [
+            BiquadFilterType::Lowpass,
+            BiquadFilterType::Highpass,
+            BiquadFilterType::HighpassVariableQ,
+            BiquadFilterType::Bandpass,
+            BiquadFilterType::Peak,
+            BiquadFilterType::Notch,
+            BiquadFilterType::Lowshelf,
+            BiquadFilterType::Highshelf,
+            BiquadFilterType::LowpassFirstOrder,
+            BiquadFilterType::HighpassFirstOrder,
+            BiquadFilterType::LowshelfFirstOrder,
+            BiquadFilterType::HighshelfFirstOrder,
+            BiquadFilterType::AllpassFirstOrder,
+            BiquadFilterType::Allpass,
+            BiquadFilterType::LinkwitzTransform,
+        ]
+        .into_iter()
+        .find(|filter_type| filter_type.short_name() == name)
+    }
+
     /// Returns the long string representation of the filter type (e.g., "Lowpass").
     pub fn long_name(&self) -> &'static str {
         match self {
@@ -79,8 +149,27 @@ impl BiquadFilterType {
             BiquadFilterType::Notch => "Notch",
             BiquadFilterType::Lowshelf => "Lowshelf",
             BiquadFilterType::Highshelf => "Highshelf",
+            BiquadFilterType::LowpassFirstOrder => "LowpassFirstOrder",
+            BiquadFilterType::HighpassFirstOrder => "HighpassFirstOrder",
+            BiquadFilterType::LowshelfFirstOrder => "LowshelfFirstOrder",
+            BiquadFilterType::HighshelfFirstOrder => "HighshelfFirstOrder",
+            BiquadFilterType::AllpassFirstOrder => "AllpassFirstOrder",
+            BiquadFilterType::Allpass => "Allpass",
+            BiquadFilterType::LinkwitzTransform => "LinkwitzTransform",
         }
     }
+
+    /// Returns true for first-order filters (b2 = a2 = 0, Q is ignored).
+    pub fn is_first_order(&self) -> bool {
+        matches!(
+            self,
+            BiquadFilterType::LowpassFirstOrder
+                | BiquadFilterType::HighpassFirstOrder
+                | BiquadFilterType::LowshelfFirstOrder
+                | BiquadFilterType::HighshelfFirstOrder
+                | BiquadFilterType::AllpassFirstOrder
+        )
+    }
 }
 
 /// Represents a single biquad IIR filter.
@@ -94,8 +183,17 @@ pub struct Biquad {
     pub srate: f64,
     /// Q factor (quality factor)
     pub q: f64,
-    /// Gain in dB (for peaking and shelving filters)
+    /// Gain in dB (for peaking and shelving filters, DC boost for a Linkwitz transform)
     pub db_gain: f64,
+    /// Target resonance frequency in Hz (Linkwitz transform only)
+    #[serde(default)]
+    pub target_freq: f64,
+    /// Target Q factor (Linkwitz transform only)
+    #[serde(default)]
+    pub target_q: f64,
+    /// Coefficient design method
+    #[serde(default)]
+    pub design: BiquadDesign,
     /// Filter coefficients
     a1: f64,
     a2: f64,
@@ -118,13 +216,37 @@ pub struct Biquad {
 
 impl Biquad {
     /// Creates and initializes a new Biquad filter.
+    ///
+    /// For a `LinkwitzTransform`, `freq` and `q` describe the existing
+    /// resonance (f0, Q0) and `db_gain` the boost at DC: the target resonance
+    /// is placed at `freq * 10^(-db_gain / 40)` with a Butterworth Q. Use
+    /// [`Biquad::new_linkwitz_transform`] to choose the target Q as well.
     pub fn new(filter_type: BiquadFilterType, freq: f64, srate: f64, q: f64, db_gain: f64) -> Self {
+        Self::new_with_design(filter_type, freq, srate, q, db_gain, BiquadDesign::Rbj)
+    }
+
+    /// Creates and initializes a new Biquad filter with a given coefficient design.
+    ///
+    /// `BiquadDesign::Vicanek` keeps peaks and shelves close to Nyquist at the
+    /// frequency and gain of the analog prototype, where the RBJ design is
+    /// compressed by the bilinear transform.
+    pub fn new_with_design(
+        filter_type: BiquadFilterType,
+        freq: f64,
+        srate: f64,
+        q: f64,
+        db_gain: f64,
+        design: BiquadDesign,
+    ) -> Self {
         let mut biquad = Biquad {
             filter_type,
             freq,
             srate,
             q,
             db_gain,
+            target_freq: freq * 10.0_f64.powf(-db_gain / 40.0),
+            target_q: DEFAULT_Q_HIGH_LOW_PASS,
+            design,
             a1: 0.0,
             a2: 0.0,
             b0: 0.0,
@@ -149,7 +271,9 @@ impl Biquad {
             match biquad.filter_type {
                 BiquadFilterType::Bandpass
                 | BiquadFilterType::Highpass
-                | BiquadFilterType::Lowpass => {
+                | BiquadFilterType::Lowpass
+                | BiquadFilterType::Allpass
+                | BiquadFilterType::LinkwitzTransform => {
                     biquad.q = DEFAULT_Q_HIGH_LOW_PASS;
                 }
                 BiquadFilterType::Lowshelf | BiquadFilterType::Highshelf => {
@@ -168,7 +292,59 @@ impl Biquad {
         biquad
     }
 
+    /// Creates a Linkwitz transform moving a resonance from f0/Q0 to fp/Qp.
+    ///
+    /// # Arguments
+    /// * `f0` - Resonance frequency of the existing system in Hz
+    /// * `q0` - Q of the existing system
+    /// * `fp` - Target resonance frequency in Hz
+    /// * `qp` - Target Q
+    /// * `srate` - Sample rate in Hz
+    ///
+    /// # Returns
+    /// * Biquad with unity gain at high frequencies and (f0/fp)^2 at DC
+    pub fn new_linkwitz_transform(f0: f64, q0: f64, fp: f64, qp: f64, srate: f64) -> Self {
+        let mut biquad = Biquad::new(
+            BiquadFilterType::LinkwitzTransform,
+            f0,
+            srate,
+            q0,
+            40.0 * (f0 / fp).log10(),
+        );
+        biquad.target_freq = fp;
+        biquad.target_q = if qp > 0.0 {
+            qp
+        } else {
+            DEFAULT_Q_HIGH_LOW_PASS
+        };
+        biquad.compute_coeffs();
+        biquad
+    }
+
+    /// Returns a copy of the filter redesigned for another sample rate and design.
+    ///
+    /// Type, frequency, Q, gain and Linkwitz transform target are kept; the
+    /// processing state is reset.
+    pub fn redesign(&self, srate: f64, design: BiquadDesign) -> Biquad {
+        let mut biquad = self.clone();
+        biquad.srate = srate;
+        biquad.design = design;
+        biquad.x1 = 0.0;
+        biquad.x2 = 0.0;
+        biquad.y1 = 0.0;
+        biquad.y2 = 0.0;
+        biquad.compute_coeffs();
+        biquad
+    }
+
     fn compute_coeffs(&mut self) {
+        if self.design == BiquadDesign::Vicanek
+            && let Some((b0, b1, b2, a1, a2)) = matched::vicanek_coeffs(self)
+        {
+            self.set_coeffs(b0, b1, b2, a1, a2);
+            return;
+        }
+
         // Intermediate variables
         let a = 10.0_f64.powf(self.db_gain / 40.0);
         let omega = 2.0 * PI * self.freq / self.srate;
@@ -237,14 +413,92 @@ impl Biquad {
                 a1 = 2.0 * ((a - 1.0) - (a + 1.0) * cs);
                 a2 = (a + 1.0) - (a - 1.0) * cs - beta * sn;
             }
+            // First-order sections use the bilinear transform with a
+            // prewarped corner k = tan(w/2)
+            BiquadFilterType::LowpassFirstOrder => {
+                let k = (omega / 2.0).tan();
+                b0 = k;
+                b1 = k;
+                b2 = 0.0;
+                a0 = k + 1.0;
+                a1 = k - 1.0;
+                a2 = 0.0;
+            }
+            BiquadFilterType::HighpassFirstOrder => {
+                let k = (omega / 2.0).tan();
+                b0 = 1.0;
+                b1 = -1.0;
+                b2 = 0.0;
+                a0 = k + 1.0;
+                a1 = k - 1.0;
+                a2 = 0.0;
+            }
+            BiquadFilterType::LowshelfFirstOrder => {
+                // H(s) = (s + w*sqrt(G)) / (s + w/sqrt(G)), with sqrt(G) = a
+                let k = (omega / 2.0).tan();
+                b0 = 1.0 + k * a;
+                b1 = k * a - 1.0;
+                b2 = 0.0;
+                a0 = 1.0 + k / a;
+                a1 = k / a - 1.0;
+                a2 = 0.0;
+            }
+            BiquadFilterType::HighshelfFirstOrder => {
+                // H(s) = sqrt(G) * (s*sqrt(G) + w) / (s + w*sqrt(G)), with sqrt(G) = a
+                let k = (omega / 2.0).tan();
+                b0 = a * (a + k);
+                b1 = a * (k - a);
+                b2 = 0.0;
+                a0 = 1.0 + k * a;
+                a1 = k * a - 1.0;
+                a2 = 0.0;
+            }
+            BiquadFilterType::AllpassFirstOrder => {
+                let k = (omega / 2.0).tan();
+                b0 = k - 1.0;
+                b1 = k + 1.0;
+                b2 = 0.0;
+                a0 = k + 1.0;
+                a1 = k - 1.0;
+                a2 = 0.0;
+            }
+            BiquadFilterType::Allpass => {
+                b0 = 1.0 - alpha;
+                b1 = -2.0 * cs;
+                b2 = 1.0 + alpha;
+                a0 = 1.0 + alpha;
+                a1 = -2.0 * cs;
+                a2 = 1.0 - alpha;
+            }
+            BiquadFilterType::LinkwitzTransform => {
+                // H(s) = (s^2 + s*w0/q0 + w0^2) / (s^2 + s*wp/qp + wp^2), both
+                // resonances prewarped before the bilinear transform
+                let k0 = (omega / 2.0).tan();
+                let kp = (PI * self.target_freq / self.srate).tan();
+                let qp = if self.target_q > 0.0 {
+                    self.target_q
+                } else {
+                    self.q
+                };
+                b0 = 1.0 + k0 / self.q + k0 * k0;
+                b1 = 2.0 * (k0 * k0 - 1.0);
+                b2 = 1.0 - k0 / self.q + k0 * k0;
+                a0 = 1.0 + kp / qp + kp * kp;
+                a1 = 2.0 * (kp * kp - 1.0);
+                a2 = 1.0 - kp / qp + kp * kp;
+            }
         }
 
         // Normalize coefficients
-        self.b0 = b0 / a0;
-        self.b1 = b1 / a0;
-        self.b2 = b2 / a0;
-        self.a1 = a1 / a0;
-        self.a2 = a2 / a0;
+        self.set_coeffs(b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0);
+    }
+
+    fn set_coeffs(&mut self, b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) {
+        self.b0 = b0;
+        self.b1 = b1;
+        self.b2 = b2;
+        self.a1 = a1;
+        self.a2 = a2;
 
         // Pre-compute for result()
         self.r_up0 = (self.b0 + self.b1 + self.b2).powi(2);
@@ -371,6 +625,28 @@ pub fn compute_peq_response(freqs: &Array1<f64>, peq: &Peq, _sample_rate: f64) -
     response
 }
 
+/// Compute the combined PEQ response (in dB) with every filter redesigned using `design`.
+///
+/// # Arguments
+/// * `freqs` - Frequency points for evaluation (Hz)
+/// * `peq` - Parametric equalizer containing weighted biquad filters
+/// * `design` - Coefficient design method applied to all filters
+///
+/// # Returns
+/// Frequency response in dB SPL at the specified frequency points
+pub fn compute_peq_response_with_design(
+    freqs: &Array1<f64>,
+    peq: &Peq,
+    design: BiquadDesign,
+) -> Array1<f64> {
+    let mut response = Array1::zeros(freqs.len());
+    for (weight, filter) in peq {
+        let filter = filter.redesign(filter.srate, design);
+        response += &(filter.np_log_result(freqs) * *weight);
+    }
+    response
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,6 +693,107 @@ mod tests {
         }
     }
 
+    #[test]
+    fn first_order_pass_filters_are_3db_down_at_freq() {
+        let lp = Biquad::new(
+            BiquadFilterType::LowpassFirstOrder,
+            1_000.0,
+            48_000.0,
+            0.0,
+            0.0,
+        );
+        let hp = Biquad::new(
+            BiquadFilterType::HighpassFirstOrder,
+            1_000.0,
+            48_000.0,
+            0.0,
+            0.0,
+        );
+        let (_, a2, _, _, b2) = lp.constants();
+        assert_eq!((a2, b2), (0.0, 0.0));
+        assert!(approx_eq(lp.log_result(1_000.0), -3.0103, 1e-3));
+        assert!(approx_eq(hp.log_result(1_000.0), -3.0103, 1e-3));
+        assert!(approx_eq(lp.log_result(10.0), 0.0, 1e-3));
+        assert!(approx_eq(hp.log_result(10.0), -40.0, 0.1));
+        // 6 dB/octave well above the corner
+        let slope = lp.log_result(8_000.0) - lp.log_result(4_000.0);
+        assert!(approx_eq(slope, -6.0, 0.5), "slope {}", slope);
+    }
+
+    #[test]
+    fn first_order_shelves_have_half_gain_at_freq() {
+        let ls = Biquad::new(
+            BiquadFilterType::LowshelfFirstOrder,
+            200.0,
+            48_000.0,
+            0.0,
+            6.0,
+        );
+        let hs = Biquad::new(
+            BiquadFilterType::HighshelfFirstOrder,
+            2_000.0,
+            48_000.0,
+            0.0,
+            -6.0,
+        );
+        assert!(approx_eq(ls.log_result(200.0), 3.0, 1e-3));
+        assert!(approx_eq(ls.log_result(2.0), 6.0, 0.01));
+        assert!(approx_eq(ls.log_result(20_000.0), 0.0, 0.01));
+        assert!(approx_eq(hs.log_result(2_000.0), -3.0, 1e-3));
+        assert!(approx_eq(hs.log_result(20.0), 0.0, 0.01));
+    }
+
+    #[test]
+    fn allpass_filters_have_unity_magnitude() {
+        let freqs = Array1::logspace(10.0, 20.0_f64.log10(), 20_000.0_f64.log10(), 50);
+        for filter_type in [
+            BiquadFilterType::AllpassFirstOrder,
+            BiquadFilterType::Allpass,
+        ] {
+            let bq = Biquad::new(filter_type, 500.0, 48_000.0, 0.0, 0.0);
+            for v in bq.np_log_result(&freqs).iter() {
+                assert!(v.abs() < 1e-6, "{:?}: {} dB", filter_type, v);
+            }
+        }
+    }
+
+    #[test]
+    fn linkwitz_transform_matches_analog_response() {
+        let (f0, q0, fp, qp) = (60.0, 1.1, 30.0, 0.5);
+        let lt = Biquad::new_linkwitz_transform(f0, q0, fp, qp, 48_000.0);
+        assert!(approx_eq(lt.db_gain, 40.0 * 2.0_f64.log10(), 1e-9));
+        let analog = |f: f64| {
+            let h = |fr: f64, q: f64| {
+                let w = f / fr;
+                ((1.0 - w * w).powi(2) + (w / q).powi(2)).sqrt()
+            };
+            20.0 * (h(f0, q0) * (f0 / fp).powi(2) / h(fp, qp)).log10()
+        };
+        let freqs = Array1::logspace(10.0, 10.0_f64.log10(), 2_000.0_f64.log10(), 40);
+        let resp = lt.np_log_result(&freqs);
+        for (f, v) in freqs.iter().zip(resp.iter()) {
+            assert!(
+                approx_eq(*v, analog(*f), 0.05),
+                "{} Hz: {} vs {}",
+                f,
+                v,
+                analog(*f)
+            );
+        }
+        // +12 dB at DC, flat at high frequencies
+        assert!(approx_eq(lt.log_result(1.0), 12.04, 0.05));
+        assert!(approx_eq(lt.log_result(10_000.0), 0.0, 0.01));
+
+        // Biquad::new derives the target frequency from the DC boost
+        let lt = Biquad::new(BiquadFilterType::LinkwitzTransform, f0, 48_000.0, q0, 12.0);
+        assert!(approx_eq(
+            lt.target_freq,
+            f0 * 10.0_f64.powf(-12.0 / 40.0),
+            1e-9
+        ));
+        assert!(approx_eq(lt.target_q, DEFAULT_Q_HIGH_LOW_PASS, 1e-12));
+    }
+
     #[test]
     fn test_a_weighting() {
         // Test A-weighting at specific frequencies
@@ -839,9 +1216,44 @@ pub fn peq_preamp_gain_max(peq: &Peq) -> f64 {
 ///
 /// # Returns
 /// * String formatted for EqualizerAPO
+///
+/// # Notes
+/// Filters with the Vicanek design are written as raw `IIR` coefficients,
+/// preceded by a `# Vicanek:` comment holding their parameters.
 pub fn peq_format_apo(comment: &str, peq: &Peq) -> String {
-    let mut res = Vec::new();
-    res.push(comment.to_string());
+    let mut res = vec![comment.to_string()];
+    apo_push_channel(&mut res, peq);
+    res.push(String::new());
+    res.join("\n")
+}
+
+/// Format a stereo PEQ as APO configuration string
+///
+/// # Arguments
+/// * `comment` - Comment string to include at the top
+/// * `left` - PEQ of the left channel
+/// * `right` - PEQ of the right channel
+///
+/// # Returns
+/// * String formatted for EqualizerAPO, with a `Channel:` block per side
+///
+/// # Notes
+/// Each side has its own preamp. The configuration ends with `Channel: all`
+/// so that commands appended or included afterwards apply to every channel.
+pub fn peq_format_apo_stereo(comment: &str, left: &Peq, right: &Peq) -> String {
+    let mut res = vec![comment.to_string()];
+    for (channel, peq) in [("L", left), ("R", right)] {
+        res.push(format!("Channel: {}", channel));
+        apo_push_channel(&mut res, peq);
+        res.push(String::new());
+    }
+    res.push("Channel: all".to_string());
+    res.push(String::new());
+    res.join("\n")
+}
+
+/// Append the preamp and filter lines of a PEQ to an APO configuration
+fn apo_push_channel(res: &mut Vec<String>, peq: &Peq) {
     res.push(format!("Preamp: {:.1} dB", peq_preamp_gain(peq)));
     res.push(String::new());
 
@@ -850,6 +1262,21 @@ pub fn peq_format_apo(comment: &str, peq: &Peq) -> String {
     sorted_peq.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
 
     for (i, (_, iir)) in sorted_peq.iter().enumerate() {
+        if iir.design == BiquadDesign::Vicanek {
+            // EqualizerAPO designs its filter types with RBJ: keep the matched
+            // coefficients, and the parameters in a comment for `peq_parse_apo`
+            res.push(format!(
+                "# Vicanek: {} {} {} {} {} {}",
+                iir.filter_type.short_name(),
+                iir.freq,
+                iir.q,
+                iir.db_gain,
+                iir.target_freq,
+                iir.target_q
+            ));
+            res.push(apo_coefficients_line(i, iir));
+            continue;
+        }
         match iir.filter_type {
             BiquadFilterType::Peak | BiquadFilterType::Notch | BiquadFilterType::Bandpass => {
                 res.push(format!(
@@ -897,11 +1324,56 @@ pub fn peq_format_apo(comment: &str, peq: &Peq) -> String {
                     iir.q
                 ));
             }
+            BiquadFilterType::LowshelfFirstOrder | BiquadFilterType::HighshelfFirstOrder => {
+                res.push(format!(
+                    "Filter {:2}: ON {} 6dB Fc {:5} Hz Gain {:+0.2} dB",
+                    i + 1,
+                    &iir.filter_type.short_name()[..2],
+                    iir.freq as i32,
+                    iir.db_gain
+                ));
+            }
+            BiquadFilterType::Allpass => {
+                res.push(format!(
+                    "Filter {:2}: ON AP Fc {:5} Hz Q {:0.2}",
+                    i + 1,
+                    iir.freq as i32,
+                    iir.q
+                ));
+            }
+            BiquadFilterType::LowpassFirstOrder
+            | BiquadFilterType::HighpassFirstOrder
+            | BiquadFilterType::AllpassFirstOrder
+            | BiquadFilterType::LinkwitzTransform => {
+                // No dedicated APO keyword: write the raw coefficients
+                res.push(apo_coefficients_line(i, iir));
+            }
         }
     }
+}
 
-    res.push(String::new());
-    res.join("\n")
+/// Format the `i`-th filter as an APO `IIR` line with its raw coefficients
+fn apo_coefficients_line(i: usize, iir: &Biquad) -> String {
+    let (a1, a2, b0, b1, b2) = iir.constants();
+    if iir.filter_type.is_first_order() {
+        format!(
+            "Filter {:2}: ON IIR Order 1 Coefficients {} {} 1 {}",
+            i + 1,
+            b0,
+            b1,
+            a1
+        )
+    } else {
+        format!(
+            "Filter {:2}: ON IIR Order 2 Coefficients {} {} {} 1 {} {}",
+            i + 1,
+            b0,
+            b1,
+            b2,
+            a1,
+            a2
+        )
+    }
 }
 
 /// Compute Q values for Butterworth filters
@@ -941,10 +1413,13 @@ pub fn peq_butterworth_lowpass(order: usize, freq: f64, srate: f64) -> Peq {
     q_values
         .into_iter()
         .map(|q| {
-            (
-                1.0,
-                Biquad::new(BiquadFilterType::Lowpass, freq, srate, q, 0.0),
-            )
+            // odd orders end with a first-order section, marked by a negative Q
+            let filter_type = if q < 0.0 {
+                BiquadFilterType::LowpassFirstOrder
+            } else {
+                BiquadFilterType::Lowpass
+            };
+            (1.0, Biquad::new(filter_type, freq, srate, q.abs(), 0.0))
         })
         .collect()
 }
@@ -963,10 +1438,13 @@ pub fn peq_butterworth_highpass(order: usize, freq: f64, srate: f64) -> Peq {
     q_values
         .into_iter()
         .map(|q| {
-            (
-                1.0,
-                Biquad::new(BiquadFilterType::Highpass, freq, srate, q, 0.0),
-            )
+            // odd orders end with a first-order section, marked by a negative Q
+            let filter_type = if q < 0.0 {
+                BiquadFilterType::HighpassFirstOrder
+            } else {
+                BiquadFilterType::Highpass
+            };
+            (1.0, Biquad::new(filter_type, freq, srate, q.abs(), 0.0))
         })
         .collect()
 }
@@ -1169,6 +1647,20 @@ mod peq_tests {
         }
     }
 
+    #[test]
+    fn test_butterworth_odd_order() {
+        let lp = peq_butterworth_lowpass(3, 1000.0, 48000.0);
+        let hp = peq_butterworth_highpass(3, 1000.0, 48000.0);
+        assert_eq!(lp[1].1.filter_type, BiquadFilterType::LowpassFirstOrder);
+        assert_eq!(hp[1].1.filter_type, BiquadFilterType::HighpassFirstOrder);
+
+        // -3 dB at the cutoff and 18 dB/octave further out
+        let freqs = array![1000.0, 8000.0];
+        let lp_db = compute_peq_response(&freqs, &lp, 48000.0);
+        assert!((lp_db[0] + 3.01).abs() < 0.05, "{}", lp_db[0]);
+        assert!(lp_db[1] < -50.0, "{}", lp_db[1]);
+    }
+
     #[test]
     fn test_linkwitzriley_filters() {
         let lp = peq_linkwitzriley_lowpass(4, 1000.0, 48000.0);
@@ -1619,9 +2111,7 @@ const K_AUNBANDEQ_PARAM_BANDWIDTH: i32 = 5000;
 
 // Apple AUNBandEQ filter type constants
 const K_AUNBANDEQ_FILTER_TYPE_PARAMETRIC: i32 = 0;
-#[allow(dead_code)]
 const K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_LOW_PASS: i32 = 1;
-#[allow(dead_code)]
 const K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_HIGH_PASS: i32 = 2;
 const K_AUNBANDEQ_FILTER_TYPE_RESONANT_LOW_PASS: i32 = 3;
 const K_AUNBANDEQ_FILTER_TYPE_RESONANT_HIGH_PASS: i32 = 4;
@@ -0,0 +1,980 @@
+// ----------------------------------------------------------------------
+// Preset Parsers (EqualizerAPO, RME TotalMix, Apple AUNBandEQ)
+// ----------------------------------------------------------------------
+//
+// Each parser is the inverse of the matching `peq_format_*` writer in the
+// crate root. Presets do not carry a sample rate, so the caller provides the
+// one used to build the biquads.
+
+use super::{
+    Biquad, BiquadDesign, BiquadFilterType,
+    K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_HIGH_PASS,
+    K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_LOW_PASS, K_AUNBANDEQ_FILTER_TYPE_BAND_PASS,
+    K_AUNBANDEQ_FILTER_TYPE_HIGH_SHELF, K_AUNBANDEQ_FILTER_TYPE_LOW_SHELF,
+    K_AUNBANDEQ_FILTER_TYPE_PARAMETRIC, K_AUNBANDEQ_FILTER_TYPE_RESONANT_HIGH_PASS,
+    K_AUNBANDEQ_FILTER_TYPE_RESONANT_LOW_PASS, K_AUNBANDEQ_PARAM_BANDWIDTH,
+    K_AUNBANDEQ_PARAM_BYPASS_BAND, K_AUNBANDEQ_PARAM_FILTER_TYPE, K_AUNBANDEQ_PARAM_FREQUENCY,
+    K_AUNBANDEQ_PARAM_GAIN, Peq, bw2q,
+};
+use base64::{Engine as _, engine::general_purpose};
+use byteorder::{BigEndian, ReadBytesExt};
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+// Apple AUNBandEQ filter types that we never write but may find in presets
+// produced by other tools.
+const K_AUNBANDEQ_FILTER_TYPE_BAND_STOP: i32 = 6;
+const K_AUNBANDEQ_FILTER_TYPE_RESONANT_LOW_SHELF: i32 = 9;
+const K_AUNBANDEQ_FILTER_TYPE_RESONANT_HIGH_SHELF: i32 = 10;
+
+/// Convert an EqualizerAPO filter keyword to a BiquadFilterType
+///
+/// # Arguments
+/// * `keyword` - Filter type as written after `ON` (e.g. "PK", "HPQ", "LSC")
+///
+/// # Returns
+/// * The matching filter type, or None if the keyword is not supported
+fn apo_keyword_to_biquad(keyword: &str) -> Option<BiquadFilterType> {
+    match keyword.to_ascii_uppercase().as_str() {
+        "PK" | "PEQ" => Some(BiquadFilterType::Peak),
+        "LP" | "LPQ" => Some(BiquadFilterType::Lowpass),
+        "HP" => Some(BiquadFilterType::Highpass),
+        "HPQ" => Some(BiquadFilterType::HighpassVariableQ),
+        "BP" => Some(BiquadFilterType::Bandpass),
+        "NO" => Some(BiquadFilterType::Notch),
+        "LS" | "LSC" => Some(BiquadFilterType::Lowshelf),
+        "HS" | "HSC" => Some(BiquadFilterType::Highshelf),
+        "AP" => Some(BiquadFilterType::Allpass),
+        _ => None,
+    }
+}
+
+fn parse_number(token: Option<&&str>, what: &str, line: &str) -> Result<f64, String> {
+    let token = token.ok_or_else(|| format!("missing {} value in line '{}'", what, line))?;
+    token
+        .parse::<f64>()
+        .map_err(|_| format!("invalid {} value '{}' in line '{}'", what, token, line))
+}
+
+/// Parse an EqualizerAPO configuration into a PEQ
+///
+/// # Arguments
+/// * `text` - Content of an EqualizerAPO configuration file
+/// * `srate` - Sample rate in Hz used to build the biquads
+///
+/// # Returns
+/// * PEQ vector with one biquad per enabled filter line, or an error message
+///
+/// # Notes
+/// Accepts the output of `peq_format_apo` as well as hand-written files:
+/// - `Filter N:` and `Filter:` prefixes are both recognized
+/// - `OFF` filters, comments, `Preamp:` and other commands are skipped
+/// - Q may be given as `Q x` or as a bandwidth `BW Oct x`
+/// - A missing Q keeps the default Q of the filter type
+///
+/// `peq_format_apo` writes a Highpass with a non-default Q as `HPQ`, so such a
+/// filter comes back as `HighpassVariableQ` which has the same coefficients.
+/// Filters written as raw `IIR` coefficients (first-order low/high/all-pass,
+/// Linkwitz transforms and the Vicanek design) are mapped back to their type;
+/// other coefficient sets are reported as errors.
+pub fn peq_parse_apo(text: &str, srate: f64) -> Result<Peq, String> {
+    let mut peq = Peq::new();
+    let mut vicanek = None;
+
+    for raw in text.lines() {
+        let line = raw.trim();
+        let hint = vicanek.take();
+        if let Some(params) = line.strip_prefix(APO_VICANEK_PREFIX) {
+            vicanek = Some(params);
+            continue;
+        }
+        if let Some(biquad) = apo_filter_line(line, srate, hint)? {
+            peq.push((1.0, biquad));
+        }
+    }
+
+    Ok(peq)
+}
+
+/// Parse an EqualizerAPO configuration into left and right PEQs
+///
+/// # Arguments
+/// * `text` - Content of an EqualizerAPO configuration file
+/// * `srate` - Sample rate in Hz used to build the biquads
+///
+/// # Returns
+/// * Tuple of (left, right) PEQ vectors, or an error message
+///
+/// # Notes
+/// Inverse of `peq_format_apo_stereo`. Filters apply to the channels selected
+/// by the last `Channel:` command (`L`/`1`, `R`/`2` or `all`); filters before
+/// any `Channel:` command apply to both sides, and filters of other channels
+/// (center, surrounds, ...) are ignored. A configuration without `Channel:`
+/// commands gives the same PEQ on both sides.
+pub fn peq_parse_apo_stereo(text: &str, srate: f64) -> Result<(Peq, Peq), String> {
+    let mut left = Peq::new();
+    let mut right = Peq::new();
+    let (mut to_left, mut to_right) = (true, true);
+    let mut vicanek = None;
+
+    for raw in text.lines() {
+        let line = raw.trim();
+        let hint = vicanek.take();
+        if let Some(params) = line.strip_prefix(APO_VICANEK_PREFIX) {
+            vicanek = Some(params);
+            continue;
+        }
+        if let Some(channels) = line.strip_prefix("Channel:") {
+            (to_left, to_right) = (false, false);
+            for channel in channels.split_whitespace() {
+                match channel.to_ascii_uppercase().as_str() {
+                    "ALL" => (to_left, to_right) = (true, true),
+                    "L" | "1" => to_left = true,
+                    "R" | "2" => to_right = true,
+                    _ => {}
+                }
+            }
+            continue;
+        }
+        if let Some(biquad) = apo_filter_line(line, srate, hint)? {
+            if to_left {
+                left.push((1.0, biquad.clone()));
+            }
+            if to_right {
+                right.push((1.0, biquad));
+            }
+        }
+    }
+
+    Ok((left, right))
+}
+
+/// Parse one line of an EqualizerAPO configuration
+///
+/// # Arguments
+/// * `line` - Trimmed line
+/// * `srate` - Sample rate in Hz used to build the biquad
+/// * `vicanek` - Parameters of the `# Vicanek:` comment just above, if any
+///
+/// # Returns
+/// * The biquad of an enabled `Filter` line, None for any other line, or an
+///   error message
+fn apo_filter_line(
+    line: &str,
+    srate: f64,
+    vicanek: Option<&str>,
+) -> Result<Option<Biquad>, String> {
+    let Some(rest) = line.strip_prefix("Filter") else {
+        return Ok(None);
+    };
+    let Some((_, rest)) = rest.split_once(':') else {
+        return Ok(None);
+    };
+
+    let tokens: Vec<&str> = rest.split_whitespace().collect();
+    match tokens.first() {
+        Some(state) if state.eq_ignore_ascii_case("ON") => {}
+        Some(state) if state.eq_ignore_ascii_case("OFF") => return Ok(None),
+        _ => return Err(format!("missing ON/OFF state in line '{}'", line)),
+    }
+    let keyword = tokens
+        .get(1)
+        .ok_or_else(|| format!("missing filter type in line '{}'", line))?;
+    if keyword.eq_ignore_ascii_case("IIR") {
+        return apo_iir_line(&tokens, line, srate, vicanek).map(Some);
+    }
+    let mut filter_type = apo_keyword_to_biquad(keyword)
+        .ok_or_else(|| format!("unsupported filter type '{}' in line '{}'", keyword, line))?;
+    // `LS 6dB` / `HS 6dB` are the first-order shelves
+    if tokens
+        .get(2)
+        .is_some_and(|slope| slope.eq_ignore_ascii_case("6dB"))
+    {
+        filter_type = match filter_type {
+            BiquadFilterType::Lowshelf => BiquadFilterType::LowshelfFirstOrder,
+            BiquadFilterType::Highshelf => BiquadFilterType::HighshelfFirstOrder,
+            other => other,
+        };
+    }
+
+    let mut freq = None;
+    let mut gain = 0.0;
+    let mut q = 0.0;
+    let mut i = 2;
+    while i < tokens.len() {
+        match tokens[i] {
+            "Fc" => {
+                freq = Some(parse_number(tokens.get(i + 1), "Fc", line)?);
+                i += 2;
+            }
+            "Gain" => {
+                gain = parse_number(tokens.get(i + 1), "Gain", line)?;
+                i += 2;
+            }
+            "Q" => {
+                q = parse_number(tokens.get(i + 1), "Q", line)?;
+                i += 2;
+            }
+            "BW" => {
+                // BW Oct <value>
+                let bw = parse_number(tokens.get(i + 2), "BW", line)?;
+                q = bw2q(bw);
+                i += 3;
+            }
+            _ => i += 1,
+        }
+    }
+
+    let freq = freq.ok_or_else(|| format!("missing Fc in line '{}'", line))?;
+    Ok(Some(Biquad::new(filter_type, freq, srate, q, gain)))
+}
+
+/// Comment written by `peq_format_apo` above the coefficients of a Vicanek filter
+const APO_VICANEK_PREFIX: &str = "# Vicanek:";
+
+/// Rebuild a Vicanek filter from the parameters of its `# Vicanek:` comment
+///
+/// # Arguments
+/// * `params` - Filter type short name, freq, Q, gain, target freq and target Q
+/// * `srate` - Sample rate in Hz used to build the biquad
+///
+/// # Returns
+/// * The filter, or None if the parameters are malformed
+fn apo_vicanek_filter(params: &str, srate: f64) -> Option<Biquad> {
+    let tokens: Vec<&str> = params.split_whitespace().collect();
+    let filter_type = BiquadFilterType::from_short_name(tokens.first()?)?;
+    let values = tokens[1..]
+        .iter()
+        .map(|t| t.parse::<f64>().ok())
+        .collect::<Option<Vec<f64>>>()?;
+    let &[freq, q, gain, target_freq, target_q] = values.as_slice() else {
+        return None;
+    };
+    let biquad = if filter_type == BiquadFilterType::LinkwitzTransform {
+        Biquad::new_linkwitz_transform(freq, q, target_freq, target_q, srate)
+    } else {
+        Biquad::new(filter_type, freq, srate, q, gain)
+    };
+    Some(biquad.redesign(srate, BiquadDesign::Vicanek))
+}
+
+/// Parse an `IIR Order N Coefficients b... a...` filter line
+///
+/// # Arguments
+/// * `tokens` - Whitespace separated tokens after `Filter N:`
+/// * `line` - Full line, for error messages
+/// * `srate` - Sample rate in Hz used to build the biquad
+/// * `vicanek` - Parameters of the `# Vicanek:` comment just above, if any
+///
+/// # Returns
+/// * The filter whose coefficients match, or an error message
+///
+/// # Notes
+/// `peq_format_apo` writes first-order low/high/all-pass filters as order 1
+/// and Linkwitz transforms as order 2. Their frequency (and Q) are recovered
+/// by inverting the bilinear transform, then the filter is rebuilt and kept
+/// only if its coefficients match the line. Vicanek filters are rebuilt from
+/// their comment and checked the same way.
+fn apo_iir_line(
+    tokens: &[&str],
+    line: &str,
+    srate: f64,
+    vicanek: Option<&str>,
+) -> Result<Biquad, String> {
+    let order = parse_number(tokens.get(3), "Order", line)?;
+    let values = tokens
+        .iter()
+        .skip(5)
+        .map(|t| {
+            t.parse::<f64>()
+                .map_err(|_| format!("invalid coefficient '{}' in line '{}'", t, line))
+        })
+        .collect::<Result<Vec<f64>, String>>()?;
+    let unsupported = || format!("unsupported IIR coefficients in line '{}'", line);
+    // tan(w/2) of a prewarped corner back to a frequency
+    let freq_of = |k: f64| srate * k.atan() / std::f64::consts::PI;
+
+    let (mut candidates, expected) = match (order as i32, values.as_slice()) {
+        (1, &[b0, b1, a0, a1]) if a0 != 0.0 => {
+            let (b0, b1, a1) = (b0 / a0, b1 / a0, a1 / a0);
+            // a1 = (k - 1) / (k + 1)
+            let freq = freq_of((1.0 + a1) / (1.0 - a1));
+            let expected = [b0, b1, 0.0, a1, 0.0];
+            let candidates: Vec<Biquad> = [
+                BiquadFilterType::LowpassFirstOrder,
+                BiquadFilterType::HighpassFirstOrder,
+                BiquadFilterType::AllpassFirstOrder,
+            ]
+            .into_iter()
+            .map(|filter_type| Biquad::new(filter_type, freq, srate, 0.0, 0.0))
+            .collect();
+            (candidates, expected)
+        }
+        (2, &[b0, b1, b2, a0, a1, a2]) if a0 != 0.0 => {
+            let (b0, b1, b2, a1, a2) = (b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0);
+            // For p(z) = (1 + k/q + k^2) + 2(k^2 - 1) z^-1 + (1 - k/q + k^2) z^-2:
+            // p(1) = 4k^2, p(-1) = 4 and p0 - p2 = 2k/q, up to a common scale
+            let resonance = |p0: f64, p1: f64, p2: f64| {
+                let k = ((p0 + p1 + p2) / (p0 - p1 + p2)).sqrt();
+                (freq_of(k), k * (p0 - p1 + p2) / (2.0 * (p0 - p2)))
+            };
+            let (f0, q0) = resonance(b0, b1, b2);
+            let (fp, qp) = resonance(1.0, a1, a2);
+            let expected = [b0, b1, b2, a1, a2];
+            (
+                vec![Biquad::new_linkwitz_transform(f0, q0, fp, qp, srate)],
+                expected,
+            )
+        }
+        _ => return Err(unsupported()),
+    };
+    if let Some(biquad) = vicanek.and_then(|params| apo_vicanek_filter(params, srate)) {
+        candidates.insert(0, biquad);
+    }
+
+    candidates
+        .into_iter()
+        .find(|biquad| {
+            let (a1, a2, b0, b1, b2) = biquad.constants();
+            [b0, b1, b2, a1, a2]
+                .iter()
+                .zip(expected.iter())
+                .all(|(got, want)| (got - want).abs() < 1e-9)
+        })
+        .ok_or_else(unsupported)
+}
+
+/// Extract all `<val e="name" v="value,"/>` entries from an RME preset
+///
+/// # Arguments
+/// * `text` - Content (or a section) of an RME TotalMix preset
+///
+/// # Returns
+/// * List of (name, value) pairs in document order, or an error message
+fn rme_values(text: &str) -> Result<Vec<(String, f64)>, String> {
+    let mut values = Vec::new();
+
+    for raw in text.lines() {
+        let line = raw.trim();
+        let Some(rest) = line.strip_prefix("<val e=\"") else {
+            continue;
+        };
+        let Some((name, rest)) = rest.split_once('"') else {
+            return Err(format!("malformed RME entry '{}'", line));
+        };
+        let Some(rest) = rest.trim_start().strip_prefix("v=\"") else {
+            return Err(format!("missing value in RME entry '{}'", line));
+        };
+        let Some((value, _)) = rest.split_once('"') else {
+            return Err(format!("malformed RME entry '{}'", line));
+        };
+        let value = value.trim().trim_end_matches(',').trim();
+        let value = value
+            .parse::<f64>()
+            .map_err(|_| format!("invalid value '{}' in RME entry '{}'", value, line))?;
+        values.push((name.to_string(), value));
+    }
+
+    Ok(values)
+}
+
+/// Convert an RME format code back to a BiquadFilterType
+///
+/// # Arguments
+/// * `code` - RME type code
+/// * `pos` - The position (1-based index) of the band in the chain
+///
+/// # Returns
+/// * The matching filter type, or None if the code is unknown
+///
+/// # Notes
+/// Inverse of `biquad_to_rme_type`. RME uses a single shelf code, so a shelf
+/// at position 1 is read as a Lowshelf and anywhere else as a Highshelf.
+fn rme_type_to_biquad(code: f64, pos: usize) -> Option<BiquadFilterType> {
+    match code.round() as i32 {
+        0 => Some(BiquadFilterType::Peak),
+        1 if pos == 1 => Some(BiquadFilterType::Lowshelf),
+        1 => Some(BiquadFilterType::Highshelf),
+        2 if pos == 1 => Some(BiquadFilterType::Highpass),
+        2 => Some(BiquadFilterType::Lowpass),
+        3 if pos == 1 => Some(BiquadFilterType::Lowpass),
+        3 => Some(BiquadFilterType::Highpass),
+        _ => None,
+    }
+}
+
+/// Fields of one RME band collected from `<val>` entries
+#[derive(Default)]
+struct RmeBand {
+    freq: Option<f64>,
+    q: Option<f64>,
+    gain: f64,
+    code: Option<f64>,
+}
+
+/// Build a PEQ from RME band entries sharing a common name prefix
+///
+/// # Arguments
+/// * `values` - (name, value) pairs from `rme_values`
+/// * `prefix` - Band name prefix ("Band" for channel EQ, "REQ Band" for room EQ)
+/// * `srate` - Sample rate in Hz used to build the biquads
+///
+/// # Returns
+/// * PEQ vector ordered by band number, or an error message
+fn rme_bands_to_peq(values: &[(String, f64)], prefix: &str, srate: f64) -> Result<Peq, String> {
+    let mut bands: BTreeMap<usize, RmeBand> = BTreeMap::new();
+
+    for (name, value) in values {
+        let Some(rest) = name.strip_prefix(prefix) else {
+            continue;
+        };
+        let Some((number, field)) = rest.split_once(' ') else {
+            continue;
+        };
+        let Ok(number) = number.parse::<usize>() else {
+            continue;
+        };
+        let band = bands.entry(number).or_default();
+        match field {
+            "Freq" => band.freq = Some(*value),
+            "Q" => band.q = Some(*value),
+            "Gain" => band.gain = *value,
+            "Type" => band.code = Some(*value),
+            _ => {}
+        }
+    }
+
+    let mut peq = Peq::new();
+    for (number, band) in bands {
+        let freq = band
+            .freq
+            .ok_or_else(|| format!("{}{} has no Freq", prefix, number))?;
+        let filter_type = match band.code {
+            Some(code) => rme_type_to_biquad(code, number)
+                .ok_or_else(|| format!("{}{} has unsupported Type {}", prefix, number, code))?,
+            None => BiquadFilterType::Peak,
+        };
+        peq.push((
+            1.0,
+            Biquad::new(filter_type, freq, srate, band.q.unwrap_or(0.0), band.gain),
+        ));
+    }
+
+    Ok(peq)
+}
+
+/// Parse an RME TotalMix channel EQ preset into a PEQ
+///
+/// # Arguments
+/// * `text` - Content of a TotalMix channel preset (`.tmeq`)
+/// * `srate` - Sample rate in Hz used to build the biquads
+///
+/// # Returns
+/// * PEQ vector ordered by band number, or an error message
+///
+/// # Notes
+/// Inverse of `peq_format_rme_channel`. The low cut (`LC Grade`, `LC Freq`)
+/// is not part of the PEQ and is ignored.
+pub fn peq_parse_rme_channel(text: &str, srate: f64) -> Result<Peq, String> {
+    if !text.contains("<Preset>") {
+        return Err("not an RME TotalMix preset: missing <Preset>".to_string());
+    }
+    let values = rme_values(text)?;
+    rme_bands_to_peq(&values, "Band", srate)
+}
+
+/// Extract the content between `<tag>` and `</tag>`
+fn section<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = text.find(&open)? + open.len();
+    let end = start + text[start..].find(&close)?;
+    Some(&text[start..end])
+}
+
+/// Parse an RME TotalMix room EQ preset into left and right PEQs
+///
+/// # Arguments
+/// * `text` - Content of a TotalMix room EQ preset (`.tmreq`)
+/// * `srate` - Sample rate in Hz used to build the biquads
+///
+/// # Returns
+/// * Tuple of (left, right) PEQ vectors, or an error message
+///
+/// # Notes
+/// Inverse of `peq_format_rme_room`. The writer always emits 9 bands and pads
+/// unused slots with 0 dB peaks; those have no effect and are dropped. If the
+/// preset only contains a left channel, it is used for both sides.
+pub fn peq_parse_rme_room(text: &str, srate: f64) -> Result<(Peq, Peq), String> {
+    let parse_channel = |body: &str| -> Result<Peq, String> {
+        let values = rme_values(body)?;
+        let peq = rme_bands_to_peq(&values, "REQ Band", srate)?;
+        Ok(peq
+            .into_iter()
+            .filter(|(_, bq)| bq.filter_type != BiquadFilterType::Peak || bq.db_gain != 0.0)
+            .collect())
+    };
+
+    let left = section(text, "Room EQ L")
+        .ok_or_else(|| "not an RME room EQ preset: missing <Room EQ L>".to_string())?;
+    let left = parse_channel(left)?;
+    let right = match section(text, "Room EQ R") {
+        Some(body) => parse_channel(body)?,
+        None => left.clone(),
+    };
+
+    Ok((left, right))
+}
+
+/// Convert an Apple AUNBandEQ filter type constant to a BiquadFilterType
+///
+/// # Arguments
+/// * `code` - Apple AUNBandEQ filter type constant
+///
+/// # Returns
+/// * The matching filter type, or None if unsupported
+///
+/// # Notes
+/// Inverse of `biquad_to_apple_type`. Apple has both Butterworth and resonant
+/// variants of the pass filters: they map to the same biquad type, the
+/// Butterworth ones with the default Q.
+fn apple_type_to_biquad(code: i32) -> Option<BiquadFilterType> {
+    match code {
+        K_AUNBANDEQ_FILTER_TYPE_PARAMETRIC => Some(BiquadFilterType::Peak),
+        K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_LOW_PASS
+        | K_AUNBANDEQ_FILTER_TYPE_RESONANT_LOW_PASS => Some(BiquadFilterType::Lowpass),
+        K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_HIGH_PASS
+        | K_AUNBANDEQ_FILTER_TYPE_RESONANT_HIGH_PASS => Some(BiquadFilterType::Highpass),
+        K_AUNBANDEQ_FILTER_TYPE_BAND_PASS => Some(BiquadFilterType::Bandpass),
+        K_AUNBANDEQ_FILTER_TYPE_BAND_STOP => Some(BiquadFilterType::Notch),
+        K_AUNBANDEQ_FILTER_TYPE_LOW_SHELF | K_AUNBANDEQ_FILTER_TYPE_RESONANT_LOW_SHELF => {
+            Some(BiquadFilterType::Lowshelf)
+        }
+        K_AUNBANDEQ_FILTER_TYPE_HIGH_SHELF | K_AUNBANDEQ_FILTER_TYPE_RESONANT_HIGH_SHELF => {
+            Some(BiquadFilterType::Highshelf)
+        }
+        _ => None,
+    }
+}
+
+/// Parse an Apple AUNBandEQ preset (aupreset) into a PEQ
+///
+/// # Arguments
+/// * `text` - Content of an `.aupreset` plist XML file
+/// * `srate` - Sample rate in Hz used to build the biquads
+///
+/// # Returns
+/// * PEQ vector ordered by band index, or an error message
+///
+/// # Notes
+/// Inverse of `peq_format_aupreset`: decodes the base64 `data` blob, skips the
+/// 20 byte header and reads (param_id, value) pairs. Bypassed bands are dropped
+/// and bandwidths (octaves) are converted back to Q. Values are stored as f32
+/// so they come back with single precision.
+pub fn peq_parse_aupreset(text: &str, srate: f64) -> Result<Peq, String> {
+    let data = section(text, "data")
+        .ok_or_else(|| "not an aupreset: missing <data> section".to_string())?;
+    let b64: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = general_purpose::STANDARD
+        .decode(b64.as_bytes())
+        .map_err(|e| format!("invalid base64 in aupreset data: {}", e))?;
+
+    const HEADER_LEN: usize = 20;
+    if bytes.len() < HEADER_LEN || !(bytes.len() - HEADER_LEN).is_multiple_of(8) {
+        return Err(format!(
+            "unexpected aupreset data length {} bytes",
+            bytes.len()
+        ));
+    }
+
+    let mut params: BTreeMap<i32, f32> = BTreeMap::new();
+    let mut cursor = Cursor::new(&bytes[HEADER_LEN..]);
+    while (cursor.position() as usize) < bytes.len() - HEADER_LEN {
+        let param_id = cursor
+            .read_i32::<BigEndian>()
+            .map_err(|e| format!("truncated aupreset data: {}", e))?;
+        let value = cursor
+            .read_f32::<BigEndian>()
+            .map_err(|e| format!("truncated aupreset data: {}", e))?;
+        params.insert(param_id, value);
+    }
+
+    let mut peq = Peq::new();
+    for idx in 0..16 {
+        let get = |base: i32| params.get(&(base + idx)).map(|v| *v as f64);
+        let Some(freq) = get(K_AUNBANDEQ_PARAM_FREQUENCY) else {
+            continue;
+        };
+        if get(K_AUNBANDEQ_PARAM_BYPASS_BAND).unwrap_or(0.0) != 0.0 {
+            continue;
+        }
+        let code = get(K_AUNBANDEQ_PARAM_FILTER_TYPE).unwrap_or(0.0) as i32;
+        let filter_type = apple_type_to_biquad(code)
+            .ok_or_else(|| format!("band {} has unsupported filter type {}", idx + 1, code))?;
+        let q = match filter_type {
+            BiquadFilterType::Lowpass | BiquadFilterType::Highpass
+                if code == K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_LOW_PASS
+                    || code == K_AUNBANDEQ_FILTER_TYPE_2ND_ORDER_BUTTERWORTH_HIGH_PASS =>
+            {
+                0.0
+            }
+            _ => match get(K_AUNBANDEQ_PARAM_BANDWIDTH) {
+                Some(bw) if bw > 0.0 => bw2q(bw),
+                _ => 0.0,
+            },
+        };
+        let gain = get(K_AUNBANDEQ_PARAM_GAIN).unwrap_or(0.0);
+        peq.push((1.0, Biquad::new(filter_type, freq, srate, q, gain)));
+    }
+
+    Ok(peq)
+}
+
+#[cfg(test)]
+mod parse_tests {
+    use super::*;
+    use crate::{
+        DEFAULT_Q_HIGH_LOW_PASS, peq_format_apo, peq_format_apo_stereo, peq_format_aupreset,
+        peq_format_rme_channel, peq_format_rme_room,
+    };
+
+    fn assert_biquad_close(got: &Biquad, expected: &Biquad, tol: f64) {
+        assert_eq!(got.filter_type, expected.filter_type);
+        assert!(
+            (got.freq - expected.freq).abs() < tol,
+            "freq {} vs {}",
+            got.freq,
+            expected.freq
+        );
+        assert!(
+            (got.q - expected.q).abs() < tol,
+            "q {} vs {}",
+            got.q,
+            expected.q
+        );
+        assert!(
+            (got.db_gain - expected.db_gain).abs() < tol,
+            "gain {} vs {}",
+            got.db_gain,
+            expected.db_gain
+        );
+    }
+
+    fn sample_peq() -> Peq {
+        vec![
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Lowshelf, 105.0, 48000.0, 0.8, 2.5),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 350.0, 48000.0, 1.5, -3.25),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 2200.0, 48000.0, 4.0, 1.75),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highshelf, 9000.0, 48000.0, 0.7, -1.5),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_apo_round_trip() {
+        let mut peq = sample_peq();
+        peq.push((
+            1.0,
+            Biquad::new(
+                BiquadFilterType::Highpass,
+                30.0,
+                48000.0,
+                DEFAULT_Q_HIGH_LOW_PASS,
+                0.0,
+            ),
+        ));
+        peq.push((
+            1.0,
+            Biquad::new(BiquadFilterType::Lowpass, 18000.0, 48000.0, 0.9, 0.0),
+        ));
+        peq.push((
+            1.0,
+            Biquad::new(BiquadFilterType::HighpassVariableQ, 20.0, 48000.0, 1.2, 0.0),
+        ));
+        peq.push((
+            1.0,
+            Biquad::new(BiquadFilterType::Notch, 6000.0, 48000.0, 1.0, 0.0),
+        ));
+        peq.push((
+            1.0,
+            Biquad::new(
+                BiquadFilterType::LowshelfFirstOrder,
+                60.0,
+                48000.0,
+                0.0,
+                3.0,
+            ),
+        ));
+        peq.push((
+            1.0,
+            Biquad::new(
+                BiquadFilterType::HighshelfFirstOrder,
+                12000.0,
+                48000.0,
+                0.0,
+                -2.0,
+            ),
+        ));
+        peq.push((
+            1.0,
+            Biquad::new(BiquadFilterType::Allpass, 500.0, 48000.0, 0.9, 0.0),
+        ));
+
+        let text = peq_format_apo("# round trip", &peq);
+        let parsed = peq_parse_apo(&text, 48000.0).unwrap();
+        assert_eq!(parsed.len(), peq.len());
+
+        // The writer sorts by frequency
+        let mut expected: Vec<&Biquad> = peq.iter().map(|(_, bq)| bq).collect();
+        expected.sort_by(|a, b| a.freq.partial_cmp(&b.freq).unwrap());
+        for ((_, got), want) in parsed.iter().zip(expected) {
+            assert_biquad_close(got, want, 0.006);
+        }
+    }
+
+    #[test]
+    fn test_apo_round_trip_raw_coefficients() {
+        let mut peq: Peq = [
+            (BiquadFilterType::LowpassFirstOrder, 8000.0),
+            (BiquadFilterType::HighpassFirstOrder, 40.0),
+            (BiquadFilterType::AllpassFirstOrder, 700.0),
+        ]
+        .into_iter()
+        .map(|(filter_type, freq)| (1.0, Biquad::new(filter_type, freq, 48000.0, 0.0, 0.0)))
+        .collect();
+        peq.push((
+            1.0,
+            Biquad::new(BiquadFilterType::LinkwitzTransform, 55.0, 48000.0, 0.9, 6.0),
+        ));
+        peq.push((
+            1.0,
+            Biquad::new_linkwitz_transform(70.0, 1.1, 35.0, 0.6, 48000.0),
+        ));
+
+        let text = peq_format_apo("# raw", &peq);
+        assert_eq!(text.matches("ON IIR Order 1 Coefficients").count(), 3);
+        assert_eq!(text.matches("ON IIR Order 2 Coefficients").count(), 2);
+        let parsed = peq_parse_apo(&text, 48000.0).unwrap();
+        assert_eq!(parsed.len(), peq.len());
+
+        let mut expected: Vec<&Biquad> = peq.iter().map(|(_, bq)| bq).collect();
+        expected.sort_by(|a, b| a.freq.partial_cmp(&b.freq).unwrap());
+        for ((_, got), want) in parsed.iter().zip(expected) {
+            assert_biquad_close(got, want, 1e-6);
+            assert!((got.target_freq - want.target_freq).abs() < 1e-6);
+            assert!((got.target_q - want.target_q).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_apo_round_trip_vicanek() {
+        let peq: Peq = [
+            (BiquadFilterType::LowshelfFirstOrder, 100.0, 0.0, 3.0),
+            (BiquadFilterType::Peak, 12000.0, 2.0, -4.0),
+            (BiquadFilterType::Highshelf, 9000.0, 0.0, 2.5),
+        ]
+        .into_iter()
+        .map(|(filter_type, freq, q, gain)| {
+            (
+                1.0,
+                Biquad::new_with_design(filter_type, freq, 48000.0, q, gain, BiquadDesign::Vicanek),
+            )
+        })
+        .chain(std::iter::once((
+            1.0,
+            Biquad::new_linkwitz_transform(60.0, 1.2, 30.0, 0.6, 48000.0)
+                .redesign(48000.0, BiquadDesign::Vicanek),
+        )))
+        .collect();
+
+        let text = peq_format_apo("# vicanek", &peq);
+        assert_eq!(text.matches("# Vicanek: ").count(), 4);
+        assert!(!text.contains("ON PK"));
+        let parsed = peq_parse_apo(&text, 48000.0).unwrap();
+        assert_eq!(parsed.len(), peq.len());
+
+        let mut expected: Vec<&Biquad> = peq.iter().map(|(_, bq)| bq).collect();
+        expected.sort_by(|a, b| a.freq.partial_cmp(&b.freq).unwrap());
+        for ((_, got), want) in parsed.iter().zip(expected) {
+            assert_biquad_close(got, want, 1e-9);
+            assert_eq!(got.design, BiquadDesign::Vicanek);
+            assert_eq!(got.constants(), want.constants());
+        }
+
+        // the comment must describe the coefficients below it
+        let tampered = text.replacen("# Vicanek: PK 12000", "# Vicanek: PK 11000", 1);
+        assert!(peq_parse_apo(&tampered, 48000.0).is_err());
+    }
+
+    #[test]
+    fn test_apo_hand_written() {
+        let text = "\
+# comment
+Preamp: -3.2 dB
+Filter: ON PK Fc 100 Hz Gain -2.5 dB Q 1.41
+Filter 2: OFF PK Fc 200 Hz Gain 4.0 dB Q 1.0
+Filter 3: ON PEQ Fc 1000.5 Hz Gain 1.0 dB BW Oct 1.0
+Filter 4: ON LSC Fc 80 Hz Gain 3.0 dB
+Filter 5: ON LP Fc 15000 Hz
+";
+        let peq = peq_parse_apo(text, 44100.0).unwrap();
+        assert_eq!(peq.len(), 4);
+        assert_eq!(peq[0].1.filter_type, BiquadFilterType::Peak);
+        assert_eq!(peq[0].1.srate, 44100.0);
+        assert!((peq[1].1.freq - 1000.5).abs() < 1e-9);
+        assert!((peq[1].1.q - bw2q(1.0)).abs() < 1e-9);
+        assert_eq!(peq[2].1.filter_type, BiquadFilterType::Lowshelf);
+        assert!((peq[2].1.q - crate::DEFAULT_Q_HIGH_LOW_SHELF).abs() < 1e-9);
+        assert_eq!(peq[3].1.filter_type, BiquadFilterType::Lowpass);
+        assert!((peq[3].1.q - DEFAULT_Q_HIGH_LOW_PASS).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_apo_errors() {
+        assert!(peq_parse_apo("Filter 1: ON XX Fc 100 Hz", 48000.0).is_err());
+        assert!(peq_parse_apo("Filter 1: ON PK Gain 1 dB Q 1", 48000.0).is_err());
+        assert!(peq_parse_apo("Filter 1: ON PK Fc abc Hz", 48000.0).is_err());
+        // raw coefficients that are not one of our filter types
+        assert!(
+            peq_parse_apo(
+                "Filter 1: ON IIR Order 1 Coefficients 0.5 0.2 1 0.1",
+                48000.0
+            )
+            .is_err()
+        );
+        assert!(
+            peq_parse_apo(
+                "Filter 1: ON IIR Order 3 Coefficients 1 0 0 0 1 0 0 0",
+                48000.0
+            )
+            .is_err()
+        );
+        assert!(
+            peq_parse_apo("Preamp: -1 dB\n", 48000.0)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_apo_stereo_round_trip() {
+        let left = sample_peq();
+        let right = vec![(
+            1.0,
+            Biquad::new(BiquadFilterType::Peak, 60.0, 48000.0, 3.0, -6.0),
+        )];
+        let text = peq_format_apo_stereo("# stereo", &left, &right);
+        assert!(text.contains("Channel: L"));
+        assert!(text.trim_end().ends_with("Channel: all"));
+        let (parsed_left, parsed_right) = peq_parse_apo_stereo(&text, 48000.0).unwrap();
+        assert_eq!(parsed_left.len(), left.len());
+        assert_eq!(parsed_right.len(), 1);
+        assert_biquad_close(&parsed_right[0].1, &right[0].1, 0.006);
+
+        // without Channel commands both sides get every filter
+        let (l, r) = peq_parse_apo_stereo(&peq_format_apo("", &right), 48000.0).unwrap();
+        assert_eq!((l.len(), r.len()), (1, 1));
+    }
+
+    #[test]
+    fn test_apo_stereo_channel_selection() {
+        let text = "\
+Filter: ON PK Fc 100 Hz Gain -1.0 dB Q 1.0
+Channel: 2
+Filter: ON PK Fc 200 Hz Gain -2.0 dB Q 1.0
+Channel: C SUB
+Filter: ON PK Fc 300 Hz Gain -3.0 dB Q 1.0
+Channel: L R
+Filter: ON PK Fc 400 Hz Gain -4.0 dB Q 1.0
+";
+        let (left, right) = peq_parse_apo_stereo(text, 48000.0).unwrap();
+        let freqs = |peq: &Peq| peq.iter().map(|(_, bq)| bq.freq).collect::<Vec<_>>();
+        assert_eq!(freqs(&left), vec![100.0, 400.0]);
+        assert_eq!(freqs(&right), vec![100.0, 200.0, 400.0]);
+    }
+
+    #[test]
+    fn test_rme_channel_round_trip() {
+        let peq = vec![
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Lowshelf, 80.0, 48000.0, 0.9, 3.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 1234.5, 48000.0, 2.25, -4.5),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Lowpass, 12000.0, 48000.0, 0.71, 0.0),
+            ),
+        ];
+        let text = peq_format_rme_channel(&peq);
+        let parsed = peq_parse_rme_channel(&text, 48000.0).unwrap();
+        assert_eq!(parsed.len(), peq.len());
+        for ((_, got), (_, want)) in parsed.iter().zip(peq.iter()) {
+            assert_biquad_close(got, want, 0.006);
+        }
+    }
+
+    #[test]
+    fn test_rme_room_round_trip() {
+        let left = sample_peq();
+        let right = vec![(
+            1.0,
+            Biquad::new(BiquadFilterType::Peak, 60.0, 48000.0, 3.0, -6.0),
+        )];
+        let text = peq_format_rme_room(&left, &right);
+        let (parsed_left, parsed_right) = peq_parse_rme_room(&text, 48000.0).unwrap();
+
+        // Shelves go to positions 1 and 9, peaks in between
+        let expected_left = [&left[0].1, &left[1].1, &left[2].1, &left[3].1];
+        assert_eq!(parsed_left.len(), expected_left.len());
+        for ((_, got), want) in parsed_left.iter().zip(expected_left) {
+            assert_biquad_close(got, want, 0.006);
+        }
+        assert_eq!(parsed_right.len(), 1);
+        assert_biquad_close(&parsed_right[0].1, &right[0].1, 0.006);
+    }
+
+    #[test]
+    fn test_rme_errors() {
+        assert!(peq_parse_rme_channel("", 48000.0).is_err());
+        assert!(peq_parse_rme_room("<Preset></Preset>", 48000.0).is_err());
+        let bad = "<Preset>\n<val e=\"Band1 Freq\" v=\"abc,\"/>\n</Preset>";
+        assert!(peq_parse_rme_channel(bad, 48000.0).is_err());
+    }
+
+    #[test]
+    fn test_aupreset_round_trip() {
+        let mut peq = sample_peq();
+        peq.push((
+            1.0,
+            Biquad::new(BiquadFilterType::Bandpass, 500.0, 48000.0, 2.0, 0.0),
+        ));
+        let text = peq_format_aupreset(&peq, "Round Trip");
+        let parsed = peq_parse_aupreset(&text, 48000.0).unwrap();
+        assert_eq!(parsed.len(), peq.len());
+        for ((_, got), (_, want)) in parsed.iter().zip(peq.iter()) {
+            assert_biquad_close(got, want, 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_aupreset_errors() {
+        assert!(peq_parse_aupreset("<plist></plist>", 48000.0).is_err());
+        assert!(peq_parse_aupreset("<data>!!!</data>", 48000.0).is_err());
+        assert!(peq_parse_aupreset("<data>AAAA</data>", 48000.0).is_err());
+    }
+}
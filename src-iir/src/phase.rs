@@ -0,0 +1,245 @@
+// ----------------------------------------------------------------------
+// Complex Response, Phase and Group Delay
+// ----------------------------------------------------------------------
+//
+// `np_log_result` and `compute_peq_response` only give the magnitude. Driver
+// alignment and phase plots need the complex response H(e^jw), the unwrapped
+// phase and the group delay, both for a single biquad and for a whole PEQ.
+// Phases are in degrees and group delays in milliseconds.
+
+use super::{Biquad, Peq};
+use ndarray::Array1;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+impl Biquad {
+    /// Calculates the filter's complex response at a single frequency `f`.
+    pub fn complex_result(&self, f: f64) -> Complex64 {
+        let (a1, a2, b0, b1, b2) = self.constants();
+        let z1 = Complex64::from_polar(1.0, -2.0 * PI * f / self.srate);
+        let z2 = z1 * z1;
+        (b0 + b1 * z1 + b2 * z2) / (1.0 + a1 * z1 + a2 * z2)
+    }
+
+    /// Vectorized complex response for a vector of frequencies.
+    pub fn np_complex_result(&self, freq: &Array1<f64>) -> Array1<Complex64> {
+        freq.mapv(|f| self.complex_result(f))
+    }
+
+    /// Unwrapped phase in degrees for a vector of frequencies.
+    pub fn np_phase_result(&self, freq: &Array1<f64>) -> Array1<f64> {
+        unwrap_phase(&self.np_complex_result(freq).mapv(|h| h.arg().to_degrees()))
+    }
+
+    /// Group delay in milliseconds for a vector of frequencies.
+    ///
+    /// Computed analytically from the coefficients: for a polynomial
+    /// P(z) = sum p_k z^-k the delay is Re(sum k p_k z^-k / P(z)) samples, and
+    /// the delay of the filter is the one of the numerator minus the one of the
+    /// denominator.
+    pub fn np_group_delay(&self, freq: &Array1<f64>) -> Array1<f64> {
+        let (a1, a2, b0, b1, b2) = self.constants();
+        let to_ms = 1000.0 / self.srate;
+        freq.mapv(|f| {
+            let z1 = Complex64::from_polar(1.0, -2.0 * PI * f / self.srate);
+            let z2 = z1 * z1;
+            let num = (b1 * z1 + 2.0 * b2 * z2) / (b0 + b1 * z1 + b2 * z2);
+            let den = (a1 * z1 + 2.0 * a2 * z2) / (1.0 + a1 * z1 + a2 * z2);
+            (num.re - den.re) * to_ms
+        })
+    }
+}
+
+/// Unwrap a phase curve in degrees
+///
+/// # Arguments
+/// * `phase` - Phase in degrees, e.g. wrapped to (-180, 180]
+///
+/// # Returns
+/// * Phase with multiples of 360 degrees added so that consecutive points
+///   never differ by more than 180 degrees
+pub fn unwrap_phase(phase: &Array1<f64>) -> Array1<f64> {
+    let mut unwrapped = phase.clone();
+    let mut offset = 0.0;
+    for i in 1..phase.len() {
+        let delta = phase[i] - phase[i - 1];
+        if delta > 180.0 {
+            offset -= 360.0 * ((delta - 180.0) / 360.0).ceil();
+        } else if delta < -180.0 {
+            offset += 360.0 * ((-delta - 180.0) / 360.0).ceil();
+        }
+        unwrapped[i] = phase[i] + offset;
+    }
+    unwrapped
+}
+
+/// Compute the complex response of a PEQ on a given frequency grid
+///
+/// # Arguments
+/// * `freqs` - Frequency points for evaluation (Hz)
+/// * `peq` - Parametric equalizer containing weighted biquad filters
+///
+/// # Returns
+/// * Product of the filter responses; a weight w scales the dB magnitude and
+///   the unwrapped phase of its filter, as in `compute_peq_response`
+pub fn compute_peq_complex_response(freqs: &Array1<f64>, peq: &Peq) -> Array1<Complex64> {
+    let mut response = Array1::from_elem(freqs.len(), Complex64::new(1.0, 0.0));
+    for (weight, filter) in peq {
+        let h = filter.np_complex_result(freqs);
+        let phase = filter.np_phase_result(freqs);
+        for ((r, h), p) in response.iter_mut().zip(h.iter()).zip(phase.iter()) {
+            *r *= Complex64::from_polar(h.norm().powf(*weight), (p * weight).to_radians());
+        }
+    }
+    response
+}
+
+/// Compute the unwrapped phase (in degrees) of a PEQ on a given frequency grid
+///
+/// # Arguments
+/// * `freqs` - Frequency points for evaluation (Hz)
+/// * `peq` - Parametric equalizer containing weighted biquad filters
+///
+/// # Returns
+/// * Weighted sum of the unwrapped phase of each filter
+pub fn compute_peq_phase(freqs: &Array1<f64>, peq: &Peq) -> Array1<f64> {
+    let mut phase = Array1::zeros(freqs.len());
+    for (weight, filter) in peq {
+        phase += &(filter.np_phase_result(freqs) * *weight);
+    }
+    phase
+}
+
+/// Compute the group delay (in milliseconds) of a PEQ on a given frequency grid
+///
+/// # Arguments
+/// * `freqs` - Frequency points for evaluation (Hz)
+/// * `peq` - Parametric equalizer containing weighted biquad filters
+///
+/// # Returns
+/// * Weighted sum of the group delay of each filter
+pub fn compute_peq_group_delay(freqs: &Array1<f64>, peq: &Peq) -> Array1<f64> {
+    let mut delay = Array1::zeros(freqs.len());
+    for (weight, filter) in peq {
+        delay += &(filter.np_group_delay(freqs) * *weight);
+    }
+    delay
+}
+
+#[cfg(test)]
+mod phase_tests {
+    use super::*;
+    use crate::{BiquadFilterType, compute_peq_response};
+    use ndarray::array;
+
+    fn grid() -> Array1<f64> {
+        Array1::logspace(10.0, 20.0_f64.log10(), 20_000.0_f64.log10(), 400)
+    }
+
+    #[test]
+    fn test_complex_magnitude_matches_log_result() {
+        let bq = Biquad::new(BiquadFilterType::Peak, 1_000.0, 48_000.0, 2.0, 6.0);
+        let freqs = grid();
+        let mag = bq
+            .np_complex_result(&freqs)
+            .mapv(|h| 20.0 * h.norm().log10());
+        let reference = bq.np_log_result(&freqs);
+        for (a, b) in mag.iter().zip(reference.iter()) {
+            assert!((a - b).abs() < 1e-9, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_unwrap_phase() {
+        let wrapped = array![170.0, -170.0, -150.0, 175.0, 10.0];
+        let unwrapped = unwrap_phase(&wrapped);
+        assert_eq!(unwrapped.to_vec(), vec![170.0, 190.0, 210.0, 175.0, 10.0]);
+    }
+
+    #[test]
+    fn test_allpass_phase_and_group_delay() {
+        let ap1 = Biquad::new(
+            BiquadFilterType::AllpassFirstOrder,
+            1_000.0,
+            48_000.0,
+            0.0,
+            0.0,
+        );
+        let phase = ap1.np_phase_result(&array![1.0, 1_000.0, 23_999.0]);
+        assert!(phase[0].abs() < 0.5);
+        assert!((phase[1] + 90.0).abs() < 1e-6);
+        assert!((phase[2] + 180.0).abs() < 1.0);
+        // 2 / w0 at DC for a first-order all-pass
+        let gd = ap1.np_group_delay(&array![1.0]);
+        let expected = 2.0 / (2.0 * PI * 1_000.0) * 1000.0;
+        assert!(
+            (gd[0] - expected).abs() / expected < 0.01,
+            "{} vs {}",
+            gd[0],
+            expected
+        );
+
+        let ap2 = Biquad::new(BiquadFilterType::Allpass, 500.0, 48_000.0, 0.7, 0.0);
+        let phase = ap2.np_phase_result(&grid());
+        assert!(phase.windows(2).into_iter().all(|w| w[1] <= w[0] + 1e-9));
+        assert!((ap2.np_phase_result(&array![1.0, 500.0])[1] + 180.0).abs() < 1e-6);
+        assert!((phase[phase.len() - 1] + 360.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_group_delay_is_phase_derivative() {
+        let bq = Biquad::new(BiquadFilterType::Peak, 200.0, 48_000.0, 3.0, -8.0);
+        let h = 0.01;
+        for f in [50.0, 150.0, 200.0, 260.0, 800.0, 5_000.0] {
+            let phase = bq.np_phase_result(&array![f - h, f + h]);
+            let numeric = -(phase[1] - phase[0]).to_radians() / (2.0 * PI * 2.0 * h) * 1000.0;
+            let gd = bq.np_group_delay(&array![f])[0];
+            assert!(
+                (numeric - gd).abs() < 1e-5,
+                "{} Hz: {} vs {}",
+                f,
+                numeric,
+                gd
+            );
+        }
+    }
+
+    #[test]
+    fn test_peq_phase_is_sum_of_filters() {
+        let peq: Peq = vec![
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highpass, 40.0, 48_000.0, 0.7, 0.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 1_500.0, 48_000.0, 1.0, 4.0),
+            ),
+            (
+                2.0,
+                Biquad::new(BiquadFilterType::Lowshelf, 100.0, 48_000.0, 0.7, -2.0),
+            ),
+        ];
+        let freqs = grid();
+        let phase = compute_peq_phase(&freqs, &peq);
+        let expected = peq[0].1.np_phase_result(&freqs)
+            + peq[1].1.np_phase_result(&freqs)
+            + peq[2].1.np_phase_result(&freqs) * 2.0;
+        for (a, b) in phase.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        // a second-order highpass leads by 180 degrees at DC and 90 at its corner
+        let hp = peq[0].1.np_phase_result(&array![0.1, 40.0]);
+        assert!((hp[0] - 180.0).abs() < 0.5, "{}", hp[0]);
+        assert!((hp[1] - 90.0).abs() < 1e-6, "{}", hp[1]);
+
+        let h = compute_peq_complex_response(&freqs, &peq);
+        let spl = compute_peq_response(&freqs, &peq, 48_000.0);
+        for (h, s) in h.iter().zip(spl.iter()) {
+            assert!((20.0 * h.norm().log10() - s).abs() < 1e-9);
+        }
+
+        let gd = compute_peq_group_delay(&freqs, &peq);
+        assert!(gd.iter().all(|v| v.is_finite()));
+    }
+}
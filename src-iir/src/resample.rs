@@ -0,0 +1,257 @@
+// ----------------------------------------------------------------------
+// PEQ Sample Rate Conversion
+// ----------------------------------------------------------------------
+//
+// Every `Biquad` carries the sample rate it was designed for. Redesigning a
+// filter at another rate with the same frequency, Q and gain keeps its
+// response at low frequencies but not close to Nyquist, where the bilinear
+// transform compresses the response differently at each rate. The conversion
+// below starts from the redesigned filters and refits their parameters so that
+// the magnitude response follows the original one up to the lower Nyquist
+// frequency.
+
+use super::{Biquad, BiquadFilterType, Peq, compute_peq_response};
+use ndarray::Array1;
+use std::fmt;
+
+/// Number of points of the frequency grid used for the fit
+const RESAMPLE_GRID_SIZE: usize = 256;
+/// Maximum number of coordinate descent passes
+const RESAMPLE_MAX_PASSES: usize = 200;
+
+/// A PEQ converted to another sample rate with its residual error
+#[derive(Debug, Clone)]
+pub struct PeqResample {
+    /// Refitted filters, all at the new sample rate
+    pub peq: Peq,
+    /// New sample rate in Hz
+    pub srate: f64,
+    /// Upper frequency of the fit in Hz
+    pub max_freq: f64,
+    /// Maximum absolute deviation from the original response in dB
+    pub max_error_db: f64,
+    /// RMS deviation from the original response in dB
+    pub rms_error_db: f64,
+    /// Maximum absolute deviation in dB before refitting (plain redesign)
+    pub redesign_max_error_db: f64,
+}
+
+impl fmt::Display for PeqResample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Resampled to {} Hz: max error {:.4} dB, rms error {:.4} dB up to {:.0} Hz (redesign only: {:.4} dB)",
+            self.srate,
+            self.max_error_db,
+            self.rms_error_db,
+            self.max_freq,
+            self.redesign_max_error_db
+        )
+    }
+}
+
+/// Parameters refitted for a filter type: (frequency, Q, gain)
+fn fitted_params(filter_type: BiquadFilterType) -> (bool, bool, bool) {
+    match filter_type {
+        BiquadFilterType::Peak => (true, true, true),
+        BiquadFilterType::Lowshelf
+        | BiquadFilterType::Highshelf
+        | BiquadFilterType::LowshelfFirstOrder
+        | BiquadFilterType::HighshelfFirstOrder => (true, false, true),
+        BiquadFilterType::Lowpass
+        | BiquadFilterType::Highpass
+        | BiquadFilterType::HighpassVariableQ
+        | BiquadFilterType::Bandpass
+        | BiquadFilterType::Notch => (true, true, false),
+        BiquadFilterType::LowpassFirstOrder | BiquadFilterType::HighpassFirstOrder => {
+            (true, false, false)
+        }
+        // flat magnitude, or defined by its analog resonances
+        BiquadFilterType::AllpassFirstOrder
+        | BiquadFilterType::Allpass
+        | BiquadFilterType::LinkwitzTransform => (false, false, false),
+    }
+}
+
+/// Filter parameters in the space used by the fit: (log10 freq, ln Q, gain)
+fn to_params(biquad: &Biquad) -> [f64; 3] {
+    [
+        biquad.freq.log10(),
+        biquad.q.max(1.0e-3).ln(),
+        biquad.db_gain,
+    ]
+}
+
+fn from_params(template: &Biquad, params: &[f64; 3], srate: f64) -> Biquad {
+    let freq = 10.0_f64.powf(params[0]).min(0.49 * srate);
+    Biquad::new_with_design(
+        template.filter_type,
+        freq,
+        srate,
+        params[1].exp(),
+        params[2],
+        template.design,
+    )
+}
+
+fn error_db(freqs: &Array1<f64>, peq: &Peq, reference: &Array1<f64>) -> Array1<f64> {
+    compute_peq_response(freqs, peq, 0.0) - reference
+}
+
+fn sum_squares(diff: &Array1<f64>) -> f64 {
+    diff.iter().map(|d| d * d).sum()
+}
+
+/// Convert a PEQ to another sample rate while keeping its magnitude response
+///
+/// Each filter is first redesigned at `srate`, then frequency, Q and gain are
+/// refitted with a coordinate descent minimizing the squared dB difference to
+/// the original response on a log grid from 20 Hz to 95% of the lower Nyquist
+/// frequency. Filters are only refitted on the parameters that shape their
+/// magnitude; all-pass and Linkwitz transform sections are redesigned as is.
+///
+/// # Arguments
+/// * `peq` - Parametric equalizer designed at any sample rate(s)
+/// * `srate` - New sample rate in Hz
+///
+/// # Returns
+/// * The converted PEQ with its residual error, or an error if the sample
+///   rate is invalid
+pub fn peq_resample(peq: &Peq, srate: f64) -> Result<PeqResample, String> {
+    if !(srate.is_finite() && srate > 0.0) {
+        return Err(format!("invalid sample rate {}", srate));
+    }
+    let min_srate = peq.iter().map(|(_, bq)| bq.srate).fold(srate, f64::min);
+    let max_freq = 0.95 * min_srate / 2.0;
+    if max_freq <= 20.0 {
+        return Err(format!("sample rate {} is too low", min_srate));
+    }
+    let freqs = Array1::logspace(10.0, 20.0_f64.log10(), max_freq.log10(), RESAMPLE_GRID_SIZE);
+    let reference = compute_peq_response(&freqs, peq, 0.0);
+
+    let mut resampled: Peq = peq
+        .iter()
+        .map(|(w, bq)| (*w, bq.redesign(srate, bq.design)))
+        .collect();
+    let redesign_error = error_db(&freqs, &resampled, &reference);
+    let redesign_max_error_db = redesign_error
+        .iter()
+        .fold(0.0_f64, |acc, d| acc.max(d.abs()));
+
+    let mut best = sum_squares(&redesign_error);
+    let mut params: Vec<[f64; 3]> = resampled.iter().map(|(_, bq)| to_params(bq)).collect();
+    let mut steps: Vec<[f64; 3]> = vec![[0.01, 0.05, 0.1]; resampled.len()];
+    for _ in 0..RESAMPLE_MAX_PASSES {
+        let mut converged = true;
+        for i in 0..resampled.len() {
+            let (fit_freq, fit_q, fit_gain) = fitted_params(resampled[i].1.filter_type);
+            for (k, active) in [fit_freq, fit_q, fit_gain].into_iter().enumerate() {
+                if !active || steps[i][k] < 1.0e-6 {
+                    continue;
+                }
+                converged = false;
+                let mut improved = false;
+                for sign in [1.0, -1.0] {
+                    let mut candidate = params[i];
+                    candidate[k] += sign * steps[i][k];
+                    let previous = resampled[i].1.clone();
+                    resampled[i].1 = from_params(&previous, &candidate, srate);
+                    let err = sum_squares(&error_db(&freqs, &resampled, &reference));
+                    if err < best {
+                        best = err;
+                        params[i] = candidate;
+                        improved = true;
+                        break;
+                    }
+                    resampled[i].1 = previous;
+                }
+                if improved {
+                    steps[i][k] *= 1.5;
+                } else {
+                    steps[i][k] *= 0.5;
+                }
+            }
+        }
+        if converged {
+            break;
+        }
+    }
+
+    let diff = error_db(&freqs, &resampled, &reference);
+    let max_error_db = diff.iter().fold(0.0_f64, |acc, d| acc.max(d.abs()));
+    let rms_error_db = (diff.mapv(|d| d * d).sum() / diff.len() as f64).sqrt();
+
+    Ok(PeqResample {
+        peq: resampled,
+        srate,
+        max_freq,
+        max_error_db,
+        rms_error_db,
+        redesign_max_error_db,
+    })
+}
+
+#[cfg(test)]
+mod resample_tests {
+    use super::*;
+
+    fn peq_48k() -> Peq {
+        vec![
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highpass, 30.0, 48_000.0, 0.7, 0.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 120.0, 48_000.0, 2.0, -5.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Peak, 12_000.0, 48_000.0, 1.5, 4.0),
+            ),
+            (
+                1.0,
+                Biquad::new(BiquadFilterType::Highshelf, 8_000.0, 48_000.0, 0.7, -3.0),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_resample_to_44100_improves_on_redesign() {
+        let result = peq_resample(&peq_48k(), 44_100.0).unwrap();
+        println!("{}", result);
+        assert!(result.peq.iter().all(|(_, bq)| bq.srate == 44_100.0));
+        assert!(result.max_error_db < result.redesign_max_error_db);
+        assert!(result.max_error_db < 0.1, "{}", result.max_error_db);
+        assert!(result.rms_error_db <= result.max_error_db);
+        // low frequency filters barely move
+        let peak = &result.peq[1].1;
+        assert!((peak.freq - 120.0).abs() < 1.0);
+        assert!((peak.db_gain + 5.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_resample_up_keeps_response() {
+        let result = peq_resample(&peq_48k(), 96_000.0).unwrap();
+        assert!((result.max_freq - 0.95 * 24_000.0).abs() < 1e-9);
+        assert!(result.max_error_db < 0.1, "{}", result.max_error_db);
+    }
+
+    #[test]
+    fn test_resample_same_rate_is_identity() {
+        let peq = peq_48k();
+        let result = peq_resample(&peq, 48_000.0).unwrap();
+        assert!(result.max_error_db < 1e-9);
+        for ((_, a), (_, b)) in peq.iter().zip(result.peq.iter()) {
+            assert_eq!(a.freq, b.freq);
+            assert_eq!(a.q, b.q);
+            assert_eq!(a.db_gain, b.db_gain);
+        }
+    }
+
+    #[test]
+    fn test_resample_invalid_rate() {
+        assert!(peq_resample(&peq_48k(), 0.0).is_err());
+        assert!(peq_resample(&peq_48k(), f64::NAN).is_err());
+    }
+}
@@ -17,6 +17,10 @@ pub async fn generate_apo_format(
         "hp-pk-lp" => autoeq::cli::PeqModel::HpPkLp,
         "ls-pk" => autoeq::cli::PeqModel::LsPk,
         "ls-pk-hs" => autoeq::cli::PeqModel::LsPkHs,
+        "hp1-pk" => autoeq::cli::PeqModel::Hp1Pk,
+        "hp1-pk-lp1" => autoeq::cli::PeqModel::Hp1PkLp1,
+        "ls1-pk-hs1" => autoeq::cli::PeqModel::Ls1PkHs1,
+        "lt-pk" => autoeq::cli::PeqModel::LtPk,
         "free-pk-free" => autoeq::cli::PeqModel::FreePkFree,
         "free" => autoeq::cli::PeqModel::Free,
         "pk" | _ => autoeq::cli::PeqModel::Pk,
@@ -54,6 +58,10 @@ pub async fn generate_aupreset_format(
         "hp-pk-lp" => autoeq::cli::PeqModel::HpPkLp,
         "ls-pk" => autoeq::cli::PeqModel::LsPk,
         "ls-pk-hs" => autoeq::cli::PeqModel::LsPkHs,
+        "hp1-pk" => autoeq::cli::PeqModel::Hp1Pk,
+        "hp1-pk-lp1" => autoeq::cli::PeqModel::Hp1PkLp1,
+        "ls1-pk-hs1" => autoeq::cli::PeqModel::Ls1PkHs1,
+        "lt-pk" => autoeq::cli::PeqModel::LtPk,
         "free-pk-free" => autoeq::cli::PeqModel::FreePkFree,
         "free" => autoeq::cli::PeqModel::Free,
         "pk" | _ => autoeq::cli::PeqModel::Pk,
@@ -92,6 +100,10 @@ pub async fn generate_rme_format(
         "hp-pk-lp" => autoeq::cli::PeqModel::HpPkLp,
         "ls-pk" => autoeq::cli::PeqModel::LsPk,
         "ls-pk-hs" => autoeq::cli::PeqModel::LsPkHs,
+        "hp1-pk" => autoeq::cli::PeqModel::Hp1Pk,
+        "hp1-pk-lp1" => autoeq::cli::PeqModel::Hp1PkLp1,
+        "ls1-pk-hs1" => autoeq::cli::PeqModel::Ls1PkHs1,
+        "lt-pk" => autoeq::cli::PeqModel::LtPk,
         "free-pk-free" => autoeq::cli::PeqModel::FreePkFree,
         "free" => autoeq::cli::PeqModel::Free,
         "pk" | _ => autoeq::cli::PeqModel::Pk,
@@ -127,6 +139,10 @@ pub async fn generate_rme_room_format(
         "hp-pk-lp" => autoeq::cli::PeqModel::HpPkLp,
         "ls-pk" => autoeq::cli::PeqModel::LsPk,
         "ls-pk-hs" => autoeq::cli::PeqModel::LsPkHs,
+        "hp1-pk" => autoeq::cli::PeqModel::Hp1Pk,
+        "hp1-pk-lp1" => autoeq::cli::PeqModel::Hp1PkLp1,
+        "ls1-pk-hs1" => autoeq::cli::PeqModel::Ls1PkHs1,
+        "lt-pk" => autoeq::cli::PeqModel::LtPk,
         "free-pk-free" => autoeq::cli::PeqModel::FreePkFree,
         "free" => autoeq::cli::PeqModel::Free,
         "pk" | _ => autoeq::cli::PeqModel::Pk,
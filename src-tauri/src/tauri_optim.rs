@@ -51,6 +51,14 @@ pub struct OptimizationParams {
     pub num_filters: usize,
     pub curve_path: Option<String>,
     pub target_path: Option<String>,
+    pub target_builtin: Option<String>, // e.g. "harman-over-ear-2018", see autoeq::targets
+    pub target_tilt: Option<f64>,
+    pub target_bass_freq: Option<f64>,
+    pub target_bass_gain: Option<f64>,
+    pub target_treble_freq: Option<f64>,
+    pub target_treble_gain: Option<f64>,
+    pub room_gain: Option<f64>,
+    pub room_gain_freq: Option<f64>,
     pub sample_rate: f64,
     pub max_db: f64,
     pub min_db: f64,
@@ -92,7 +100,7 @@ pub struct OptimizationParams {
     pub driver2_path: Option<String>,
     pub driver3_path: Option<String>,
     pub driver4_path: Option<String>,
-    pub crossover_type: Option<String>, // "butterworth2", "linkwitzriley2", "linkwitzriley4"
+    pub crossover_type: Option<String>, // e.g. "linkwitzriley4", or "lr4,lr4/bw3" per pair
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -133,6 +141,18 @@ pub fn validate_params(
         .into());
     }
 
+    // A target file and a built-in target are mutually exclusive, as on the CLI
+    if params.target_path.is_some() && params.target_builtin.is_some() {
+        return Err("Use either a target file or a built-in target, not both".into());
+    }
+
+    // Validate built-in target name
+    if let Some(ref name) = params.target_builtin
+        && autoeq::targets::BuiltinTarget::from_name(name).is_none()
+    {
+        return Err(format!("Unknown built-in target: {}", name).into());
+    }
+
     // Validate tolerance (lower bound 1e-12, no upper bound)
     if let Some(tol) = params.tolerance
         && tol < 1e-12
@@ -276,19 +296,6 @@ pub fn validate_params(
             return Err("Multi-driver optimization requires at least driver1_path and driver2_path when using loss='drivers-flat'".into());
         }
 
-        // Check crossover type
-        if let Some(ref crossover_type) = params.crossover_type {
-            let valid_types = ["butterworth2", "linkwitzriley2", "linkwitzriley4"];
-            if !valid_types.contains(&crossover_type.as_str()) {
-                return Err(format!(
-                    "Invalid crossover type '{}'. Valid types: {}",
-                    crossover_type,
-                    valid_types.join(", ")
-                )
-                .into());
-            }
-        }
-
         // Count number of drivers
         let n_drivers = [
             &params.driver1_path,
@@ -307,6 +314,11 @@ pub fn validate_params(
             )
             .into());
         }
+
+        // Check crossover types (one for all pairs or one per pair)
+        if let Some(ref crossover_type) = params.crossover_type {
+            autoeq::loss::Crossover::parse_list(crossover_type, n_drivers - 1)?;
+        }
     } else {
         // If not using drivers-flat loss, driver arguments should not be provided
         if params.driver1_path.is_some()
@@ -428,11 +440,28 @@ pub async fn run_optimization_internal<P: ProgressCallback + 'static>(
     validate_params(&params)?;
     println!("[RUST DEBUG] Parameters validated successfully");
 
-    // Convert parameters to AutoEQ Args structure
+    // Convert parameters to AutoEQ Args structure, the CLI defaults fill the
+    // settings the UI does not expose
+    let defaults = AutoEQArgs::default();
     let args = AutoEQArgs {
         num_filters: params.num_filters,
         curve: params.curve_path.map(PathBuf::from),
         target: params.target_path.map(PathBuf::from),
+        target_builtin: params
+            .target_builtin
+            .as_deref()
+            .and_then(autoeq::targets::BuiltinTarget::from_name),
+        target_tilt: params.target_tilt.unwrap_or(defaults.target_tilt),
+        target_bass_freq: params.target_bass_freq.unwrap_or(defaults.target_bass_freq),
+        target_bass_gain: params.target_bass_gain.unwrap_or(defaults.target_bass_gain),
+        target_treble_freq: params
+            .target_treble_freq
+            .unwrap_or(defaults.target_treble_freq),
+        target_treble_gain: params
+            .target_treble_gain
+            .unwrap_or(defaults.target_treble_gain),
+        room_gain: params.room_gain.unwrap_or(defaults.room_gain),
+        room_gain_freq: params.room_gain_freq.unwrap_or(defaults.room_gain_freq),
         sample_rate: params.sample_rate,
         max_db: params.max_db,
         min_db: params.min_db,
@@ -440,7 +469,6 @@ pub async fn run_optimization_internal<P: ProgressCallback + 'static>(
         min_q: params.min_q,
         min_freq: params.min_freq,
         max_freq: params.max_freq,
-        output: None, // We'll handle plotting in the frontend
         speaker: params.speaker,
         version: params.version,
         measurement: params.measurement,
@@ -462,31 +490,28 @@ pub async fn run_optimization_internal<P: ProgressCallback + 'static>(
             "headphone-flat" => LossType::HeadphoneFlat,
             "headphone-score" => LossType::HeadphoneScore,
             "drivers-flat" => LossType::DriversFlat,
+            "perceptual-flat" => LossType::PerceptualFlat,
+            "speaker-score-with-sub" => LossType::SpeakerScoreWithSub,
             _ => LossType::SpeakerFlat,
         },
         peq_model: match params.peq_model.as_deref() {
             Some("hp-pk") => autoeq::cli::PeqModel::HpPk,
             Some("hp-pk-lp") => autoeq::cli::PeqModel::HpPkLp,
+            Some("hp1-pk") => autoeq::cli::PeqModel::Hp1Pk,
+            Some("hp1-pk-lp1") => autoeq::cli::PeqModel::Hp1PkLp1,
+            Some("ls1-pk-hs1") => autoeq::cli::PeqModel::Ls1PkHs1,
+            Some("lt-pk") => autoeq::cli::PeqModel::LtPk,
             Some("free-pk-free") => autoeq::cli::PeqModel::FreePkFree,
             Some("free") => autoeq::cli::PeqModel::Free,
             Some("pk") => autoeq::cli::PeqModel::Pk,
             _ => autoeq::cli::PeqModel::Pk, // Default to Pk
         },
-        peq_model_list: false,
-        algo_list: false, // UI doesn't need to list algorithms
-        tolerance: params.tolerance.unwrap_or(1e-3), // Use provided tolerance or default
-        atolerance: params.atolerance.unwrap_or(1e-4), // Use provided atolerance or default
-        recombination: params.de_cr.unwrap_or(0.9), // DE crossover probability
-        strategy: params
-            .strategy
-            .unwrap_or_else(|| "currenttobest1bin".to_string()), // DE strategy
-        strategy_list: false, // UI doesn't need to list strategies
-        adaptive_weight_f: params.adaptive_weight_f.unwrap_or(0.8), // Adaptive weight for F
-        adaptive_weight_cr: params.adaptive_weight_cr.unwrap_or(0.7), // Adaptive weight for CR
-        no_parallel: false,
-        parallel_threads: 0,
-        seed: None, // Random seed for deterministic optimization (None = random)
-        qa: None,   // Quality assurance mode disabled for UI (None = disabled)
+        tolerance: params.tolerance.unwrap_or(defaults.tolerance),
+        atolerance: params.atolerance.unwrap_or(defaults.atolerance),
+        recombination: params.de_cr.unwrap_or(defaults.recombination), // DE crossover probability
+        strategy: params.strategy.unwrap_or_else(|| defaults.strategy.clone()), // DE strategy
+        adaptive_weight_f: params.adaptive_weight_f.unwrap_or(0.8),    // Adaptive weight for F
+        adaptive_weight_cr: params.adaptive_weight_cr.unwrap_or(0.7),  // Adaptive weight for CR
         // Multi-driver crossover optimization parameters
         driver1: params.driver1_path.map(PathBuf::from),
         driver2: params.driver2_path.map(PathBuf::from),
@@ -494,7 +519,8 @@ pub async fn run_optimization_internal<P: ProgressCallback + 'static>(
         driver4: params.driver4_path.map(PathBuf::from),
         crossover_type: params
             .crossover_type
-            .unwrap_or_else(|| "linkwitzriley4".to_string()),
+            .unwrap_or_else(|| defaults.crossover_type.clone()),
+        ..defaults
     };
 
     // Load input data (following autoeq.rs pattern)
@@ -548,11 +574,19 @@ pub async fn run_optimization_internal<P: ProgressCallback + 'static>(
             freq: Array1::from_vec(target_freqs.clone()),
             spl: Array1::from_vec(target_mags.clone()),
         };
-        autoeq::read::normalize_and_interpolate_response(&standard_freq, &target_curve_raw)
+        let target_curve =
+            autoeq::read::normalize_and_interpolate_response(&standard_freq, &target_curve_raw);
+        args.target_modifiers()
+            .apply(&target_curve, args.sample_rate)
     } else {
         // Build target using RAW input curve (before normalization)
         println!("[RUST DEBUG] Building target curve using raw input...");
-        autoeq::workflow::build_target_curve(&args, &standard_freq, &input_curve_raw)
+        autoeq::workflow::build_target_curve(
+            &args,
+            &standard_freq,
+            &input_curve_raw,
+            spin_data_raw.as_ref(),
+        )
     };
 
     // Normalize input curve AFTER building target
@@ -611,7 +645,8 @@ pub async fn run_optimization_internal<P: ProgressCallback + 'static>(
     } else if args.loss == LossType::HeadphoneFlat || args.loss == LossType::HeadphoneScore {
         // Calculate headphone preference score using Olive et al. model
         println!("[RUST DEBUG] Calculating headphone preference score before optimization");
-        let headphone_data = autoeq::loss::HeadphoneLossData::new(args.smooth, args.smooth_n);
+        let headphone_data = autoeq::loss::HeadphoneLossData::new(args.smooth, args.smooth_n)
+            .with_headphone_type(args.effective_headphone_type());
         let loss_value =
             autoeq::loss::headphone_loss_with_target(&headphone_data, &input_curve, &target_curve);
         // Negate the loss value to convert to preference score (higher is better)
@@ -740,7 +775,8 @@ pub async fn run_optimization_internal<P: ProgressCallback + 'static>(
             freq: input_curve.freq.clone(),
             spl: &input_curve.spl + &peq_response,
         };
-        let headphone_data = autoeq::loss::HeadphoneLossData::new(args.smooth, args.smooth_n);
+        let headphone_data = autoeq::loss::HeadphoneLossData::new(args.smooth, args.smooth_n)
+            .with_headphone_type(args.effective_headphone_type());
         let loss_value = autoeq::loss::headphone_loss_with_target(
             &headphone_data,
             &corrected_curve,
@@ -854,6 +890,14 @@ pub mod mocks {
             num_filters: 3,
             curve_path: None,
             target_path: None,
+            target_builtin: None,
+            target_tilt: None,
+            target_bass_freq: None,
+            target_bass_gain: None,
+            target_treble_freq: None,
+            target_treble_gain: None,
+            room_gain: None,
+            room_gain_freq: None,
             sample_rate: 48000.0,
             max_db: 3.0,
             min_db: 1.0,
@@ -1234,6 +1278,14 @@ mod tests {
             num_filters: 5,
             curve_path: None,
             target_path: None,
+            target_builtin: None,
+            target_tilt: None,
+            target_bass_freq: None,
+            target_bass_gain: None,
+            target_treble_freq: None,
+            target_treble_gain: None,
+            room_gain: None,
+            room_gain_freq: None,
             sample_rate: 48000.0,
             max_db: 5.0,
             min_db: 1.0,
@@ -1284,6 +1336,16 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_params_target_path_and_builtin() {
+        let mut params = create_test_optimization_params();
+        params.target_path = Some("target.csv".to_string());
+        params.target_builtin = Some("harman-over-ear-2018".to_string());
+        let result = validate_params(&params);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("built-in target"));
+    }
+
     #[test]
     fn test_validate_params_invalid_num_filters() {
         let mut params = create_test_optimization_params();
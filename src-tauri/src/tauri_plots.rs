@@ -1,5 +1,5 @@
 use autoeq::{
-    Curve, LossType, cli::Args as AutoEQArgs, plot_filters, plot_spin, plot_spin_details,
+    Curve, cli::Args as AutoEQArgs, plot_filters, plot_spin, plot_spin_details,
     plot_spin_tonal,
     iir::{Biquad, BiquadFilterType},
 };
@@ -208,33 +208,13 @@ pub async fn generate_plot_filters(params: PlotFiltersParams) -> Result<serde_js
     let target_curve = curve_data_to_curve(&params.target_curve);
     let deviation_curve = curve_data_to_curve(&params.deviation_curve);
 
-    // Create a minimal Args struct for the plot function
+    // Create a minimal Args struct for the plot function, with the CLI defaults
+    // for everything the plot does not set
     let args = AutoEQArgs {
         num_filters: params.num_filters,
-        curve: None,
-        target: None,
         sample_rate: params.sample_rate,
-        max_db: 3.0,
-        min_db: 1.0,
-        max_q: 3.0,
-        min_q: 1.0,
-        min_freq: 60.0,
-        max_freq: 16000.0,
-        output: None,
-        speaker: None,
-        version: None,
-        measurement: None,
-        curve_name: "Listening Window".to_string(),
         algo: "autoeq:de".to_string(),
-        population: 300,
-        maxeval: 2000,
-        refine: false,
-        local_algo: "cobyla".to_string(),
         min_spacing_oct: 0.5,
-        spacing_weight: 20.0,
-        smooth: true,
-        smooth_n: 2,
-        loss: LossType::SpeakerFlat,
         peq_model: match params.peq_model.as_deref() {
             Some("hp-pk") => autoeq::cli::PeqModel::HpPk,
             Some("hp-pk-lp") => autoeq::cli::PeqModel::HpPkLp,
@@ -242,24 +222,7 @@ pub async fn generate_plot_filters(params: PlotFiltersParams) -> Result<serde_js
             Some("free") => autoeq::cli::PeqModel::Free,
             Some("pk") | _ => autoeq::cli::PeqModel::Pk,
         },
-        peq_model_list: false,
-        algo_list: false,
-        tolerance: 1e-3,
-        atolerance: 1e-4,
-        recombination: 0.9,
-        strategy: "currenttobest1bin".to_string(),
-        strategy_list: false,
-        adaptive_weight_f: 0.9,
-        adaptive_weight_cr: 0.9,
-        no_parallel: false,
-        parallel_threads: 0,
-        seed: None, // Random seed for deterministic optimization (None = random)
-        qa: None,   // Quality assurance mode disabled for UI (None = disabled)
-        driver1: None,
-        driver2: None,
-        driver3: None,
-        driver4: None,
-        crossover_type: "linkwitzriley4".to_string(),
+        ..AutoEQArgs::default()
     };
 
     // Generate the plot
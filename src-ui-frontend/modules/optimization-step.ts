@@ -127,6 +127,10 @@ export class OptimizationStep {
                   <option value="hp-pk-lp">HP+PK+LP - Highpass + Peaks + Lowpass</option>
                   <option value="ls-pk">LS+PK - Low Shelf + Peaks</option>
                   <option value="ls-pk-hs">LS+PK+HS - Low Shelf + Peaks + High Shelf</option>
+                  <option value="hp1-pk">HP1+PK - 1st order Highpass + Peaks</option>
+                  <option value="hp1-pk-lp1">HP1+PK+LP1 - 1st order Highpass + Peaks + 1st order Lowpass</option>
+                  <option value="ls1-pk-hs1">LS1+PK+HS1 - 1st order Low Shelf + Peaks + 1st order High Shelf</option>
+                  <option value="lt-pk">LT+PK - Linkwitz Transform + Peaks</option>
                   <option value="free-pk-free">Free+PK+Free - Flexible ends, peaks middle</option>
                   <option value="free">Free - All filters flexible</option>
                 </select>
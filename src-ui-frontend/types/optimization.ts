@@ -6,6 +6,10 @@ export type PeqModel =
   | "hp-pk-lp"
   | "ls-pk"
   | "ls-pk-hs"
+  | "hp1-pk"
+  | "hp1-pk-lp1"
+  | "ls1-pk-hs1"
+  | "lt-pk"
   | "free-pk-free"
   | "free";
 
@@ -17,6 +21,13 @@ export const PEQ_MODEL_DESCRIPTIONS: Record<PeqModel, string> = {
   "ls-pk": "First filter is low shelve, rest are peak filters",
   "ls-pk-hs":
     "First filter is low shelve, last is high shelve, rest are peak filters",
+  "hp1-pk": "First filter is a first-order highpass, rest are peak filters",
+  "hp1-pk-lp1":
+    "First filter is a first-order highpass, last is a first-order lowpass, rest are peak filters",
+  "ls1-pk-hs1":
+    "First filter is a first-order low shelve, last is a first-order high shelve, rest are peak filters",
+  "lt-pk":
+    "First filter is a Linkwitz transform (f0, Q0, bass boost), rest are peak filters",
   "free-pk-free":
     "First and last filters can be any type, middle filters are peak",
   free: "All filters can be any type",
@@ -26,6 +37,14 @@ export interface OptimizationParams {
   num_filters: number;
   curve_path?: string;
   target_path?: string;
+  target_builtin?: string; // e.g. "harman-over-ear-2018", "harman-in-ear-2019", "diffuse-field"
+  target_tilt?: number; // dB/octave around 1 kHz
+  target_bass_freq?: number;
+  target_bass_gain?: number;
+  target_treble_freq?: number;
+  target_treble_gain?: number;
+  room_gain?: number; // dB bass rise of the predicted in-room target
+  room_gain_freq?: number;
   sample_rate: number;
   max_db: number;
   min_db: number;
@@ -46,7 +65,7 @@ export interface OptimizationParams {
   spacing_weight: number;
   smooth: boolean;
   smooth_n: number;
-  loss: string; // "flat", "score", "mixed", "drivers-flat", etc.
+  loss: string; // "flat", "score", "mixed", "drivers-flat", "perceptual-flat", etc.
   peq_model?: PeqModel; // New PEQ model system
   iir_hp_pk: boolean; // Deprecated, kept for backward compatibility
   // DE-specific parameters
@@ -69,7 +88,7 @@ export interface OptimizationParams {
   driver2_path?: string;
   driver3_path?: string;
   driver4_path?: string;
-  crossover_type?: string; // "butterworth2", "linkwitzriley2", "linkwitzriley4"
+  crossover_type?: string; // e.g. "linkwitzriley4", or "lr4,lr4/bw3" per pair
 }
 
 export interface PlotData {